@@ -1,5 +1,6 @@
 //! Benchmark for the methods of the array data structure.
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::measurement::WallTime;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, Criterion};
 use hyperloglog_rs::prelude::*;
 
 const PRECISION: usize = 15;
@@ -47,6 +48,66 @@ fn bench_array(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_array);
+/// Returns the registers of a counter filled with the given number of random values.
+fn random_registers<B: Bits, R: Registers<Precision10, B>>(number_of_values: u64, seed: u64) -> R
+where
+    Precision10: ArrayRegister<B>,
+{
+    let mut counter = PlusPlus::<Precision10, B, R>::default();
+    counter.extend(iter_random_values::<u64>(
+        number_of_values,
+        None,
+        Some(seed),
+    ));
+    counter.registers().clone()
+}
+
+/// Benchmarks the union of two registers of the provided type.
+fn bench_registers_union<B: Bits, R: Registers<Precision10, B>>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    name: &str,
+) where
+    Precision10: ArrayRegister<B>,
+{
+    let left: R = random_registers(2_000, 4_567);
+    let right: R = random_registers(3_000, 8_901);
+
+    group.bench_function(format!("{name}_harmonic_sum_and_zeros"), |b| {
+        b.iter(|| black_box(&left).get_harmonic_sum_and_zeros(black_box(&right)));
+    });
+
+    group.bench_function(format!("{name}_set_greater_registers"), |b| {
+        b.iter(|| {
+            let mut merged = left.clone();
+            let mut increases = 0_u32;
+            merged.set_greater_registers(black_box(&right), |_, _| increases += 1);
+            (merged, increases)
+        });
+    });
+}
+
+fn bench_union(c: &mut Criterion) {
+    let mut group = c.benchmark_group("union");
+
+    bench_registers_union::<Bits4, <Precision10 as ArrayRegister<Bits4>>::Array>(
+        &mut group, "array4",
+    );
+    bench_registers_union::<Bits5, <Precision10 as ArrayRegister<Bits5>>::Array>(
+        &mut group, "array5",
+    );
+    bench_registers_union::<Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>(
+        &mut group, "array6",
+    );
+    bench_registers_union::<Bits8, <Precision10 as ArrayRegister<Bits8>>::Array>(
+        &mut group, "array8",
+    );
+    bench_registers_union::<Bits6, <Precision10 as ArrayRegister<Bits6>>::Packed>(
+        &mut group, "packed6",
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_array, bench_union);
 
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -53,6 +53,38 @@ fn bench_hybrid(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_hybrid);
+fn bench_hybrid_extend(c: &mut Criterion) {
+    type Counter =
+        Hybrid<PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>, u32>;
+
+    // We fill the counter up to its capacity, so that it remains in hybrid mode.
+    let capacity = Counter::default().capacity();
+    let values: Vec<u64> =
+        iter_random_values::<u64>(capacity as u64, None, Some(9_753_124)).collect();
+
+    let mut group = c.benchmark_group("hybrid_extend");
+
+    group.bench_function("insert", |b| {
+        b.iter(|| {
+            let mut counter = Counter::default();
+            for value in &values {
+                counter.insert(black_box(value));
+            }
+            counter
+        })
+    });
+
+    group.bench_function("extend", |b| {
+        b.iter(|| {
+            let mut counter = Counter::default();
+            counter.extend(black_box(&values));
+            counter
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_hybrid, bench_hybrid_extend);
 
 criterion_main!(benches);
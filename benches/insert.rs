@@ -0,0 +1,62 @@
+//! Benchmark comparing the insertion of elements with the insertion of pre-computed hashes.
+use core::hash::{Hash, Hasher};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hyperloglog_rs::prelude::*;
+
+type Counter = PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>;
+
+fn bench_insert(c: &mut Criterion) {
+    let values: Vec<u64> = iter_random_values::<u64>(100_000, None, Some(7_654)).collect();
+    let hashes: Vec<u64> = values
+        .iter()
+        .map(|value| {
+            let mut hasher = <Counter as HyperLogLog>::Hasher::default();
+            value.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("insert");
+
+    group.bench_function("insert", |b| {
+        b.iter(|| {
+            let mut counter = Counter::default();
+            for value in &values {
+                counter.insert(black_box(value));
+            }
+            counter
+        });
+    });
+
+    group.bench_function("insert_hash", |b| {
+        b.iter(|| {
+            let mut counter = Counter::default();
+            for hash in &hashes {
+                counter.insert_hash(black_box(*hash));
+            }
+            counter
+        });
+    });
+
+    group.bench_function("extend_hashes", |b| {
+        b.iter(|| {
+            let mut counter = Counter::default();
+            counter.extend_hashes(black_box(&hashes));
+            counter
+        });
+    });
+
+    group.bench_function("hybrid_extend_hashes", |b| {
+        b.iter(|| {
+            let mut counter: Hybrid<Counter> = Hybrid::default();
+            counter.extend_hashes(black_box(&hashes));
+            counter
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert);
+
+criterion_main!(benches);
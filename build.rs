@@ -197,7 +197,7 @@ fn get_sorted_biases_and_estimates(precision: usize) -> (Vec<f64>, Vec<f64>) {
     (biases, estimates)
 }
 
-#[cfg(feature = "integer_plusplus")]
+#[cfg(feature = "plusplus")]
 fn get_integer_biases_and_estimates(precision: usize) -> (Vec<i32>, Vec<u32>) {
     let (biases, estimates) = get_sorted_biases_and_estimates(precision);
 
@@ -249,7 +249,7 @@ fn get_beta(precision: usize) -> [f64; 8] {
     BETAS[precision - 4]
 }
 
-#[cfg(feature = "precomputed_beta")]
+#[cfg(feature = "beta")]
 fn beta_horner(number_of_zeros: f64, precision: usize) -> f64 {
     let beta = get_beta(precision);
     let zl = number_of_zeros.ln_1p();
@@ -260,7 +260,7 @@ fn beta_horner(number_of_zeros: f64, precision: usize) -> f64 {
     res * zl + beta[0] * number_of_zeros
 }
 
-#[cfg(feature = "precomputed_beta")]
+#[cfg(feature = "beta")]
 fn get_unrolled_beta_horner(precision: usize) -> Vec<f64> {
     (0..(1 << precision))
         .map(|x| beta_horner(x as f64, precision))
@@ -291,31 +291,32 @@ fn get_linear_count_threshold(precision: usize) -> usize {
 }
 
 #[cfg(feature = "plusplus")]
-fn write_weights(precisions: &[usize]) {
-    // For each precision, we generate the biases and estimates
-    let mut all_types = Vec::new();
-    let mut all_biases = Vec::new();
-    let mut all_estimates = Vec::new();
-
-    for precision in precisions.iter().copied() {
-        #[cfg(feature = "integer_plusplus")]
-        let (biases, estimates, biases_type, estimates_type, resolution) = {
-            let (biases, estimates) = get_integer_biases_and_estimates(precision);
-            (biases, estimates, "i32", "u32", 0)
-        };
-
-        #[cfg(not(feature = "integer_plusplus"))]
-        let (biases, estimates, biases_type, estimates_type, resolution) = {
-            let (biases, estimates) = get_sorted_biases_and_estimates(precision);
-            (biases, estimates, "f64", "f64", 3)
-        };
+/// The formatted type aliases, biases and estimates of the weights.
+#[derive(Default)]
+struct FormattedWeights {
+    types: Vec<String>,
+    biases: Vec<String>,
+    estimates: Vec<String>,
+}
 
+#[cfg(feature = "plusplus")]
+impl FormattedWeights {
+    /// Formats the biases and estimates of the provided precision, prefixing their names.
+    fn push<Bias: RedableNumber, Estimate: RedableNumber>(
+        &mut self,
+        precision: usize,
+        (type_prefix, constant_prefix): (&str, &str),
+        biases: &[Bias],
+        estimates: &[Estimate],
+        (biases_type, estimates_type): (&str, &str),
+        resolution: usize,
+    ) {
         let number_of_biases = biases.len().format_with_precision(0);
 
-        let weights_type = format!("/// Bias centroid type for precision {precision} for [`PlusPlus`]. \ntype Bias{precision} = [{biases_type}; {number_of_biases}];");
+        let weights_type = format!("/// Bias centroid type for precision {precision} for [`PlusPlus`]. \ntype {type_prefix}Bias{precision} = [{biases_type}; {number_of_biases}];");
 
         let biases = format!(
-            "/// Biases aligned with estimates centroids for precision {precision} for [`PlusPlus`]. \nconst BIAS_{precision}:  Bias{precision} = [\n{}\n];",
+            "/// Biases aligned with estimates centroids for precision {precision} for [`PlusPlus`]. \nconst {constant_prefix}BIAS_{precision}:  {type_prefix}Bias{precision} = [\n{}\n];",
             biases
                 .iter()
                 .map(|x| format!("    {},", x.format_with_precision(resolution)))
@@ -324,12 +325,10 @@ fn write_weights(precisions: &[usize]) {
         );
 
         let estimate_type =
-            format!("/// Estimates centroid type for precision {precision} for [`PlusPlus`]. \ntype Estimates{precision} = [{estimates_type}; {number_of_biases}];");
-
-        all_types.push(estimate_type);
+            format!("/// Estimates centroid type for precision {precision} for [`PlusPlus`]. \ntype {type_prefix}Estimates{precision} = [{estimates_type}; {number_of_biases}];");
 
         let estimates = format!(
-            "/// Sorted estimates centroids for precision {precision} for [`PlusPlus`]. \nconst ESTIMATES_{precision}:  Estimates{precision} = [\n{}\n];",
+            "/// Sorted estimates centroids for precision {precision} for [`PlusPlus`]. \nconst {constant_prefix}ESTIMATES_{precision}:  {type_prefix}Estimates{precision} = [\n{}\n];",
             estimates
                 .iter()
                 .map(|x| format!("    {},", x.format_with_precision(resolution)))
@@ -337,9 +336,32 @@ fn write_weights(precisions: &[usize]) {
                 .join("\n")
         );
 
-        all_types.push(weights_type);
-        all_biases.push(biases);
-        all_estimates.push(estimates);
+        self.types.push(estimate_type);
+        self.types.push(weights_type);
+        self.biases.push(biases);
+        self.estimates.push(estimates);
+    }
+}
+
+#[cfg(feature = "plusplus")]
+fn write_weights(precisions: &[usize]) {
+    // For each precision, we generate both the floating point and the integer
+    // biases and estimates, as the counters may employ either of them.
+    let mut weights = FormattedWeights::default();
+
+    for precision in precisions.iter().copied() {
+        let (biases, estimates) = get_sorted_biases_and_estimates(precision);
+        weights.push(precision, ("", ""), &biases, &estimates, ("f64", "f64"), 3);
+
+        let (biases, estimates) = get_integer_biases_and_estimates(precision);
+        weights.push(
+            precision,
+            ("Integer", "INTEGER_"),
+            &biases,
+            &estimates,
+            ("i32", "u32"),
+            0,
+        );
     }
 
     // Define the output path for the generated code
@@ -352,16 +374,16 @@ fn write_weights(precisions: &[usize]) {
         .write_all(
             format!(
                 "{}\n\n{}\n\n{}\n",
-                all_types.join("\n"),
-                all_biases.join("\n"),
-                all_estimates.join("\n"),
+                weights.types.join("\n"),
+                weights.biases.join("\n"),
+                weights.estimates.join("\n"),
             )
             .as_bytes(),
         )
         .unwrap();
 }
 
-#[cfg(feature = "zero_count_correction")]
+#[cfg(any(feature = "beta", feature = "plusplus"))]
 fn write_linear_count_zeros(precisions: &[usize]) {
     let linear_count_zeros = precisions
         .iter()
@@ -402,13 +424,7 @@ fn write_alphas(precisions: &[usize]) {
         .unwrap();
 }
 
-#[cfg(all(
-    not(feature = "std_ln"),
-    any(
-        all(feature = "beta", not(feature = "precomputed_beta")),
-        feature = "plusplus",
-    )
-))]
+#[cfg(any(feature = "beta", feature = "plusplus"))]
 fn write_ln_values(precisions: &[usize]) {
     // Since the ln values are needed up to the maximal number of registers, we
     // determine what is the largest number of registers we need to generate the
@@ -439,7 +455,7 @@ fn write_ln_values(precisions: &[usize]) {
     ln_values_file.write_all(ln_values.as_bytes()).unwrap();
 }
 
-#[cfg(all(feature = "beta", not(feature = "precomputed_beta")))]
+#[cfg(feature = "beta")]
 fn write_beta(precisions: &[usize]) {
     // For each precision, we generate the biases and estimates
     let mut all_betas = Vec::new();
@@ -470,7 +486,7 @@ fn write_beta(precisions: &[usize]) {
         .unwrap();
 }
 
-#[cfg(feature = "precomputed_beta")]
+#[cfg(feature = "beta")]
 fn write_precomputed_beta(precisions: &[usize]) {
     // For each precision, we generate the biases and estimates
     let mut all_beta_horner = Vec::new();
@@ -532,22 +548,16 @@ fn main() {
     write_alphas(&precisions);
     write_number_of_registers(&precisions);
 
-    #[cfg(all(
-        not(feature = "std_ln"),
-        any(
-            all(feature = "beta", not(feature = "precomputed_beta")),
-            feature = "plusplus"
-        )
-    ))]
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
     write_ln_values(&precisions);
 
-    #[cfg(all(feature = "beta", not(feature = "precomputed_beta")))]
+    #[cfg(feature = "beta")]
     write_beta(&precisions);
 
-    #[cfg(feature = "precomputed_beta")]
+    #[cfg(feature = "beta")]
     write_precomputed_beta(&precisions);
 
-    #[cfg(feature = "plusplus")]
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
     write_linear_count_zeros(&precisions);
 
     #[cfg(feature = "plusplus")]
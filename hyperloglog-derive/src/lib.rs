@@ -780,6 +780,11 @@ pub fn test_estimator(_attr: TokenStream, item: TokenStream) -> TokenStream {
                     if fn_name.to_string().contains("beta") {
                         feature_constraints.push(quote! { #[cfg(feature = "beta")] });
                     }
+
+                    // If in the name of the function there appears the word improved, we add the feature improved
+                    if fn_name.to_string().contains("improved") {
+                        feature_constraints.push(quote! { #[cfg(feature = "improved")] });
+                    }
                     if packed {
                         quote! {
                             #[test]
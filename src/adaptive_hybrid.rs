@@ -0,0 +1,342 @@
+//! Submodule providing the adaptive hybrid counter, which narrows the composite hashes it keeps explicit as it fills.
+
+use crate::prelude::*;
+use core::hash::Hash;
+
+/// Trait for a hybrid counter whose composite hashes can be re-encoded into narrower words.
+pub trait ShrinkableHybrid<
+    CH: CompositeHash<Self::Precision, Self::Bits>,
+    NCH: CompositeHash<Self::Precision, Self::Bits>,
+>: Hybridazable<CH> + Hybridazable<NCH>
+{
+    /// Re-encodes the composite hashes stored as `CH` words into the narrower `NCH` words.
+    ///
+    /// # Implementative details
+    /// The counter must be in hybrid mode. The narrower words keep the index and the
+    /// register value of each hash, while the bits of padding that do not fit anymore
+    /// are dropped: the re-encoded hashes are therefore sorted again and deduplicated,
+    /// as hashes that only differed in the dropped bits now collide.
+    fn shrink_composite_hashes(&mut self);
+}
+
+/// Trait for a counter that can be employed in the [`AdaptiveHybrid`] counter, i.e. whose
+/// composite hashes can be narrowed from `u64` words down to `u24` words.
+pub trait AdaptiveHybridazable:
+    ShrinkableHybrid<u64, u56>
+    + ShrinkableHybrid<u56, u48>
+    + ShrinkableHybrid<u48, u40>
+    + ShrinkableHybrid<u40, u32>
+    + ShrinkableHybrid<u32, u24>
+where
+    u24: CompositeHash<Self::Precision, Self::Bits>,
+{
+}
+
+impl<H> AdaptiveHybridazable for H
+where
+    H: ShrinkableHybrid<u64, u56>
+        + ShrinkableHybrid<u56, u48>
+        + ShrinkableHybrid<u48, u40>
+        + ShrinkableHybrid<u40, u32>
+        + ShrinkableHybrid<u32, u24>,
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+}
+
+/// The number of bits of the widest composite hashes, employed by an empty counter.
+const WIDEST_COMPOSITE_HASH_BITS: u8 = 64;
+
+/// The number of bits of the narrowest composite hashes, after which the counter is dehybridized.
+const NARROWEST_COMPOSITE_HASH_BITS: u8 = 24;
+
+/// Evaluates the provided expression with the composite hash type of the given number of bits.
+macro_rules! with_composite_hash {
+    ($bits:expr, $composite_hash:ident => $body:expr) => {
+        match $bits {
+            64 => {
+                type $composite_hash = u64;
+                $body
+            }
+            56 => {
+                type $composite_hash = u56;
+                $body
+            }
+            48 => {
+                type $composite_hash = u48;
+                $body
+            }
+            40 => {
+                type $composite_hash = u40;
+                $body
+            }
+            32 => {
+                type $composite_hash = u32;
+                $body
+            }
+            24 => {
+                type $composite_hash = u24;
+                $body
+            }
+            bits => unreachable!("Composite hashes of {bits} bits are not supported."),
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A struct representing the adaptive hybrid for approximate set cardinality estimation,
+/// where the hash values are kept explicit in progressively narrower words up until
+/// even the narrowest ones do not fit into the registers anymore.
+///
+/// # Implementative details
+/// A [`Hybrid`] counter fixes the width of its composite hashes at the type level,
+/// trading the collision rate against the capacity once and for all. This counter
+/// instead starts with `u64` composite hashes, which practically never collide, and
+/// when the list of hashes is full it re-encodes it into the next narrower words,
+/// going through `u56`, `u48`, `u40`, `u32` and finally `u24`. Only when the list
+/// of `u24` composite hashes is full, the counter switches to the registers. Since
+/// the narrowest words are `u24`, the counter requires the precision and the number
+/// of bits to fit in them, which excludes the largest precisions with most bits.
+pub struct AdaptiveHybrid<H> {
+    /// The inner counter.
+    inner: H,
+    /// The number of bits of the composite hashes currently employed.
+    composite_hash_bits: u8,
+}
+
+impl<H: Default + AdaptiveHybridazable> Default for AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            inner: Hybridazable::<u64>::new_hybrid(),
+            composite_hash_bits: WIDEST_COMPOSITE_HASH_BITS,
+        }
+    }
+}
+
+impl<H: AdaptiveHybridazable> AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    #[must_use]
+    /// Returns a new empty counter in hybrid mode, hashing its elements with the hashers
+    /// created by the provided builder.
+    ///
+    /// # Arguments
+    /// * `hasher_builder` - The builder of the hashers of the counter.
+    pub fn with_hasher_builder(hasher_builder: H::HasherBuilder) -> Self {
+        let mut inner = H::with_hasher_builder(hasher_builder);
+        Hybridazable::<u64>::clear_words(&mut inner);
+        Self {
+            inner,
+            composite_hash_bits: WIDEST_COMPOSITE_HASH_BITS,
+        }
+    }
+
+    #[inline]
+    /// Returns whether the counter is in hybrid mode.
+    pub fn is_hybrid(&self) -> bool {
+        Hybridazable::<u64>::is_hybrid(&self.inner)
+    }
+
+    #[inline]
+    /// Returns the maximum number of hashes that can be stored in the counter
+    /// with the composite hashes currently employed.
+    pub fn capacity(&self) -> usize {
+        with_composite_hash!(self.composite_hash_bits, CH => Hybridazable::<CH>::capacity(&self.inner))
+    }
+
+    #[inline]
+    /// Returns the number of bits of the composite hashes currently employed.
+    pub fn composite_hash_bits(&self) -> u8 {
+        self.composite_hash_bits
+    }
+
+    #[inline]
+    /// Returns the number of hashes stored in the counter in hybrid mode.
+    fn number_of_hashes(&self) -> usize {
+        Hybridazable::<u64>::number_of_hashes(&self.inner)
+    }
+
+    /// Re-encodes the composite hashes into the next narrower words.
+    fn shrink(&mut self) {
+        debug_assert!(self.is_hybrid(), "The counter is not in hybrid mode.");
+        match self.composite_hash_bits {
+            64 => ShrinkableHybrid::<u64, u56>::shrink_composite_hashes(&mut self.inner),
+            56 => ShrinkableHybrid::<u56, u48>::shrink_composite_hashes(&mut self.inner),
+            48 => ShrinkableHybrid::<u48, u40>::shrink_composite_hashes(&mut self.inner),
+            40 => ShrinkableHybrid::<u40, u32>::shrink_composite_hashes(&mut self.inner),
+            32 => ShrinkableHybrid::<u32, u24>::shrink_composite_hashes(&mut self.inner),
+            bits => unreachable!("Composite hashes of {bits} bits cannot be narrowed."),
+        }
+        self.composite_hash_bits -= 8;
+    }
+
+    /// Re-encodes the composite hashes into narrower words up until there is room
+    /// for a new hash, or the narrowest words are reached.
+    fn make_room(&mut self) {
+        while self.composite_hash_bits > NARROWEST_COMPOSITE_HASH_BITS
+            && self.number_of_hashes() == self.capacity()
+        {
+            self.shrink();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: Named> Named for AdaptiveHybrid<H> {
+    #[inline]
+    fn name(&self) -> String {
+        format!("AH-{}", self.inner.name())
+    }
+}
+
+impl<H: SetProperties + AdaptiveHybridazable> SetProperties for AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    fn is_empty(&self) -> bool {
+        if self.is_hybrid() {
+            self.number_of_hashes() == 0
+        } else {
+            self.inner.is_empty()
+        }
+    }
+
+    #[inline]
+    fn is_full(&self) -> bool {
+        if self.is_hybrid() {
+            self.composite_hash_bits == NARROWEST_COMPOSITE_HASH_BITS
+                && self.number_of_hashes() == self.capacity()
+        } else {
+            self.inner.is_full()
+        }
+    }
+}
+
+impl<T: Hash, H: ApproximatedSet<T> + AdaptiveHybridazable> ApproximatedSet<T> for AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    fn may_contain(&self, element: &T) -> bool {
+        if self.is_hybrid() {
+            with_composite_hash!(self.composite_hash_bits, CH => Hybridazable::<CH>::contains(&self.inner, element))
+        } else {
+            self.inner.may_contain(element)
+        }
+    }
+}
+
+impl<H: MutableSet + AdaptiveHybridazable> MutableSet for AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    fn clear(&mut self) {
+        Hybridazable::<u64>::clear_words(&mut self.inner);
+        self.composite_hash_bits = WIDEST_COMPOSITE_HASH_BITS;
+    }
+}
+
+impl<T: Hash, H: ExtendableApproximatedSet<T> + AdaptiveHybridazable> ExtendableApproximatedSet<T>
+    for AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    fn insert(&mut self, element: &T) -> bool {
+        if self.is_hybrid() {
+            // When the list of hashes is full, we narrow the composite hashes before
+            // inserting the new one: once the narrowest ones are full as well, the
+            // insertion in hybrid mode switches the counter to the registers.
+            self.make_room();
+            with_composite_hash!(self.composite_hash_bits, CH => Hybridazable::<CH>::hybrid_insert(&mut self.inner, element))
+        } else {
+            self.inner.insert(element)
+        }
+    }
+}
+
+impl<H: Clone + Correction + Estimator<f64> + AdaptiveHybridazable> Estimator<f64>
+    for AdaptiveHybrid<H>
+where
+    u24: CompositeHash<H::Precision, H::Bits>,
+{
+    #[inline]
+    fn estimate_cardinality(&self) -> f64 {
+        if self.is_hybrid() {
+            // As for the hybrid counter, the number of hashes fits in an u32, and it is
+            // corrected for the collisions of the composite hashes currently employed.
+            let number_of_hashes = u32::try_from(self.number_of_hashes()).unwrap();
+            with_composite_hash!(self.composite_hash_bits, CH => <CH as CompositeHash<H::Precision, H::Bits>>::collision_corrected_cardinality(number_of_hashes))
+        } else {
+            self.inner.estimate_cardinality()
+        }
+    }
+
+    #[inline]
+    fn is_union_estimate_non_deterministic(&self, other: &Self) -> bool {
+        !(self.is_hybrid() && other.is_hybrid())
+            && self.inner.is_union_estimate_non_deterministic(&other.inner)
+    }
+
+    #[inline]
+    fn estimate_union_cardinality_with_cardinalities(
+        &self,
+        other: &Self,
+        self_cardinality: f64,
+        other_cardinality: f64,
+    ) -> f64 {
+        match (self.is_hybrid(), other.is_hybrid()) {
+            (true, true) => {
+                // The sorted lists of hashes can only be compared when they employ the
+                // same words, so we narrow a copy of the counter with the wider ones.
+                if self.composite_hash_bits != other.composite_hash_bits {
+                    let (mut wider, narrower) =
+                        if self.composite_hash_bits > other.composite_hash_bits {
+                            (self.clone(), other)
+                        } else {
+                            (other.clone(), self)
+                        };
+                    while wider.composite_hash_bits > narrower.composite_hash_bits {
+                        wider.shrink();
+                    }
+                    return wider.estimate_union_cardinality_with_cardinalities(
+                        narrower,
+                        self_cardinality,
+                        other_cardinality,
+                    );
+                }
+                with_composite_hash!(self.composite_hash_bits, CH => <CH as CompositeHash<H::Precision, H::Bits>>::collision_corrected_cardinality(unique_count_from_sorted_iterators(
+                    Hybridazable::<CH>::iter_sorted_hashes(&self.inner),
+                    Hybridazable::<CH>::iter_sorted_hashes(&other.inner),
+                )))
+            }
+            (true, false) => {
+                with_composite_hash!(self.composite_hash_bits, CH => union_estimation_from_sorted_iterator_and_counter(
+                    Hybridazable::<CH>::iter_sorted_hashes(&self.inner)
+                        .map(<CH as CompositeHash<H::Precision, H::Bits>>::decode),
+                    &other.inner,
+                    self_cardinality,
+                    other_cardinality,
+                ))
+            }
+            (false, true) => other.estimate_union_cardinality_with_cardinalities(
+                self,
+                self_cardinality,
+                other_cardinality,
+            ),
+            (false, false) => self.inner.estimate_union_cardinality_with_cardinalities(
+                &other.inner,
+                self_cardinality,
+                other_cardinality,
+            ),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Submodule implementing a `HyperLogLog++` counter that may be updated concurrently.
+//!
+//! The [`AtomicPlusPlus`] counter stores its registers in [`AtomicRegisters`], so that many
+//! threads may insert elements through a shared reference, without sharding the counter and
+//! merging the shards afterwards. Since maintaining the harmonic sum and the number of zero
+//! registers would require synchronizing the threads, the counter does not provide estimates
+//! directly: a [`PlusPlus`] counter is built from a snapshot of its registers instead.
+use crate::prelude::*;
+use core::fmt::{Debug, Formatter};
+use core::hash::Hash;
+use core::marker::PhantomData;
+
+/// A `HyperLogLog++` counter whose registers may be updated concurrently.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// type Registers = <Precision8 as ArrayRegister<Bits6>>::Array;
+/// type Atomic = <Precision8 as ArrayRegister<Bits6>>::Atomic;
+///
+/// let counter = AtomicPlusPlus::<Precision8, Bits6, Atomic>::default();
+///
+/// std::thread::scope(|scope| {
+///     for thread in 0..4_u64 {
+///         let counter = &counter;
+///         scope.spawn(move || {
+///             for value in 0..250_u64 {
+///                 counter.insert(&(thread * 250 + value));
+///             }
+///         });
+///     }
+/// });
+///
+/// let mut sequential = PlusPlus::<Precision8, Bits6, Registers>::default();
+/// sequential.extend(0..1_000_u64);
+///
+/// assert_eq!(counter.snapshot(), sequential);
+/// ```
+pub struct AtomicPlusPlus<
+    P: Precision,
+    B: Bits,
+    R: AtomicRegisters<P, B>,
+    Hasher: HasherType = twox_hash::XxHash64,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+> {
+    /// The atomic registers of the counter.
+    registers: R,
+    /// The builder of the hashers of the counter.
+    hasher_builder: Builder,
+    /// Phantom data to ensure the type parameters are used.
+    _phantom: PhantomData<(P, B, Hasher)>,
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: AtomicRegisters<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Debug for AtomicPlusPlus<P, B, R, Hasher, Builder>
+{
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        formatter
+            .debug_struct("AtomicPlusPlus")
+            .field("registers", &self.registers)
+            .field("hasher_builder", &self.hasher_builder)
+            .finish()
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: AtomicRegisters<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Default for AtomicPlusPlus<P, B, R, Hasher, Builder>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher_builder(Builder::default())
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: AtomicRegisters<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > From<&PlusPlus<P, B, R::Registers, Hasher, Builder>>
+    for AtomicPlusPlus<P, B, R, Hasher, Builder>
+{
+    #[inline]
+    fn from(counter: &PlusPlus<P, B, R::Registers, Hasher, Builder>) -> Self {
+        Self {
+            registers: R::from_registers(counter.registers()),
+            hasher_builder: counter.hasher_builder().clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: AtomicRegisters<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > AtomicPlusPlus<P, B, R, Hasher, Builder>
+{
+    #[inline]
+    #[must_use]
+    /// Returns a new empty counter hashing its elements with the hashers created by the
+    /// provided builder.
+    ///
+    /// # Arguments
+    /// * `hasher_builder` - The builder of the hashers of the counter.
+    pub fn with_hasher_builder(hasher_builder: Builder) -> Self {
+        Self {
+            registers: R::default(),
+            hasher_builder,
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    /// Inserts an element into the counter, returning whether any register was increased.
+    pub fn insert<T: Hash>(&self, element: &T) -> bool {
+        let (register_value, index) = PlusPlus::<P, B, R::Registers, Hasher, Builder>::split_hash(
+            self.hasher_builder.hash_one(element),
+        );
+        let (old_register_value, larger_register_value) =
+            self.registers.set_greater(index, register_value);
+        old_register_value != larger_register_value
+    }
+
+    #[inline]
+    /// Inserts a pre-computed hash into the counter, returning whether any register was increased.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the element, which must be computed with the hasher of the counter
+    ///   for the counter to be comparable with the ones built by inserting the elements.
+    pub fn insert_hash(&self, hash: u64) -> bool {
+        let (register_value, index) =
+            PlusPlus::<P, B, R::Registers, Hasher, Builder>::split_hash(hash);
+        let (old_register_value, larger_register_value) =
+            self.registers.set_greater(index, register_value);
+        old_register_value != larger_register_value
+    }
+
+    #[inline]
+    /// Returns the value of the register at the given index.
+    pub fn get_register(&self, index: P::NumberOfRegisters) -> u8 {
+        self.registers.get_register(index)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns a [`PlusPlus`] counter built from a snapshot of the registers.
+    ///
+    /// # Implementative details
+    /// The snapshot includes all the insertions performed by the threads that have been joined,
+    /// while it may include only part of the ones being performed concurrently.
+    pub fn snapshot(&self) -> PlusPlus<P, B, R::Registers, Hasher, Builder> {
+        PlusPlus::from_registers_with_hasher_builder(
+            self.registers.snapshot(),
+            self.hasher_builder.clone(),
+        )
+    }
+
+    #[inline]
+    /// Clears the registers of the counter.
+    pub fn clear(&self) {
+        self.registers.clear_registers();
+    }
+}
@@ -1,5 +1,6 @@
 //! Implementation of the basic struct for [`HyperLogLog`] counter.
 
+use crate::binary_format::copy_words;
 use crate::prelude::*;
 use crate::utils::{HasherType, One, PositiveInteger, VariableWords, Zero};
 use core::fmt::Debug;
@@ -12,63 +13,106 @@ use core::ops::{BitOr, BitOrAssign};
 #[derive(Clone, Copy)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 /// A basic counter data structure for HyperLogLog-like counters.
-pub(crate) struct BasicLogLog<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> {
+pub(crate) struct BasicLogLog<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    Hasher: HasherType,
+    Builder: HasherBuilder<Hasher = Hasher>,
+> {
     /// The registers of the counter.
     registers: R,
     /// The number of registers with zero values.
     number_of_zero_registers: P::NumberOfRegisters,
     /// The harmonic sum of the registers, i.e. the sum of 2^(-register_value) for all registers.
     harmonic_sum: f64,
+    #[cfg(feature = "register_histogram")]
+    /// The histogram of the values of the registers, maintained as the registers are updated.
+    histogram: B::Histogram,
+    /// The builder of the hashers of the counter.
+    hasher_builder: Builder,
     /// Phantom data to ensure the type parameters are used.
     _phantom: PhantomData<(P, B, Hasher)>,
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Debug
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Debug for BasicLogLog<P, B, R, Hasher, Builder>
 {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
-        formatter
-            .debug_struct("BasicLogLog")
+        let mut debug = formatter.debug_struct("BasicLogLog");
+        debug
             .field("registers", &self.registers)
             .field("number_of_zero_registers", &self.number_of_zero_registers)
-            .field("harmonic_sum", &self.harmonic_sum)
-            .finish()
+            .field("harmonic_sum", &self.harmonic_sum);
+        #[cfg(feature = "register_histogram")]
+        debug.field("histogram", &self.histogram);
+        debug.field("hasher_builder", &self.hasher_builder);
+        debug.finish()
     }
 }
 
 /// Implementation of partial equality for [`HyperLogLog`] so as to compare two [`HyperLogLog`] instances
-/// ignoring the harmonic sum.
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> PartialEq
-    for BasicLogLog<P, B, R, Hasher>
+/// ignoring the harmonic sum, while counters with different hashers are never equal.
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > PartialEq for BasicLogLog<P, B, R, Hasher, Builder>
 {
     fn eq(&self, other: &Self) -> bool {
-        self.registers == other.registers
+        self.registers == other.registers && self.hasher_builder == other.hasher_builder
     }
 }
 
 /// Implementation of equality for [`HyperLogLog`] so as to compare two [`HyperLogLog`] instances
 /// ignoring the harmonic sum.
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Eq
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Eq for BasicLogLog<P, B, R, Hasher, Builder>
 {
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> BasicLogLog<P, B, R, Hasher> {
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > BasicLogLog<P, B, R, Hasher, Builder>
+{
     /// Create a new [`HyperLogLog`] counter.
-    fn new() -> Self {
+    fn new(hasher_builder: Builder) -> Self {
         Self {
             registers: R::default(),
             number_of_zero_registers: P::NUMBER_OF_REGISTERS,
             harmonic_sum: f64::integer_exp2(P::EXPONENT),
+            #[cfg(feature = "register_histogram")]
+            histogram: Self::empty_histogram(),
+            hasher_builder,
             _phantom: PhantomData,
         }
     }
 
-    /// Computes the hash of a value and splits it into a register value and an index.
-    fn compute_hash<T: Hash>(value: T) -> u64 {
-        let mut hasher = Hasher::default();
-        value.hash(&mut hasher);
-        hasher.finish()
+    #[cfg(feature = "register_histogram")]
+    /// Returns the histogram of the registers of an empty counter.
+    fn empty_histogram() -> B::Histogram {
+        B::Histogram::empty(u32::try_from(P::NUMBER_OF_REGISTERS.to_usize()).unwrap())
+    }
+
+    /// Computes the hash of a value with the hashers of the counter.
+    fn compute_hash<T: Hash>(&self, value: T) -> u64 {
+        self.hasher_builder.hash_one(value)
     }
 
     /// Splits a hash into a register value and an index.
@@ -95,26 +139,126 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> BasicLogLog<
         self.harmonic_sum += f64::integer_exp2_minus(larger_register_value)
             - f64::integer_exp2_minus(old_register_value);
 
+        #[cfg(feature = "register_histogram")]
+        self.histogram
+            .move_register(old_register_value, larger_register_value);
+
         old_register_value != new_register_value
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Default
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType + HasherId,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > BasicLogLog<P, B, R, Hasher, Builder>
+{
+    /// Returns the header describing the counter in the binary format.
+    pub(crate) fn binary_header(&self, estimator: u8) -> BinaryHeader {
+        let hybrid = self.harmonic_sum < f64::ZERO;
+        BinaryHeader {
+            estimator,
+            exponent: P::EXPONENT,
+            bits: B::NUMBER_OF_BITS,
+            packed: R::PACKED,
+            hybrid,
+            composite_hash_bits: 0,
+            hasher: Hasher::HASHER_ID,
+            number_of_hashes: if hybrid {
+                u32::try_from(self.number_of_zero_registers.to_usize()).unwrap()
+            } else {
+                0
+            },
+            seed: self.hasher_builder.seed(),
+            hybrid_allowed: false,
+        }
+    }
+
+    /// Builds the counter from its header and the little-endian bytes of its words.
+    pub(crate) fn from_binary(
+        header: &BinaryHeader,
+        words: &[u8],
+        estimator: u8,
+    ) -> Result<Self, Error> {
+        header.check(
+            estimator,
+            P::EXPONENT,
+            B::NUMBER_OF_BITS,
+            R::PACKED,
+            Hasher::HASHER_ID,
+        )?;
+        if header.composite_hash_bits != 0 {
+            return Err(Error::CompositeHashMismatch {
+                expected: 0,
+                found: header.composite_hash_bits,
+            });
+        }
+        if header.hybrid && !header.hybrid_allowed {
+            return Err(Error::UnsupportedHybridMode);
+        }
+
+        let hasher_builder = Builder::from_seed(header.seed).ok_or(Error::SeedMismatch {
+            expected_seeded: header.seed.is_none(),
+        })?;
+
+        let mut registers = R::default();
+        copy_words(words, registers.words_mut())?;
+
+        if !header.hybrid {
+            let mut counter = Self::try_from_registers(registers)?;
+            counter.hasher_builder = hasher_builder;
+            return Ok(counter);
+        }
+
+        // The number of hashes is checked against the capacity by the hybrid counter, and
+        // it can never exceed the number of registers.
+        Ok(Self {
+            registers,
+            number_of_zero_registers: P::NumberOfRegisters::try_from_u64(u64::from(
+                header.number_of_hashes,
+            ))
+            .map_err(|_| Error::InvalidNumberOfHashes {
+                capacity: P::NUMBER_OF_REGISTERS.to_usize(),
+                found: header.number_of_hashes as usize,
+            })?,
+            harmonic_sum: f64::NEG_INFINITY,
+            #[cfg(feature = "register_histogram")]
+            histogram: B::Histogram::ZERO,
+            hasher_builder,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Default for BasicLogLog<P, B, R, Hasher, Builder>
 {
     /// Returns a new [`HyperLogLog`] instance with default configuration settings.
     fn default() -> Self {
-        Self::new()
+        Self::new(Builder::default())
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > HyperLogLog for BasicLogLog<P, B, R, Hasher, Builder>
 {
     type Registers = R;
     type Precision = P;
     type Bits = B;
     type Hasher = Hasher;
+    type HasherBuilder = Builder;
 
     /// Returns the number of registers with zero values.
     fn get_number_of_zero_registers(&self) -> P::NumberOfRegisters {
@@ -131,11 +275,20 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
         self.harmonic_sum
     }
 
+    fn hasher_builder(&self) -> &Builder {
+        &self.hasher_builder
+    }
+
     fn get_register(&self, index: P::NumberOfRegisters) -> u8 {
         self.registers.get_register(index)
     }
 
-    fn from_registers(registers: R) -> Self {
+    #[cfg(feature = "register_histogram")]
+    fn register_histogram(&self) -> B::Histogram {
+        self.histogram
+    }
+
+    fn from_registers_with_hasher_builder(registers: R, hasher_builder: Builder) -> Self {
         let mut number_of_zero_registers = P::NumberOfRegisters::ZERO;
         let mut harmonic_sum = f64::ZERO;
 
@@ -145,51 +298,68 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
         }
 
         Self {
+            #[cfg(feature = "register_histogram")]
+            histogram: registers.register_histogram(),
             registers,
             number_of_zero_registers,
             harmonic_sum,
+            hasher_builder,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, A: Hash, Hasher: HasherType> FromIterator<A>
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        A: Hash,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > FromIterator<A> for BasicLogLog<P, B, R, Hasher, Builder>
 {
     fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
-        let mut hll = Self::new();
+        let mut hll = Self::default();
         hll.extend(iter);
         hll
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType, Rhs: HyperLogLog>
-    BitOrAssign<Rhs> for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > BitOrAssign for BasicLogLog<P, B, R, Hasher, Builder>
 {
-    fn bitor_assign(&mut self, rhs: Rhs) {
-        let mut rhs_registers = rhs.registers().iter_registers();
-
-        self.registers.apply_to_registers(|old_register| {
-            let rhs_register: u8 = rhs_registers.next().unwrap();
-
-            if rhs_register > old_register {
+    fn bitor_assign(&mut self, rhs: Self) {
+        debug_assert!(
+            self.hasher_builder == rhs.hasher_builder,
+            "The counters must hash their elements with the same hashers."
+        );
+        self.registers
+            .set_greater_registers(&rhs.registers, |old_register, rhs_register| {
                 self.harmonic_sum +=
                     f64::integer_exp2_minus(rhs_register) - f64::integer_exp2_minus(old_register);
                 self.number_of_zero_registers -= P::NumberOfRegisters::from(old_register == 0);
-                rhs_register
-            } else {
-                old_register
-            }
-        });
+                #[cfg(feature = "register_histogram")]
+                self.histogram.move_register(old_register, rhs_register);
+            });
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Rhs: HyperLogLog, Hasher: HasherType> BitOr<Rhs>
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > BitOr for BasicLogLog<P, B, R, Hasher, Builder>
 {
     type Output = Self;
 
-    fn bitor(mut self, rhs: Rhs) -> Self {
+    fn bitor(mut self, rhs: Self) -> Self {
         self.bitor_assign(rhs);
         self
     }
@@ -199,9 +369,10 @@ impl<
         P: Precision,
         B: Bits,
         Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
         R: Registers<P, B> + VariableWords<CH>,
         CH: CompositeHash<P, B>,
-    > Hybridazable<CH> for BasicLogLog<P, B, R, Hasher>
+    > Hybridazable<CH> for BasicLogLog<P, B, R, Hasher, Builder>
 {
     type IterSortedHashes<'words> = <R as VariableWords<CH>>::Words<'words> where Self: 'words, CH: 'words;
 
@@ -225,6 +396,10 @@ impl<
             let number_of_hashes = self.number_of_hashes();
             self.number_of_zero_registers = P::NUMBER_OF_REGISTERS;
             self.harmonic_sum = f64::integer_exp2(P::EXPONENT);
+            #[cfg(feature = "register_histogram")]
+            {
+                self.histogram = Self::empty_histogram();
+            }
             let registers = self.registers.clone();
             self.registers = R::default();
             for composite_hash in registers.iter_variable_words(number_of_hashes) {
@@ -244,6 +419,10 @@ impl<
         self.registers.clear_registers();
         self.number_of_zero_registers = P::NumberOfRegisters::ZERO;
         self.harmonic_sum = f64::NEG_INFINITY;
+        #[cfg(feature = "register_histogram")]
+        {
+            self.histogram = B::Histogram::ZERO;
+        }
     }
 
     fn iter_sorted_hashes(&self) -> Self::IterSortedHashes<'_> {
@@ -258,7 +437,7 @@ impl<
             self.registers.number_of_words()
         );
 
-        let hash = Self::compute_hash(element);
+        let hash = self.compute_hash(element);
         let (register, index) = Self::split_hash(hash);
 
         self.registers
@@ -307,50 +486,178 @@ impl<
                 self.dehybridize();
                 self.insert(element)
             } else {
-                let hash = Self::compute_hash(element);
+                let hash = self.compute_hash(element);
                 let (register, index) = Self::split_hash(hash);
-                let composite_hash = CH::encode(register, index, hash);
-
-                debug_assert!(composite_hash != CH::Word::ZERO, "Composite hash is zero.");
-
-                if self
-                    .registers
-                    .sorted_insert_with_len(composite_hash, self.number_of_hashes())
-                {
-                    debug_assert!(
-                        self.number_of_zero_registers <= P::NUMBER_OF_REGISTERS,
-                        "Number of zero registers ({}) is greater than the number of registers ({})",
-                        self.number_of_zero_registers,
-                        P::NUMBER_OF_REGISTERS
-                    );
-                    self.number_of_zero_registers += P::NumberOfRegisters::ONE;
-                    true
-                } else {
-                    false
-                }
+                self.insert_composite_hash(CH::encode(register, index, hash))
             }
         } else {
             self.insert(element)
         }
     }
+
+    fn insert_composite_hash(&mut self, composite_hash: CH::Word) -> bool {
+        debug_assert!(self.is_hybrid(), "The counter is not in hybrid mode.");
+        debug_assert!(
+            self.number_of_hashes() < self.capacity(),
+            "The counter in hybrid mode is full."
+        );
+        debug_assert!(composite_hash != CH::Word::ZERO, "Composite hash is zero.");
+
+        if self
+            .registers
+            .sorted_insert_with_len(composite_hash, self.number_of_hashes())
+        {
+            debug_assert!(
+                self.number_of_zero_registers <= P::NUMBER_OF_REGISTERS,
+                "Number of zero registers ({}) is greater than the number of registers ({})",
+                self.number_of_zero_registers,
+                P::NUMBER_OF_REGISTERS
+            );
+            self.number_of_zero_registers += P::NumberOfRegisters::ONE;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn insert_sorted_composite_hashes(&mut self, composite_hashes: &[CH::Word]) -> bool {
+        debug_assert!(self.is_hybrid(), "The counter is not in hybrid mode.");
+
+        match self
+            .registers
+            .sorted_merge_with_len(composite_hashes, self.number_of_hashes())
+        {
+            Some(number_of_hashes) => {
+                // The number of hashes is at most the capacity, which is smaller
+                // than the number of registers.
+                self.number_of_zero_registers =
+                    P::NumberOfRegisters::try_from_u64(number_of_hashes as u64).unwrap();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        R: Registers<P, B> + VariableWords<CH> + VariableWords<NCH>,
+        CH: CompositeHash<P, B>,
+        NCH: CompositeHash<P, B>,
+    > ShrinkableHybrid<CH, NCH> for BasicLogLog<P, B, R, Hasher, Builder>
+{
+    fn shrink_composite_hashes(&mut self) {
+        debug_assert!(
+            self.harmonic_sum < f64::ZERO,
+            "The counter is not in hybrid mode."
+        );
+        debug_assert!(
+            NCH::NUMBER_OF_BITS < CH::NUMBER_OF_BITS,
+            "The composite hashes can only be re-encoded into narrower words."
+        );
+
+        let number_of_hashes = self.get_number_of_zero_registers().to_usize();
+        // Each narrower word ends before the start of the next wider word, so that
+        // we can re-encode the words in place, from the first to the last one.
+        for position in 0..number_of_hashes {
+            let composite_hash = VariableWords::<CH>::variable_word(&self.registers, position);
+            let (register, index) = CH::decode(composite_hash);
+            let padding: u64 = composite_hash.into() & CH::PADDING_MASK;
+            VariableWords::<NCH>::set_variable_word(
+                &mut self.registers,
+                position,
+                NCH::encode(register, index, padding << P::EXPONENT),
+            );
+        }
+        let number_of_hashes =
+            VariableWords::<NCH>::sort_dedup_with_len(&mut self.registers, number_of_hashes);
+        self.number_of_zero_registers =
+            P::NumberOfRegisters::try_from_u64(number_of_hashes as u64).unwrap();
+    }
 }
 
-impl<P: Precision, B: Bits, Hasher: HasherType, R: Registers<P, B>> MutableSet
-    for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        R: Registers<P, B>,
+    > MutableSet for BasicLogLog<P, B, R, Hasher, Builder>
 {
     fn clear(&mut self) {
         self.registers.clear_registers();
         self.number_of_zero_registers = P::NUMBER_OF_REGISTERS;
         self.harmonic_sum = f64::integer_exp2(P::EXPONENT);
+        #[cfg(feature = "register_histogram")]
+        {
+            self.histogram = Self::empty_histogram();
+        }
     }
 }
 
-impl<P: Precision, B: Bits, Hasher: HasherType, R: Registers<P, B>, T: Hash>
-    ExtendableApproximatedSet<T> for BasicLogLog<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        R: Registers<P, B>,
+        T: Hash,
+    > ExtendableApproximatedSet<T> for BasicLogLog<P, B, R, Hasher, Builder>
 {
     fn insert(&mut self, element: &T) -> bool {
-        let (new_register_value, index) = Self::hash_and_index::<T>(element);
+        let (new_register_value, index) = self.hash_and_index(element);
+
+        self.insert_register_value_and_index(new_register_value, index)
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        R: Registers<P, B>,
+    > ExtendableHashes for BasicLogLog<P, B, R, Hasher, Builder>
+{
+    fn insert_hash(&mut self, hash: u64) -> bool {
+        let (new_register_value, index) = Self::split_hash(hash);
 
         self.insert_register_value_and_index(new_register_value, index)
     }
+
+    fn extend_hashes(&mut self, hashes: &[u64]) {
+        // The registers of a counter in hybrid mode store its hashes, which the batched
+        // insertion would corrupt, and the check is cheap as it is done once per batch.
+        assert!(
+            self.harmonic_sum >= f64::ZERO,
+            "The batched insertion is not available in hybrid mode."
+        );
+
+        // We accumulate the variations of the harmonic sum and of the number of
+        // zero registers, and we update the counter once per batch.
+        let mut harmonic_sum_variation = f64::ZERO;
+        let mut number_of_new_non_zero_registers = P::NumberOfRegisters::ZERO;
+
+        for &hash in hashes {
+            let (new_register_value, index) = Self::split_hash(hash);
+            let (old_register_value, larger_register_value) =
+                self.registers.set_greater(index, new_register_value);
+
+            if old_register_value != larger_register_value {
+                number_of_new_non_zero_registers +=
+                    P::NumberOfRegisters::from(old_register_value == 0);
+                harmonic_sum_variation += f64::integer_exp2_minus(larger_register_value)
+                    - f64::integer_exp2_minus(old_register_value);
+                #[cfg(feature = "register_histogram")]
+                self.histogram
+                    .move_register(old_register_value, larger_register_value);
+            }
+        }
+
+        self.number_of_zero_registers -= number_of_new_non_zero_registers;
+        self.harmonic_sum += harmonic_sum_variation;
+    }
 }
@@ -0,0 +1,515 @@
+//! Submodule providing a versioned, self-describing binary format for the counters.
+//!
+//! A serialized counter is laid out as follows, with all integers stored in little-endian order:
+//!
+//! ```text
+//! | offset | size | field                                                           |
+//! |--------|------|-----------------------------------------------------------------|
+//! | 0      | 4    | magic bytes, `HLRS`                                             |
+//! | 4      | 1    | version of the binary format                                    |
+//! | 5      | 1    | identifier of the estimator                                     |
+//! | 6      | 1    | precision exponent                                              |
+//! | 7      | 1    | number of bits per register                                     |
+//! | 8      | 1    | flags: bit 0 for packed registers, bit 1 for hybrid mode, bit 2 |
+//! |        |      | for seeded hashers                                              |
+//! | 9      | 1    | number of bits of the composite hashes, zero if not applicable  |
+//! | 10     | 2    | reserved, always zero                                           |
+//! | 12     | 8    | identifier of the hasher                                        |
+//! | 20     | 4    | number of hashes stored in hybrid mode, zero otherwise          |
+//! | 24     | 4    | number of words                                                 |
+//! | 28     | 8    | seed of the hashers, zero if they are not seeded                |
+//! | 36     | 8 n  | the words storing the registers or the hashes                   |
+//! | 36+8 n | 8    | FNV-1a checksum of all of the preceding bytes                   |
+//! ```
+//!
+//! The hasher is identified by the constant of its [`HasherId`] implementation, which for the
+//! hashers supported by the crate is the FNV-1a hash of the name of their type.
+use core::hash::Hasher;
+
+use crate::error::Error;
+use crate::utils::SeedableHasher;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+/// The magic bytes at the beginning of every serialized counter.
+pub const MAGIC: [u8; 4] = *b"HLRS";
+/// The current version of the binary format.
+pub const VERSION: u8 = 1;
+/// The number of bytes of the header preceding the words.
+const HEADER_SIZE: usize = 36;
+/// The number of bytes of the checksum following the words.
+const CHECKSUM_SIZE: usize = 8;
+/// Flag marking the registers as packed.
+const PACKED_FLAG: u8 = 0b01;
+/// Flag marking the counter as being in hybrid mode.
+const HYBRID_FLAG: u8 = 0b10;
+/// Flag marking the hashers as seeded.
+const SEEDED_FLAG: u8 = 0b100;
+
+/// Estimator identifier of the [`PlusPlus`](crate::prelude::PlusPlus) counter.
+pub const PLUSPLUS_ESTIMATOR: u8 = 0x01;
+/// Estimator identifier of the [`LogLogBeta`](crate::prelude::LogLogBeta) counter.
+pub const LOGLOGBETA_ESTIMATOR: u8 = 0x02;
+/// Estimator identifier of the [`Improved`](crate::prelude::Improved) counter.
+pub const IMPROVED_ESTIMATOR: u8 = 0x03;
+/// Flag added to the estimator identifier of counters wrapped in [`MLE`](crate::prelude::MLE).
+pub const MLE_ESTIMATOR_FLAG: u8 = 0x40;
+/// Flag added to the estimator identifier of counters wrapped in [`Hybrid`](crate::prelude::Hybrid).
+pub const HYBRID_ESTIMATOR_FLAG: u8 = 0x80;
+
+#[inline]
+/// Computes the 64-bit FNV-1a hash of the provided bytes.
+const fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Trait providing the identifier of a hasher, as stored in the binary format.
+///
+/// # Implementative details
+/// The identifier must not change across versions and builds, as it is used to check that
+/// the counters are read with the same hasher they were written with. The counters hashed with
+/// the hashers of other crates are serialized by wrapping them in [`IdentifiedHasher`].
+pub trait HasherId {
+    /// The identifier of the hasher.
+    const HASHER_ID: u64;
+}
+
+impl HasherId for twox_hash::XxHash64 {
+    const HASHER_ID: u64 = fnv1a(b"XxHash64");
+}
+
+impl HasherId for twox_hash::XxHash32 {
+    const HASHER_ID: u64 = fnv1a(b"XxHash32");
+}
+
+impl HasherId for twox_hash::Xxh3Hash64 {
+    const HASHER_ID: u64 = fnv1a(b"Xxh3Hash64");
+}
+
+impl HasherId for twox_hash::Xxh3Hash128 {
+    const HASHER_ID: u64 = fnv1a(b"Xxh3Hash128");
+}
+
+#[cfg(feature = "std")]
+impl HasherId for std::collections::hash_map::DefaultHasher {
+    const HASHER_ID: u64 = fnv1a(b"DefaultHasher");
+}
+
+#[derive(Default, Clone)]
+/// Wrapper providing a hasher with the identifier `ID` it is stored with in the binary format.
+///
+/// # Implementative details
+/// The wrapper hashes exactly as the wrapped hasher, so that the counters hash their elements
+/// into the same registers with and without it. The identifier must not change across versions,
+/// and must differ from the ones of the other hashers the counters may be read with.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// type WyHash = IdentifiedHasher<wyhash::WyHash, 0x5779>;
+/// type Counter =
+///     PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array, WyHash>;
+///
+/// let mut counter = Counter::default();
+/// counter.insert(&42);
+///
+/// let mut buffer = [0_u8; 512];
+/// let size = counter.write_bytes(&mut buffer).unwrap();
+/// let loaded = Counter::from_bytes(&buffer[..size]).unwrap();
+/// assert!(loaded == counter);
+/// ```
+pub struct IdentifiedHasher<H, const ID: u64>(H);
+
+impl<H: Hasher, const ID: u64> Hasher for IdentifiedHasher<H, ID> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+impl<H, const ID: u64> HasherId for IdentifiedHasher<H, ID> {
+    const HASHER_ID: u64 = ID;
+}
+
+impl<H: SeedableHasher, const ID: u64> SeedableHasher for IdentifiedHasher<H, ID> {
+    #[inline]
+    fn with_seed(seed: u64) -> Self {
+        Self(H::with_seed(seed))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The header of a serialized counter, describing its configuration.
+pub struct BinaryHeader {
+    /// The identifier of the estimator.
+    pub estimator: u8,
+    /// The precision exponent.
+    pub exponent: u8,
+    /// The number of bits per register.
+    pub bits: u8,
+    /// Whether the registers are packed.
+    pub packed: bool,
+    /// Whether the counter is in hybrid mode.
+    pub hybrid: bool,
+    /// The number of bits of the composite hashes, zero if not applicable.
+    pub composite_hash_bits: u8,
+    /// The identifier of the hasher.
+    pub hasher: u64,
+    /// The number of hashes stored in hybrid mode, zero otherwise.
+    pub number_of_hashes: u32,
+    /// The seed of the hashers, or `None` if they are not seeded.
+    pub seed: Option<u64>,
+    /// Whether the counter may be loaded in hybrid mode, which only the
+    /// [`Hybrid`](crate::prelude::Hybrid) counters allow as they validate the stored hashes.
+    pub(crate) hybrid_allowed: bool,
+}
+
+impl BinaryHeader {
+    /// Checks the header against the provided configuration.
+    ///
+    /// # Errors
+    /// * If any of the estimator, precision, bits, layout or hasher differ.
+    pub(crate) fn check(
+        &self,
+        estimator: u8,
+        exponent: u8,
+        bits: u8,
+        packed: bool,
+        hasher: u64,
+    ) -> Result<(), Error> {
+        if self.estimator != estimator {
+            return Err(Error::EstimatorMismatch {
+                expected: estimator,
+                found: self.estimator,
+            });
+        }
+        if self.exponent != exponent {
+            return Err(Error::PrecisionMismatch {
+                expected: exponent,
+                found: self.exponent,
+            });
+        }
+        if self.bits != bits {
+            return Err(Error::BitsMismatch {
+                expected: bits,
+                found: self.bits,
+            });
+        }
+        if self.packed != packed {
+            return Err(Error::LayoutMismatch {
+                expected_packed: packed,
+            });
+        }
+        if self.hasher != hasher {
+            return Err(Error::HasherMismatch {
+                expected: hasher,
+                found: self.hasher,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Trait for counters that can be written to and read from the binary format.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// let mut hll: PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array> =
+///     Default::default();
+/// hll.insert(&42);
+///
+/// let mut buffer = [0_u8; 512];
+/// let size = hll.write_bytes(&mut buffer).unwrap();
+/// let loaded: PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array> =
+///     PlusPlus::from_bytes(&buffer[..size]).unwrap();
+/// assert_eq!(hll, loaded);
+///
+/// // Loading the counter with a different precision fails.
+/// assert_eq!(
+///     PlusPlus::<Precision9, Bits6, <Precision9 as ArrayRegister<Bits6>>::Array>::from_bytes(
+///         &buffer[..size]
+///     ),
+///     Err(Error::PrecisionMismatch {
+///         expected: 9,
+///         found: 8
+///     })
+/// );
+/// ```
+pub trait BinaryFormat: Sized {
+    /// The identifier of the estimator stored in the binary format.
+    const ESTIMATOR: u8;
+
+    /// Returns the header describing the counter.
+    fn header(&self) -> BinaryHeader;
+
+    /// Returns the words storing the registers or, in hybrid mode, the hashes.
+    fn words(&self) -> &[u64];
+
+    /// Builds the counter from its header and the little-endian bytes of its words.
+    ///
+    /// # Errors
+    /// * If the header does not match the configuration of the counter.
+    /// * If the number of words does not match the one of the counter.
+    fn from_header_and_words(header: &BinaryHeader, words: &[u8]) -> Result<Self, Error>;
+
+    #[inline]
+    /// Returns the number of bytes required to serialize the counter.
+    fn serialized_size(&self) -> usize {
+        HEADER_SIZE + self.words().len() * 8 + CHECKSUM_SIZE
+    }
+
+    #[inline]
+    /// Writes the counter to the provided buffer, returning the number of bytes written.
+    ///
+    /// # Errors
+    /// * [`Error::BufferTooSmall`] if the buffer cannot contain the serialized counter.
+    /// * [`Error::UnexpectedLength`] if the number of words does not fit in the header.
+    fn write_bytes(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        let size = self.serialized_size();
+        if buffer.len() < size {
+            return Err(Error::BufferTooSmall {
+                required: size,
+                available: buffer.len(),
+            });
+        }
+        let header = self.header();
+        let words = self.words();
+        // The number of words is stored in four bytes of the header.
+        let number_of_words = u32::try_from(words.len()).map_err(|_| Error::UnexpectedLength {
+            expected: HEADER_SIZE + u32::MAX as usize * 8 + CHECKSUM_SIZE,
+            found: size,
+        })?;
+
+        buffer[0..4].copy_from_slice(&MAGIC);
+        buffer[4] = VERSION;
+        buffer[5] = header.estimator;
+        buffer[6] = header.exponent;
+        buffer[7] = header.bits;
+        buffer[8] = if header.packed { PACKED_FLAG } else { 0 }
+            | if header.hybrid { HYBRID_FLAG } else { 0 }
+            | if header.seed.is_some() {
+                SEEDED_FLAG
+            } else {
+                0
+            };
+        buffer[9] = header.composite_hash_bits;
+        buffer[10..12].copy_from_slice(&[0, 0]);
+        buffer[12..20].copy_from_slice(&header.hasher.to_le_bytes());
+        buffer[20..24].copy_from_slice(&header.number_of_hashes.to_le_bytes());
+        buffer[24..28].copy_from_slice(&number_of_words.to_le_bytes());
+        buffer[28..36].copy_from_slice(&header.seed.unwrap_or(0).to_le_bytes());
+        for (chunk, word) in buffer[HEADER_SIZE..size - CHECKSUM_SIZE]
+            .chunks_exact_mut(8)
+            .zip(words)
+        {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        let checksum = fnv1a(&buffer[..size - CHECKSUM_SIZE]);
+        buffer[size - CHECKSUM_SIZE..size].copy_from_slice(&checksum.to_le_bytes());
+
+        Ok(size)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    /// Returns the counter serialized in the binary format.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = vec![0; self.serialized_size()];
+        self.write_bytes(&mut buffer).unwrap();
+        buffer
+    }
+
+    #[inline]
+    /// Reads a counter from the binary format.
+    ///
+    /// # Errors
+    /// * [`Error::UnexpectedLength`] if the bytes are truncated or have trailing data.
+    /// * [`Error::InvalidMagic`] if the bytes do not start with the magic bytes.
+    /// * [`Error::UnsupportedVersion`] if the bytes were written with an unknown version.
+    /// * [`Error::ChecksumMismatch`] if the bytes were corrupted.
+    /// * [`Error::UnknownFlags`] or [`Error::NonZeroReserved`] if the bytes set flags or reserved
+    ///   bytes that this version does not assign.
+    /// * A mismatch error if the configuration of the counter differs from the serialized one.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (header, words) = read_header(bytes)?;
+        Self::from_header_and_words(&header, words)
+    }
+}
+
+/// Validates the envelope of the provided bytes, returning the header and the bytes of the words.
+fn read_header(bytes: &[u8]) -> Result<(BinaryHeader, &[u8]), Error> {
+    if bytes.len() < HEADER_SIZE + CHECKSUM_SIZE {
+        return Err(Error::UnexpectedLength {
+            expected: HEADER_SIZE + CHECKSUM_SIZE,
+            found: bytes.len(),
+        });
+    }
+    if bytes[0..4] != MAGIC {
+        return Err(Error::InvalidMagic);
+    }
+    if bytes[4] != VERSION {
+        return Err(Error::UnsupportedVersion(bytes[4]));
+    }
+    let number_of_words = u32::from_le_bytes(bytes[24..28].try_into().unwrap()) as usize;
+    // The number of words is read from the bytes, so the expected length may not fit in usize.
+    let expected = number_of_words
+        .checked_mul(8)
+        .and_then(|size| size.checked_add(HEADER_SIZE + CHECKSUM_SIZE))
+        .ok_or(Error::UnexpectedLength {
+            expected: usize::MAX,
+            found: bytes.len(),
+        })?;
+    if bytes.len() != expected {
+        return Err(Error::UnexpectedLength {
+            expected,
+            found: bytes.len(),
+        });
+    }
+    let checksum = u64::from_le_bytes(bytes[expected - CHECKSUM_SIZE..].try_into().unwrap());
+    if checksum != fnv1a(&bytes[..expected - CHECKSUM_SIZE]) {
+        return Err(Error::ChecksumMismatch);
+    }
+    // The bits and bytes not assigned by this version are set by a newer one.
+    if bytes[8] & !(PACKED_FLAG | HYBRID_FLAG | SEEDED_FLAG) != 0 {
+        return Err(Error::UnknownFlags(bytes[8]));
+    }
+    if bytes[10..12] != [0, 0] {
+        return Err(Error::NonZeroReserved(u16::from_le_bytes(
+            bytes[10..12].try_into().unwrap(),
+        )));
+    }
+
+    Ok((
+        BinaryHeader {
+            estimator: bytes[5],
+            exponent: bytes[6],
+            bits: bytes[7],
+            packed: bytes[8] & PACKED_FLAG != 0,
+            hybrid: bytes[8] & HYBRID_FLAG != 0,
+            composite_hash_bits: bytes[9],
+            hasher: u64::from_le_bytes(bytes[12..20].try_into().unwrap()),
+            number_of_hashes: u32::from_le_bytes(bytes[20..24].try_into().unwrap()),
+            seed: (bytes[8] & SEEDED_FLAG != 0)
+                .then(|| u64::from_le_bytes(bytes[28..36].try_into().unwrap())),
+            hybrid_allowed: false,
+        },
+        &bytes[HEADER_SIZE..expected - CHECKSUM_SIZE],
+    ))
+}
+
+#[inline]
+/// Copies the little-endian bytes of the words into the provided words.
+///
+/// # Errors
+/// * [`Error::UnexpectedLength`] if the number of words differs.
+pub(crate) fn copy_words(bytes: &[u8], words: &mut [u64]) -> Result<(), Error> {
+    if bytes.len() != words.len() * 8 {
+        return Err(Error::UnexpectedLength {
+            expected: HEADER_SIZE + words.len() * 8 + CHECKSUM_SIZE,
+            found: HEADER_SIZE + bytes.len() + CHECKSUM_SIZE,
+        });
+    }
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        // Reference values of the 64-bit FNV-1a hash.
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_hasher_id() {
+        // The identifiers must not change across versions of the crate.
+        assert_eq!(twox_hash::XxHash64::HASHER_ID, 0x9f61_cae0_10b9_93eb);
+        let identifiers = [
+            twox_hash::XxHash64::HASHER_ID,
+            twox_hash::XxHash32::HASHER_ID,
+            twox_hash::Xxh3Hash64::HASHER_ID,
+            twox_hash::Xxh3Hash128::HASHER_ID,
+        ];
+        for (i, identifier) in identifiers.iter().enumerate() {
+            assert!(!identifiers[..i].contains(identifier));
+        }
+    }
+
+    #[test]
+    fn test_read_header_errors() {
+        assert_eq!(
+            read_header(&[0; 10]),
+            Err(Error::UnexpectedLength {
+                expected: 44,
+                found: 10
+            })
+        );
+        assert_eq!(read_header(&[0; 44]), Err(Error::InvalidMagic));
+        let mut bytes = [0; 44];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4] = VERSION + 1;
+        assert_eq!(
+            read_header(&bytes),
+            Err(Error::UnsupportedVersion(VERSION + 1))
+        );
+        bytes[4] = VERSION;
+        assert_eq!(read_header(&bytes), Err(Error::ChecksumMismatch));
+        let checksum = fnv1a(&bytes[..36]);
+        bytes[36..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(read_header(&bytes).unwrap().0.seed, None);
+
+        // The seed is read only when the hashers are marked as seeded.
+        bytes[8] = SEEDED_FLAG;
+        bytes[28..36].copy_from_slice(&7_u64.to_le_bytes());
+        let checksum = fnv1a(&bytes[..36]);
+        bytes[36..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(read_header(&bytes).unwrap().0.seed, Some(7));
+
+        // The flags and the reserved bytes not assigned by this version are rejected.
+        bytes[8] = SEEDED_FLAG | 0b1000;
+        let checksum = fnv1a(&bytes[..36]);
+        bytes[36..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            read_header(&bytes),
+            Err(Error::UnknownFlags(SEEDED_FLAG | 0b1000))
+        );
+        bytes[8] = SEEDED_FLAG;
+        bytes[11] = 1;
+        let checksum = fnv1a(&bytes[..36]);
+        bytes[36..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(read_header(&bytes), Err(Error::NonZeroReserved(0x0100)));
+        bytes[11] = 0;
+
+        // The length expected from the number of words is computed without overflowing.
+        bytes[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            read_header(&bytes),
+            Err(Error::UnexpectedLength { found: 44, .. })
+        ));
+    }
+}
@@ -2,11 +2,68 @@
 use crate::prelude::VariableWord;
 use core::fmt::Debug;
 
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::{MemDbg, MemSize};
+
 #[cfg(feature = "std")]
 use crate::utils::Named;
 
 /// Trait marker for the number of bits.
-pub trait Bits: VariableWord {}
+pub trait Bits: VariableWord {
+    #[cfg(feature = "mem_dbg")]
+    /// The histogram of the values of registers with this number of bits, with one count for
+    /// each of the `2^BITS` values that a register may hold.
+    type Histogram: RegisterHistogram + MemSize + MemDbg;
+    #[cfg(not(feature = "mem_dbg"))]
+    /// See documentation above.
+    type Histogram: RegisterHistogram;
+}
+
+/// Trait for the histogram of the values of the registers of a counter.
+pub trait RegisterHistogram:
+    AsRef<[u32]> + AsMut<[u32]> + Copy + Debug + PartialEq + Eq + Send + Sync
+{
+    /// The histogram with all of the counts set to zero.
+    const ZERO: Self;
+
+    #[inline]
+    #[must_use]
+    /// Returns the histogram of the provided number of registers, all set to zero.
+    ///
+    /// # Arguments
+    /// * `number_of_registers` - The number of registers of the counter.
+    fn empty(number_of_registers: u32) -> Self {
+        let mut histogram = Self::ZERO;
+        histogram.as_mut()[0] = number_of_registers;
+        histogram
+    }
+
+    #[inline]
+    /// Moves a register from its previous value to its new value.
+    ///
+    /// # Arguments
+    /// * `old_register_value` - The previous value of the register.
+    /// * `new_register_value` - The new value of the register.
+    fn move_register(&mut self, old_register_value: u8, new_register_value: u8) {
+        let histogram = self.as_mut();
+        histogram[usize::from(old_register_value)] -= 1;
+        histogram[usize::from(new_register_value)] += 1;
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the sum of the provided histograms, i.e. the histogram of the registers of both.
+    fn add_histogram(mut self, other: &Self) -> Self {
+        for (count, other_count) in self.as_mut().iter_mut().zip(other.as_ref()) {
+            *count += other_count;
+        }
+        self
+    }
+}
+
+impl<const N: usize> RegisterHistogram for [u32; N] {
+    const ZERO: Self = [0; N];
+}
 
 /// Implementation
 macro_rules! impl_bits {
@@ -41,7 +98,9 @@ macro_rules! impl_bits {
                     }
                 }
 
-                impl Bits for [<Bits $n>] {}
+                impl Bits for [<Bits $n>] {
+                    type Histogram = [u32; 1 << $n];
+                }
             }
         )*
     };
@@ -0,0 +1,282 @@
+//! Submodule providing a ring of [`HyperLogLog`] counters keyed by epoch.
+//!
+//! The [`BucketedCounter`] keeps one counter per epoch for the last `N` epochs, where an epoch
+//! may be for instance a minute or an hour. Advancing the ring to a new epoch clears the counters
+//! of the epochs that fall out of it, and the cardinality of the elements inserted in a range of
+//! epochs is estimated by merging the registers of the corresponding counters on the fly.
+//!
+//! Differently from the [`SlidingWindow`](crate::prelude::SlidingWindow) counter, the windows
+//! are aligned to the epochs and limited to the last `N` of them, but the memory footprint is
+//! fixed and the counters do not require any allocation.
+use core::hash::Hash;
+use core::ops::RangeBounds;
+
+use crate::prelude::*;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A ring of `N` counters, one for each of the last `N` epochs.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+///
+/// // One bucket per hour for the last day.
+/// let mut ring = BucketedCounter::<Counter, 24>::default();
+/// let mut last_three_hours = Counter::default();
+///
+/// for hour in 0..48_u64 {
+///     ring.advance_to(hour);
+///     for user in 0..100_u64 {
+///         let user = hour * 50 + user;
+///         ring.insert(&user);
+///         if hour >= 45 {
+///             last_three_hours.insert(&user);
+///         }
+///     }
+/// }
+///
+/// assert_eq!(ring.epoch(), 47);
+/// // The harmonic sum of the counter is accumulated in a different order.
+/// let expected = last_three_hours.estimate_cardinality();
+/// assert!((ring.estimate_range(45..48) - expected).abs() <= expected * 1e-9);
+/// assert_eq!(ring.counter_range(45..), last_three_hours);
+///
+/// // The epochs older than the last 24 are not retained anymore.
+/// assert!(ring.bucket(23).is_none());
+/// assert!(ring.bucket(24).is_some());
+/// ```
+///
+/// A ring must have at least one bucket:
+///
+/// ```compile_fail
+/// use hyperloglog_rs::prelude::*;
+///
+/// type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+///
+/// let ring = BucketedCounter::<Counter, 0>::default();
+/// ```
+pub struct BucketedCounter<H, const N: usize> {
+    /// The latest epoch of the ring.
+    epoch: u64,
+    /// The counters of the last `N` epochs, with the counter of epoch `e` at position `e mod N`.
+    buckets: [H; N],
+}
+
+impl<H, const N: usize> BucketedCounter<H, N> {
+    /// Fails the compilation of rings without buckets, which could not retain any epoch.
+    const NON_EMPTY: () = assert!(N > 0, "The ring must have at least one bucket.");
+}
+
+impl<H: Default, const N: usize> Default for BucketedCounter<H, N> {
+    #[inline]
+    fn default() -> Self {
+        let () = Self::NON_EMPTY;
+        Self {
+            epoch: 0,
+            buckets: core::array::from_fn(|_| H::default()),
+        }
+    }
+}
+
+impl<H: HyperLogLog, const N: usize> BucketedCounter<H, N> {
+    /// The number of epochs retained by the ring, as an u64.
+    const NUMBER_OF_BUCKETS: u64 = N as u64;
+
+    #[inline]
+    #[must_use]
+    /// Returns the latest epoch of the ring.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    #[inline]
+    /// Returns the position in the ring of the counter of the provided epoch.
+    fn position(epoch: u64) -> usize {
+        // The remainder is smaller than N, and therefore fits in an usize.
+        usize::try_from(epoch % Self::NUMBER_OF_BUCKETS).unwrap()
+    }
+
+    #[inline]
+    /// Returns whether the provided epoch is retained by the ring.
+    fn is_retained(&self, epoch: u64) -> bool {
+        epoch <= self.epoch && self.epoch - epoch < Self::NUMBER_OF_BUCKETS
+    }
+
+    #[inline]
+    /// Returns the epoch of the counter at the provided position, if any.
+    fn epoch_at(&self, position: usize) -> Option<u64> {
+        // We reduce the epoch first, so that the sum does not overflow for the latest epochs.
+        let age = (self.epoch % Self::NUMBER_OF_BUCKETS + Self::NUMBER_OF_BUCKETS
+            - position as u64)
+            % Self::NUMBER_OF_BUCKETS;
+        self.epoch.checked_sub(age)
+    }
+
+    #[inline]
+    /// Advances the ring to the provided epoch, clearing the counters of the epochs that
+    /// are not retained anymore. Epochs earlier than the latest one are ignored.
+    pub fn advance_to(&mut self, epoch: u64) {
+        if epoch <= self.epoch {
+            return;
+        }
+        let first_new_epoch =
+            (self.epoch + 1).max(epoch.saturating_sub(Self::NUMBER_OF_BUCKETS - 1));
+        for new_epoch in first_new_epoch..=epoch {
+            self.buckets[Self::position(new_epoch)].clear();
+        }
+        self.epoch = epoch;
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the counter of the provided epoch, if it is retained by the ring.
+    pub fn bucket(&self, epoch: u64) -> Option<&H> {
+        self.is_retained(epoch)
+            .then(|| &self.buckets[Self::position(epoch)])
+    }
+
+    #[inline]
+    /// Inserts the element in the counter of the provided epoch, advancing the ring if the
+    /// epoch is later than the latest one.
+    ///
+    /// # Returns
+    /// Whether the element was inserted, which is never the case when the epoch is not
+    /// retained by the ring anymore.
+    pub fn insert_at<T: Hash>(&mut self, element: &T, epoch: u64) -> bool
+    where
+        H: ExtendableApproximatedSet<T>,
+    {
+        self.advance_to(epoch);
+        self.is_retained(epoch) && self.buckets[Self::position(epoch)].insert(element)
+    }
+
+    #[inline]
+    /// Returns the counters whose epochs are in the provided range.
+    fn buckets_in_range<'buckets>(
+        &'buckets self,
+        range: &'buckets impl RangeBounds<u64>,
+    ) -> impl Iterator<Item = &'buckets H> + 'buckets {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(move |(position, _)| {
+                self.epoch_at(*position)
+                    .is_some_and(|epoch| range.contains(&epoch))
+            })
+            .map(|(_, bucket)| bucket)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the union of the counters whose epochs are in the provided range.
+    pub fn counter_range<Rg: RangeBounds<u64>>(&self, range: Rg) -> H {
+        let mut union = H::default();
+        union.union_many(self.buckets_in_range(&range));
+        union
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the elements inserted in the epochs in the
+    /// provided range.
+    ///
+    /// # Implementative details
+    /// The estimate is computed with [`Correction::estimate_union_of`], so that no intermediate
+    /// counter is built, and it is corrected as the one of the union counter.
+    pub fn estimate_range<Rg: RangeBounds<u64>>(&self, range: Rg) -> f64
+    where
+        H: Correction,
+    {
+        H::estimate_union_of(self.buckets_in_range(&range))
+    }
+}
+
+impl<H: HyperLogLog, const N: usize> MutableSet for BucketedCounter<H, N> {
+    #[inline]
+    fn clear(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+        self.epoch = 0;
+    }
+}
+
+impl<H: HyperLogLog + ExtendableApproximatedSet<T>, T: Hash, const N: usize>
+    ExtendableApproximatedSet<T> for BucketedCounter<H, N>
+{
+    #[inline]
+    /// Inserts the element in the counter of the latest epoch.
+    fn insert(&mut self, element: &T) -> bool {
+        self.buckets[Self::position(self.epoch)].insert(element)
+    }
+}
+
+impl<H: HyperLogLog, const N: usize> BitOrAssign for BucketedCounter<H, N> {
+    #[inline]
+    /// Merges the counters of the same epochs, after advancing both rings to the latest
+    /// of their epochs.
+    fn bitor_assign(&mut self, mut rhs: Self) {
+        self.advance_to(rhs.epoch);
+        rhs.advance_to(self.epoch);
+        for (bucket, rhs_bucket) in self.buckets.iter_mut().zip(rhs.buckets) {
+            *bucket |= rhs_bucket;
+        }
+    }
+}
+
+impl<H: HyperLogLog, const N: usize> BitOr for BucketedCounter<H, N> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<H: serde::Serialize, const N: usize> serde::Serialize for BucketedCounter<H, N> {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut ring = serializer.serialize_struct("BucketedCounter", 2)?;
+        ring.serialize_field("epoch", &self.epoch)?;
+        ring.serialize_field("buckets", &self.buckets[..])?;
+        ring.end()
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[derive(serde::Deserialize)]
+#[serde(rename = "BucketedCounter")]
+/// The serialized fields of a [`BucketedCounter`], whose buckets are deserialized in a vector
+/// since serde does not support arrays of arbitrary length.
+struct SerializedBucketedCounter<H> {
+    /// The latest epoch of the ring.
+    epoch: u64,
+    /// The counters of the ring.
+    buckets: Vec<H>,
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<'de, H: serde::Deserialize<'de>, const N: usize> serde::Deserialize<'de>
+    for BucketedCounter<H, N>
+{
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let () = Self::NON_EMPTY;
+        let ring = SerializedBucketedCounter::<H>::deserialize(deserializer)?;
+        let buckets = ring.buckets.try_into().map_err(|buckets: Vec<H>| {
+            serde::de::Error::invalid_length(buckets.len(), &"as many buckets as the ring")
+        })?;
+        Ok(Self {
+            epoch: ring.epoch,
+            buckets,
+        })
+    }
+}
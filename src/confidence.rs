@@ -0,0 +1,706 @@
+//! Submodule providing the confidence intervals of the estimates of the counters.
+//!
+//! The variance of the estimates is derived from the observed Fisher information of the Poisson
+//! model of the registers, described in the [`poisson`](crate::poisson) submodule, evaluated at
+//! the point estimates. Differently from the asymptotic error rate of the precision, the width
+//! of the intervals therefore depends on the values of the registers: for instance, it is much
+//! narrower in the small range, where most of the registers are still zero.
+//!
+//! The estimates that involve two counters depend on how the counters estimate the regions of
+//! their Venn diagram. The `MLE` counters estimate them jointly, so the variance follows from
+//! the joint model of the three regions. The other counters derive them by inclusion–exclusion
+//! from the estimates of the two counters and of their union, so the variance follows from the
+//! variances of these three estimates and from their correlation.
+use crate::poisson::iter_terms;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A point estimate with the bounds of its confidence interval.
+pub struct EstimateWithBounds {
+    /// The point estimate.
+    pub estimate: f64,
+    /// The lower bound of the confidence interval.
+    pub lower_bound: f64,
+    /// The upper bound of the confidence interval.
+    pub upper_bound: f64,
+}
+
+impl EstimateWithBounds {
+    #[inline]
+    /// Returns the estimate with the normal confidence interval of the provided standard error,
+    /// clamped to the range of the values that the estimate may take.
+    pub(crate) fn new(estimate: f64, standard_error: f64, quantile: f64, maximum: f64) -> Self {
+        let margin = quantile * standard_error;
+        Self {
+            estimate,
+            lower_bound: (estimate - margin).clamp(0.0, maximum),
+            upper_bound: (estimate + margin).clamp(0.0, maximum),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns whether the provided value is within the bounds of the confidence interval.
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower_bound <= value && value <= self.upper_bound
+    }
+}
+
+/// Returns the quantile of the standard normal distribution at the provided probability.
+///
+/// # Implementative details
+/// We employ the rational approximation by Acklam, whose relative error is below 1.2e-9.
+fn normal_quantile(probability: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.024_25;
+
+    let tail = |q: f64| -> f64 {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if probability < LOW {
+        tail((-2.0 * probability.ln()).sqrt())
+    } else if probability <= 1.0 - LOW {
+        let q = probability - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - probability).ln()).sqrt())
+    }
+}
+
+/// Returns the quantile of the standard normal distribution of a two-sided confidence interval.
+///
+/// # Panics
+/// * If the confidence is not strictly between zero and one.
+pub(crate) fn two_sided_quantile(confidence: f64) -> f64 {
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "The confidence must be strictly between zero and one, got {confidence}."
+    );
+    normal_quantile(0.5 + confidence / 2.0)
+}
+
+/// Returns the observed Fisher information of the cardinalities of the regions of the counters,
+/// given the values of their registers.
+fn observed_information<H: HyperLogLog, const COUNTERS: usize, const REGIONS: usize>(
+    registers: impl Iterator<Item = [u8; COUNTERS]>,
+    cardinalities: &[f64; REGIONS],
+) -> [[f64; REGIONS]; REGIONS] {
+    let mut information = [[0.0; REGIONS]; REGIONS];
+    for values in registers {
+        let mut probability = 0.0;
+        let mut gradient = [0.0; REGIONS];
+        let mut hessian = [[0.0; REGIONS]; REGIONS];
+        for term in iter_terms::<H, REGIONS>(&values) {
+            let value = term.value(cardinalities);
+            probability += value;
+            for (row, row_rate) in term.rates.iter().enumerate() {
+                gradient[row] -= value * row_rate;
+                for (column, column_rate) in term.rates.iter().enumerate() {
+                    hessian[row][column] += value * row_rate * column_rate;
+                }
+            }
+        }
+
+        if probability <= 0.0 {
+            continue;
+        }
+
+        // The information is the opposite of the hessian of the log-likelihood.
+        for row in 0..REGIONS {
+            for column in 0..REGIONS {
+                information[row][column] += gradient[row] * gradient[column]
+                    / (probability * probability)
+                    - hessian[row][column] / probability;
+            }
+        }
+    }
+    information
+}
+
+/// Returns the inverse of the provided matrix, if it is not singular.
+fn invert<const N: usize>(mut matrix: [[f64; N]; N]) -> Option<[[f64; N]; N]> {
+    let mut inverse = [[0.0; N]; N];
+    for (index, row) in inverse.iter_mut().enumerate() {
+        row[index] = 1.0;
+    }
+
+    for column in 0..N {
+        let pivot_row = (column..N).max_by(|&left, &right| {
+            matrix[left][column]
+                .abs()
+                .total_cmp(&matrix[right][column].abs())
+        })?;
+        if !matrix[pivot_row][column].is_normal() {
+            return None;
+        }
+        matrix.swap(column, pivot_row);
+        inverse.swap(column, pivot_row);
+
+        let pivot = matrix[column][column];
+        for index in 0..N {
+            matrix[column][index] /= pivot;
+            inverse[column][index] /= pivot;
+        }
+        for row in (0..N).filter(|row| *row != column) {
+            let factor = matrix[row][column];
+            for index in 0..N {
+                matrix[row][index] -= factor * matrix[column][index];
+                inverse[row][index] -= factor * inverse[column][index];
+            }
+        }
+    }
+    Some(inverse)
+}
+
+/// Returns the standard error of a function of the cardinalities of the regions, given the
+/// covariance of the cardinalities and the gradient of the function.
+fn standard_error<const N: usize>(covariance: Option<&[[f64; N]; N]>, gradient: [f64; N]) -> f64 {
+    covariance.map_or(f64::INFINITY, |covariance| {
+        let variance: f64 = covariance
+            .iter()
+            .zip(gradient.iter())
+            .map(|(row, left)| {
+                row.iter()
+                    .zip(gradient.iter())
+                    .map(|(value, right)| left * value * right)
+                    .sum::<f64>()
+            })
+            .sum();
+        variance.max(0.0).sqrt()
+    })
+}
+
+/// Returns the score and the observed Fisher information of the cardinality of a counter,
+/// given the value of one of its registers.
+fn score_and_information<H: HyperLogLog>(value: u8, cardinality: f64) -> (f64, f64) {
+    let mut probability = 0.0;
+    let mut gradient = 0.0;
+    let mut hessian = 0.0;
+    for term in iter_terms::<H, 1>(&[value]) {
+        let term_value = term.value(&[cardinality]);
+        let [rate] = term.rates;
+        probability += term_value;
+        gradient -= term_value * rate;
+        hessian += term_value * rate * rate;
+    }
+
+    if probability <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let score = gradient / probability;
+    (score, score * score - hessian / probability)
+}
+
+/// Returns the regions of the Venn diagram of two counters, i.e. the cardinalities of the
+/// elements only in the left counter, of the ones only in the right counter and of the ones in
+/// both, given the cardinalities of the two counters and of their union.
+fn regions_from_cardinalities(cardinalities: [f64; 3]) -> [f64; 3] {
+    let [left, right, union] = cardinalities;
+    [
+        (union - right).max(0.0),
+        (union - left).max(0.0),
+        (left + right - union).max(0.0),
+    ]
+}
+
+/// Returns the covariance of the regions of the Venn diagram of two counters, given the
+/// covariance of the cardinalities of the two counters and of their union from which the
+/// regions are derived by inclusion–exclusion.
+pub(crate) fn inclusion_exclusion_covariance(covariance: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    // The coefficients of the cardinalities of the two counters and of their union in each region.
+    const REGIONS: [[f64; 3]; 3] = [[0.0, -1.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 1.0, -1.0]];
+    let mut regions_covariance = [[0.0; 3]; 3];
+    for (row, row_coefficients) in REGIONS.iter().enumerate() {
+        for (column, column_coefficients) in REGIONS.iter().enumerate() {
+            regions_covariance[row][column] = row_coefficients
+                .iter()
+                .zip(covariance.iter())
+                .map(|(left, covariance_row)| {
+                    column_coefficients
+                        .iter()
+                        .zip(covariance_row.iter())
+                        .map(|(right, value)| left * value * right)
+                        .sum::<f64>()
+                })
+                .sum();
+        }
+    }
+    regions_covariance
+}
+
+/// Returns the covariance of the regions of the Venn diagram of two counters, derived by
+/// inclusion–exclusion from the estimated cardinalities of the two counters and of their union.
+///
+/// # Arguments
+/// * `left` - The left counter.
+/// * `right` - The right counter.
+/// * `cardinalities` - The estimated cardinalities of the two counters and of their union.
+///
+/// # Implementative details
+/// The variance of each of the three estimates is the inverse of the observed Fisher information
+/// of the registers it is computed from, as in [`EstimatorWithBounds::estimate_with_bounds`],
+/// where the registers of the union are the maxima of the registers of the two counters. Since
+/// the three estimates are computed from the same registers, they are correlated: as each of
+/// them is approximately linear in the score of its registers, i.e. in the derivative of their
+/// log-likelihood, we estimate their correlation from the products of the scores of the
+/// registers.
+fn counters_covariance<H: HyperLogLog>(
+    left: &H,
+    right: &H,
+    cardinalities: [f64; 3],
+) -> Option<[[f64; 3]; 3]> {
+    let cardinalities = cardinalities.map(|cardinality| cardinality.max(0.0));
+    let mut information = [0.0; 3];
+    let mut scores_products = [[0.0; 3]; 3];
+    for [left_value, right_value] in left.registers().iter_registers_zipped(right.registers()) {
+        let mut scores = [0.0; 3];
+        for (((score, information), value), cardinality) in scores
+            .iter_mut()
+            .zip(information.iter_mut())
+            .zip([left_value, right_value, left_value.max(right_value)])
+            .zip(cardinalities)
+        {
+            let (register_score, register_information) =
+                score_and_information::<H>(value, cardinality);
+            *score = register_score;
+            *information += register_information;
+        }
+        for (row, row_score) in scores.iter().enumerate() {
+            for (column, column_score) in scores.iter().enumerate() {
+                scores_products[row][column] += row_score * column_score;
+            }
+        }
+    }
+
+    // An empty counter is only obtained from an empty set, so its estimate has no variance.
+    let empty = [
+        left.is_empty(),
+        right.is_empty(),
+        left.is_empty() && right.is_empty(),
+    ];
+    let mut standard_errors = [0.0; 3];
+    for ((standard_error, information), is_empty) in
+        standard_errors.iter_mut().zip(information).zip(empty)
+    {
+        if !is_empty {
+            if !information.is_normal() || information < 0.0 {
+                return None;
+            }
+            *standard_error = information.sqrt().recip();
+        }
+    }
+
+    let mut covariance = [[0.0; 3]; 3];
+    for (row, covariance_row) in covariance.iter_mut().enumerate() {
+        for (column, value) in covariance_row.iter_mut().enumerate() {
+            let normalization =
+                (scores_products[row][row] * scores_products[column][column]).sqrt();
+            let correlation = if row == column {
+                1.0
+            } else if normalization > 0.0 {
+                scores_products[row][column] / normalization
+            } else {
+                0.0
+            };
+            *value = correlation * standard_errors[row] * standard_errors[column];
+        }
+    }
+    Some(inclusion_exclusion_covariance(&covariance))
+}
+
+/// The point estimates of two counters and of the regions of their Venn diagram.
+struct JointEstimates {
+    /// The estimated cardinality of the union of the counters.
+    union: f64,
+    /// The estimated cardinalities of the elements only in the left counter, of the ones only
+    /// in the right counter and of the ones in both, following the bitmasks of the regions.
+    regions: [f64; 3],
+    /// The covariance of the cardinalities of the regions, if it could be determined.
+    covariance: Option<[[f64; 3]; 3]>,
+}
+
+/// Returns the point estimates of the union of two counters and of the regions of their Venn
+/// diagram, with the covariance of the latter.
+fn joint_estimates<H: SetEstimatorWithBounds>(left: &H, right: &H) -> JointEstimates {
+    let left_cardinality = left.estimate_cardinality();
+    let right_cardinality = right.estimate_cardinality();
+    let union = left.estimate_union_cardinality_with_cardinalities(
+        right,
+        left_cardinality,
+        right_cardinality,
+    );
+    let cardinalities = [left_cardinality, right_cardinality, union];
+    let covariance = if left.is_empty() && right.is_empty() {
+        // Empty counters are only obtained from empty sets.
+        Some([[0.0; 3]; 3])
+    } else {
+        left.estimate_regions_covariance(right, cardinalities)
+    };
+    JointEstimates {
+        union,
+        regions: regions_from_cardinalities(cardinalities),
+        covariance,
+    }
+}
+
+/// Trait for the estimators providing confidence intervals of their estimates.
+///
+/// The trait is implemented for all of the counters, and the point estimates are the ones of
+/// the [`Estimator`] trait. The intervals of the set operations are provided by the
+/// [`SetEstimatorWithBounds`] trait.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// let mut counter = PlusPlus::<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>::default();
+/// for value in 0..10_000_u64 {
+///     counter.insert(&value);
+/// }
+///
+/// let cardinality = counter.estimate_with_bounds(0.95);
+/// assert_eq!(cardinality.estimate, counter.estimate_cardinality());
+/// assert!(cardinality.lower_bound < cardinality.estimate);
+/// assert!(cardinality.estimate < cardinality.upper_bound);
+///
+/// // The intervals widen as the confidence increases.
+/// let wider = counter.estimate_with_bounds(0.99);
+/// assert!(wider.lower_bound < cardinality.lower_bound);
+/// assert!(wider.upper_bound > cardinality.upper_bound);
+/// ```
+pub trait EstimatorWithBounds: Estimator<f64> {
+    #[must_use]
+    /// Returns the estimated cardinality with the bounds of its confidence interval.
+    ///
+    /// # Arguments
+    /// * `confidence` - The probability that the interval contains the cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    fn estimate_with_bounds(&self, confidence: f64) -> EstimateWithBounds;
+}
+
+impl<H: HyperLogLog + Estimator<f64>> EstimatorWithBounds for H {
+    #[inline]
+    fn estimate_with_bounds(&self, confidence: f64) -> EstimateWithBounds {
+        let quantile = two_sided_quantile(confidence);
+        let estimate = self.estimate_cardinality();
+        let standard_error = if self.is_empty() {
+            // An empty counter is only obtained from an empty set.
+            0.0
+        } else {
+            let information = observed_information::<Self, 1, 1>(
+                self.registers().iter_registers().map(|value| [value]),
+                &[estimate.max(0.0)],
+            );
+            standard_error(invert(information).as_ref(), [1.0])
+        };
+        EstimateWithBounds::new(estimate, standard_error, quantile, f64::INFINITY)
+    }
+}
+
+/// Trait for the estimators providing confidence intervals of the estimates of set operations.
+///
+/// The intervals are derived from the covariance of the estimated regions of the Venn diagram of
+/// the two counters, which depends on how the counters estimate them: the `MLE` counters employ
+/// the joint model of the regions, while the others derive them by inclusion–exclusion from the
+/// estimates of the two counters and of their union, whose variance is larger.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// let mut left = PlusPlus::<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>::default();
+/// let mut right = PlusPlus::<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>::default();
+/// for value in 0..10_000_u64 {
+///     left.insert(&value);
+///     right.insert(&(value + 5_000));
+/// }
+///
+/// let intersection = left.estimate_intersection_with_bounds(&right, 0.95);
+/// assert_eq!(intersection.estimate, left.estimate_intersection_cardinality(&right));
+/// assert!(intersection.lower_bound < intersection.estimate);
+/// assert!(intersection.estimate < intersection.upper_bound);
+///
+/// let jaccard = left.estimate_jaccard_index_with_bounds(&right, 0.95);
+/// assert!(jaccard.lower_bound >= 0.0 && jaccard.upper_bound <= 1.0);
+/// ```
+pub trait SetEstimatorWithBounds: EstimatorWithBounds + SetProperties {
+    #[must_use]
+    /// Returns the covariance of the estimated cardinalities of the elements only in the current
+    /// counter, of the ones only in the other counter and of the ones in both, or `None` if it
+    /// cannot be determined.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `cardinalities` - The estimated cardinalities of the two counters and of their union.
+    fn estimate_regions_covariance(
+        &self,
+        other: &Self,
+        cardinalities: [f64; 3],
+    ) -> Option<[[f64; 3]; 3]>;
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the union of the two counters with the bounds of
+    /// its confidence interval.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    fn estimate_union_with_bounds(&self, other: &Self, confidence: f64) -> EstimateWithBounds {
+        let quantile = two_sided_quantile(confidence);
+        let joint = joint_estimates(self, other);
+        EstimateWithBounds::new(
+            joint.union,
+            standard_error(joint.covariance.as_ref(), [1.0, 1.0, 1.0]),
+            quantile,
+            f64::INFINITY,
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the intersection of the two counters with the
+    /// bounds of its confidence interval.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    fn estimate_intersection_with_bounds(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> EstimateWithBounds {
+        let quantile = two_sided_quantile(confidence);
+        let joint = joint_estimates(self, other);
+        EstimateWithBounds::new(
+            joint.regions[2],
+            standard_error(joint.covariance.as_ref(), [0.0, 0.0, 1.0]),
+            quantile,
+            f64::INFINITY,
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated Jaccard index of the two counters with the bounds of its
+    /// confidence interval.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the Jaccard index.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    fn estimate_jaccard_index_with_bounds(
+        &self,
+        other: &Self,
+        confidence: f64,
+    ) -> EstimateWithBounds {
+        let quantile = two_sided_quantile(confidence);
+        let joint = joint_estimates(self, other);
+        let [self_only, other_only, intersection] = joint.regions;
+        let union = self_only + other_only + intersection;
+        if union <= 0.0 {
+            return EstimateWithBounds::new(0.0, 0.0, quantile, 1.0);
+        }
+
+        // The standard error follows from the gradient of the ratio, i.e. the delta method.
+        let squared_union = union * union;
+        let gradient = [
+            -intersection / squared_union,
+            -intersection / squared_union,
+            (self_only + other_only) / squared_union,
+        ];
+        EstimateWithBounds::new(
+            intersection / union,
+            standard_error(joint.covariance.as_ref(), gradient),
+            quantile,
+            1.0,
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the elements of the current counter which are not
+    /// in the other counter, with the bounds of its confidence interval.
+    ///
+    /// # Arguments
+    /// * `other` - The other counter.
+    /// * `confidence` - The probability that the interval contains the cardinality.
+    ///
+    /// # Panics
+    /// * If the confidence is not strictly between zero and one.
+    fn estimate_difference_with_bounds(&self, other: &Self, confidence: f64) -> EstimateWithBounds {
+        let quantile = two_sided_quantile(confidence);
+        let joint = joint_estimates(self, other);
+        EstimateWithBounds::new(
+            joint.regions[0],
+            standard_error(joint.covariance.as_ref(), [1.0, 0.0, 0.0]),
+            quantile,
+            f64::INFINITY,
+        )
+    }
+}
+
+#[cfg(feature = "mle")]
+impl<H, const ERROR: i32, L> SetEstimatorWithBounds for MLE<H, ERROR, L>
+where
+    MLE<H, ERROR, L>: HyperLogLog + Estimator<f64>,
+{
+    #[inline]
+    fn estimate_regions_covariance(
+        &self,
+        other: &Self,
+        cardinalities: [f64; 3],
+    ) -> Option<[[f64; 3]; 3]> {
+        // The inverse of the joint information is the covariance of the maximum likelihood
+        // estimates of the regions.
+        invert(observed_information::<Self, 2, 3>(
+            self.registers().iter_registers_zipped(other.registers()),
+            &regions_from_cardinalities(cardinalities),
+        ))
+    }
+}
+
+#[cfg(feature = "plusplus")]
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > SetEstimatorWithBounds for PlusPlus<P, B, R, Hasher, Builder, C>
+where
+    Self: HyperLogLog + Estimator<f64>,
+{
+    #[inline]
+    fn estimate_regions_covariance(
+        &self,
+        other: &Self,
+        cardinalities: [f64; 3],
+    ) -> Option<[[f64; 3]; 3]> {
+        counters_covariance(self, other, cardinalities)
+    }
+}
+
+#[cfg(feature = "beta")]
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > SetEstimatorWithBounds for LogLogBeta<P, B, R, Hasher, Builder, C>
+where
+    Self: HyperLogLog + Estimator<f64>,
+{
+    #[inline]
+    fn estimate_regions_covariance(
+        &self,
+        other: &Self,
+        cardinalities: [f64; 3],
+    ) -> Option<[[f64; 3]; 3]> {
+        counters_covariance(self, other, cardinalities)
+    }
+}
+
+#[cfg(feature = "improved")]
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > SetEstimatorWithBounds for Improved<P, B, R, Hasher, Builder>
+where
+    Self: HyperLogLog + Estimator<f64>,
+{
+    #[inline]
+    fn estimate_regions_covariance(
+        &self,
+        other: &Self,
+        cardinalities: [f64; 3],
+    ) -> Option<[[f64; 3]; 3]> {
+        counters_covariance(self, other, cardinalities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_quantile() {
+        for (probability, expected) in [
+            (0.5, 0.0),
+            (0.975, 1.959_963_984_540_054),
+            (0.995, 2.575_829_303_548_901),
+            (0.01, -2.326_347_874_040_841),
+        ] {
+            assert!((normal_quantile(probability) - expected).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_invert() {
+        let matrix = [[4.0, 1.0, 0.5], [1.0, 3.0, 0.0], [0.5, 0.0, 2.0]];
+        let inverse = invert(matrix).unwrap();
+        for (row, values) in matrix.iter().enumerate() {
+            for column in 0..3 {
+                let product: f64 = values
+                    .iter()
+                    .zip(inverse.iter())
+                    .map(|(value, inverse_row)| value * inverse_row[column])
+                    .sum();
+                let expected = if row == column { 1.0 } else { 0.0 };
+                assert!((product - expected).abs() < 1e-12);
+            }
+        }
+        assert!(invert([[1.0, 2.0], [2.0, 4.0]]).is_none());
+    }
+}
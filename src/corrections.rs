@@ -0,0 +1,389 @@
+//! Submodule providing the bias correction strategies of the [`PlusPlus`] and [`LogLogBeta`]
+//! counters.
+//!
+//! The strategies are marker types provided to the counters as type parameters, so that
+//! counters correcting their estimates differently coexist in the same build and no feature
+//! enabled by a dependency changes the estimates. The default type arguments correspond to the
+//! linear interpolation of the floating point biases for [`PlusPlus`] and to the precomputed beta
+//! values for [`LogLogBeta`], both switching to the linear counting when many registers are
+//! zeros and employing a lookup table for the logarithms.
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::marker::PhantomData;
+
+use crate::prelude::*;
+use crate::utils::FloatOps;
+
+#[cfg(feature = "plusplus")]
+use core::ops::{Add, Sub};
+
+#[cfg(feature = "beta")]
+use crate::utils::ToF64;
+
+#[cfg(feature = "beta")]
+use crate::utils::Zero;
+
+/// Trait for the corrections of the estimates of counters with many zero registers.
+pub trait SmallRangeCorrection: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// The suffix of the name of the estimators employing the correction.
+    const SUFFIX: &'static str;
+
+    /// Returns the corrected estimate, if the provided number of zero registers requires it.
+    fn small_range_estimate<P: Precision, L: Logarithm>(
+        number_of_zero_registers: P::NumberOfRegisters,
+    ) -> Option<f64>;
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Switches to the linear counting when the zero registers reach the threshold of the precision.
+pub struct LinearCounting;
+
+impl SmallRangeCorrection for LinearCounting {
+    const SUFFIX: &'static str = "";
+
+    #[inline]
+    fn small_range_estimate<P: Precision, L: Logarithm>(
+        number_of_zero_registers: P::NumberOfRegisters,
+    ) -> Option<f64> {
+        (number_of_zero_registers >= P::LINEAR_COUNT_ZEROS)
+            .then(|| L::linear_counting::<P>(number_of_zero_registers))
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Never switches to the linear counting, relying solely on the bias correction.
+pub struct NoSmallRangeCorrection;
+
+impl SmallRangeCorrection for NoSmallRangeCorrection {
+    const SUFFIX: &'static str = "-no-lc";
+
+    #[inline]
+    fn small_range_estimate<P: Precision, L: Logarithm>(
+        _number_of_zero_registers: P::NumberOfRegisters,
+    ) -> Option<f64> {
+        None
+    }
+}
+
+#[cfg(feature = "plusplus")]
+/// Trait for the interpolations of the [`PlusPlus`] biases between the estimate centroids.
+pub trait BiasInterpolation: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// The suffix of the name of the estimators employing the interpolation.
+    const SUFFIX: &'static str;
+
+    /// Returns the bias of the provided estimate.
+    ///
+    /// # Arguments
+    /// * `estimates` - The sorted estimate centroids.
+    /// * `biases` - The biases aligned with the estimate centroids.
+    /// * `estimate` - The raw estimate whose bias is computed.
+    fn interpolate<V, W>(estimates: &[V], biases: &[W], estimate: V) -> f64
+    where
+        V: Copy + PartialOrd + Add<Output = V> + Sub<Output = V>,
+        W: Copy,
+        f64: From<V> + From<W>;
+}
+
+#[cfg(feature = "plusplus")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Linearly interpolates the biases of the two centroids surrounding the estimate.
+pub struct LinearInterpolation;
+
+#[cfg(feature = "plusplus")]
+impl BiasInterpolation for LinearInterpolation {
+    const SUFFIX: &'static str = "";
+
+    #[inline]
+    fn interpolate<V, W>(estimates: &[V], biases: &[W], estimate: V) -> f64
+    where
+        V: Copy + PartialOrd + Add<Output = V> + Sub<Output = V>,
+        W: Copy,
+        f64: From<V> + From<W>,
+    {
+        let index = estimates.partition_point(|estimate_centroid| estimate_centroid <= &estimate);
+
+        if index == 0 {
+            return f64::from(biases[0]);
+        }
+
+        if index == estimates.len() {
+            return f64::from(biases[estimates.len() - 1]);
+        }
+
+        let x0 = f64::from(estimates[index - 1]);
+        let x1 = f64::from(estimates[index]);
+
+        let y0 = f64::from(biases[index - 1]);
+        let y1 = f64::from(biases[index]);
+
+        y0 + (y1 - y0) * (f64::from(estimate) - x0) / (x1 - x0)
+    }
+}
+
+#[cfg(feature = "plusplus")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Averages the biases of the six centroids nearest to the estimate.
+pub struct KMeansInterpolation;
+
+#[cfg(feature = "plusplus")]
+impl BiasInterpolation for KMeansInterpolation {
+    const SUFFIX: &'static str = "K";
+
+    #[inline]
+    fn interpolate<V, W>(estimates: &[V], biases: &[W], estimate: V) -> f64
+    where
+        V: Copy + PartialOrd + Add<Output = V> + Sub<Output = V>,
+        W: Copy,
+        f64: From<V> + From<W>,
+    {
+        let index = estimates
+            .partition_point(|estimate_centroid| estimate_centroid <= &estimate)
+            .max(1)
+            - 1;
+
+        let mut min = index.saturating_sub(6);
+        let mut max = core::cmp::min(index + 6, estimates.len());
+
+        while max - min != 6 {
+            let (min_val, max_val) = (estimates[min], estimates[max - 1]);
+            if estimate + estimate - min_val > max_val {
+                min += 1;
+            } else {
+                max -= 1;
+            }
+        }
+        biases[min..max].iter().map(|b| f64::from(*b)).sum::<f64>() / 6.0
+    }
+}
+
+#[cfg(feature = "plusplus")]
+/// Trait for the tables of biases of the [`PlusPlus`] bias correction.
+pub trait BiasTable: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// The suffix of the name of the estimators employing the table.
+    const SUFFIX: &'static str;
+
+    /// Returns the bias of the provided raw estimate, interpolated between the centroids.
+    fn bias<P: Precision, I: BiasInterpolation>(estimate: f64) -> f64;
+}
+
+#[cfg(feature = "plusplus")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The floating point biases and estimate centroids.
+pub struct FloatBiases;
+
+#[cfg(feature = "plusplus")]
+impl BiasTable for FloatBiases {
+    const SUFFIX: &'static str = "";
+
+    #[inline]
+    fn bias<P: Precision, I: BiasInterpolation>(estimate: f64) -> f64 {
+        I::interpolate(P::ESTIMATES, P::BIASES, estimate)
+    }
+}
+
+#[cfg(feature = "plusplus")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The biases and estimate centroids rounded to integers, which make for smaller tables.
+pub struct IntegerBiases;
+
+#[cfg(feature = "plusplus")]
+impl BiasTable for IntegerBiases {
+    const SUFFIX: &'static str = "I";
+
+    #[inline]
+    #[expect(clippy::cast_sign_loss, reason = "Cardinality is always positive.")]
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Bias is only applied to values smaller than 2**21."
+    )]
+    fn bias<P: Precision, I: BiasInterpolation>(estimate: f64) -> f64 {
+        I::interpolate(P::INTEGER_ESTIMATES, P::INTEGER_BIASES, estimate as u32)
+    }
+}
+
+#[cfg(feature = "plusplus")]
+/// Trait for the strategies correcting the estimates of the [`PlusPlus`] counters.
+pub trait PlusPlusCorrection: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// Returns the estimate of the cardinality using the `HyperLogLog++` algorithm.
+    ///
+    /// # Arguments
+    /// * `harmonic_sum` - The harmonic sum of the registers.
+    /// * `number_of_zero_registers` - The number of registers with zero values.
+    fn estimate<P: Precision>(
+        harmonic_sum: f64,
+        number_of_zero_registers: P::NumberOfRegisters,
+    ) -> f64;
+
+    #[cfg(feature = "std")]
+    /// Returns the name of the estimator employing the correction.
+    fn estimator_name() -> String;
+}
+
+#[cfg(feature = "plusplus")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The `HyperLogLog++` bias correction, composed of the provided table of biases,
+/// interpolation, small range correction and logarithm.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// type Registers = <Precision8 as ArrayRegister<Bits6>>::Array;
+/// type Hasher = twox_hash::XxHash64;
+/// type Counter = PlusPlus<Precision8, Bits6, Registers>;
+/// type Integer = PlusPlus<
+///     Precision8,
+///     Bits6,
+///     Registers,
+///     Hasher,
+///     UnseededBuilder<Hasher>,
+///     PlusPlusBias<IntegerBiases, KMeansInterpolation>,
+/// >;
+///
+/// let mut counter = Counter::default();
+/// let mut integer = Integer::default();
+///
+/// for value in 0..1_000_u64 {
+///     counter.insert(&value);
+///     integer.insert(&value);
+/// }
+///
+/// // The two counters share the same registers, but correct their estimates differently.
+/// assert_eq!(counter.registers(), integer.registers());
+/// assert_ne!(counter.estimate_cardinality(), integer.estimate_cardinality());
+/// assert!((counter.estimate_cardinality() - integer.estimate_cardinality()).abs() < 10.0);
+/// ```
+pub struct PlusPlusBias<T = FloatBiases, I = LinearInterpolation, S = LinearCounting, L = LookupLn>(
+    PhantomData<(T, I, S, L)>,
+);
+
+#[cfg(feature = "plusplus")]
+impl<T: BiasTable, I: BiasInterpolation, S: SmallRangeCorrection, L: Logarithm> PlusPlusCorrection
+    for PlusPlusBias<T, I, S, L>
+{
+    #[inline]
+    fn estimate<P: Precision>(
+        harmonic_sum: f64,
+        number_of_zero_registers: P::NumberOfRegisters,
+    ) -> f64 {
+        if let Some(estimate) = S::small_range_estimate::<P, L>(number_of_zero_registers) {
+            return estimate;
+        }
+
+        let estimate = P::ALPHA * f64::integer_exp2(P::EXPONENT + P::EXPONENT) / harmonic_sum;
+
+        // Apply the small range correction factor if the raw estimate is below the threshold
+        // and there are zero registers in the counter.
+        if estimate <= 5.0_f64 * f64::integer_exp2(P::EXPONENT) {
+            estimate - T::bias::<P, I>(estimate)
+        } else {
+            estimate
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn estimator_name() -> String {
+        format!("PP{}{}{}{}", T::SUFFIX, I::SUFFIX, S::SUFFIX, L::SUFFIX)
+    }
+}
+
+#[cfg(feature = "beta")]
+/// Trait for the evaluations of the beta function of the [`LogLogBeta`] bias correction.
+pub trait BetaFunction: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// The suffix of the name of the estimators employing the evaluation.
+    const SUFFIX: &'static str;
+
+    /// Returns the value of the beta function for the provided number of zero registers.
+    fn beta<P: Precision, L: Logarithm>(number_of_zero_registers: P::NumberOfRegisters) -> f64;
+}
+
+#[cfg(feature = "beta")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Reads the beta function from a table precomputed by the build script for each number
+/// of zero registers.
+pub struct PrecomputedBeta;
+
+#[cfg(feature = "beta")]
+impl BetaFunction for PrecomputedBeta {
+    const SUFFIX: &'static str = "PB";
+
+    #[inline]
+    fn beta<P: Precision, L: Logarithm>(number_of_zero_registers: P::NumberOfRegisters) -> f64 {
+        P::const_beta_horner(number_of_zero_registers)
+    }
+}
+
+#[cfg(feature = "beta")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Evaluates the polynomial of the beta function using Horner's method.
+///
+/// Wikipedia: <https://en.wikipedia.org/wiki/Horner%27s_method>
+pub struct HornerBeta;
+
+#[cfg(feature = "beta")]
+impl BetaFunction for HornerBeta {
+    const SUFFIX: &'static str = "B";
+
+    #[inline]
+    fn beta<P: Precision, L: Logarithm>(number_of_zero_registers: P::NumberOfRegisters) -> f64 {
+        let number_of_zero_registers_ln = L::ln_1p_zeros::<P>(number_of_zero_registers);
+        let mut res = f64::ZERO;
+        for i in (1..8).rev() {
+            res = res * number_of_zero_registers_ln + P::BETA[i];
+        }
+        res * number_of_zero_registers_ln + P::BETA[0] * number_of_zero_registers.to_f64()
+    }
+}
+
+#[cfg(feature = "beta")]
+/// Trait for the strategies correcting the estimates of the [`LogLogBeta`] counters.
+pub trait BetaCorrection: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// Returns the estimate of the cardinality using the LogLog-Beta algorithm.
+    ///
+    /// # Arguments
+    /// * `harmonic_sum` - The harmonic sum of the registers.
+    /// * `number_of_zero_registers` - The number of registers with zero values.
+    fn estimate<P: Precision>(
+        harmonic_sum: f64,
+        number_of_zero_registers: P::NumberOfRegisters,
+    ) -> f64;
+
+    #[cfg(feature = "std")]
+    /// Returns the name of the estimator employing the correction.
+    fn estimator_name() -> String;
+}
+
+#[cfg(feature = "beta")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The LogLog-Beta bias correction, composed of the provided evaluation of the beta function,
+/// small range correction and logarithm.
+///
+/// Paper: <https://arxiv.org/pdf/1612.02284.pdf>
+pub struct BetaBias<F = PrecomputedBeta, S = LinearCounting, L = LookupLn>(PhantomData<(F, S, L)>);
+
+#[cfg(feature = "beta")]
+impl<F: BetaFunction, S: SmallRangeCorrection, L: Logarithm> BetaCorrection for BetaBias<F, S, L> {
+    #[inline]
+    fn estimate<P: Precision>(
+        harmonic_sum: f64,
+        number_of_zero_registers: P::NumberOfRegisters,
+    ) -> f64 {
+        if let Some(estimate) = S::small_range_estimate::<P, L>(number_of_zero_registers) {
+            return estimate;
+        }
+
+        P::ALPHA
+            * f64::integer_exp2(P::EXPONENT)
+            * (f64::integer_exp2(P::EXPONENT) - number_of_zero_registers.to_f64())
+            / (harmonic_sum + F::beta::<P, L>(number_of_zero_registers))
+            + 0.5
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn estimator_name() -> String {
+        format!("LL{}{}{}", F::SUFFIX, S::SUFFIX, L::SUFFIX)
+    }
+}
@@ -0,0 +1,790 @@
+//! Submodule providing [`HyperLogLog`] counters whose precision and number of bits per
+//! register are chosen at runtime instead of at compile time.
+//!
+//! The counters in this module dispatch the estimation to the type-level [`Precision`]
+//! implementations, so that they reuse the same bias tables, linear counting thresholds,
+//! alpha constants and beta coefficients generated by the build script. For the same
+//! configuration, bias correction and hasher, their estimates are bit-identical to the ones
+//! of the corresponding [`PlusPlus`] and [`LogLogBeta`] counters.
+//!
+//! Registers are stored one per byte in a heap-allocated vector: these counters trade
+//! some memory for the ability to pick the configuration from, for instance, a
+//! configuration file or a per-tenant setting.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::error::Error;
+use crate::prelude::*;
+use crate::utils::{correct_union_estimate, FloatOps, HasherType, PositiveInteger};
+
+#[cfg(feature = "std")]
+use core::any::type_name;
+
+/// Implements the runtime dispatch from a precision exponent to the corresponding
+/// type-level [`Precision`] implementation.
+macro_rules! impl_dynamic_dispatch {
+    ($($exponent:literal),*) => {
+        paste::paste! {
+            /// Returns whether the provided exponent corresponds to an enabled precision.
+            const fn is_supported_precision(exponent: u8) -> bool {
+                match exponent {
+                    $(
+                        #[cfg(feature = "precision_" $exponent)]
+                        $exponent => true,
+                    )*
+                    _ => false,
+                }
+            }
+
+            #[cfg(feature = "plusplus")]
+            /// Computes the [`PlusPlus`] estimate with the precision of the provided exponent.
+            fn plusplus_estimate<C: PlusPlusCorrection>(
+                exponent: u8,
+                harmonic_sum: f64,
+                number_of_zero_registers: u32,
+            ) -> f64 {
+                match exponent {
+                    $(
+                        #[cfg(feature = "precision_" $exponent)]
+                        $exponent => C::estimate::<[<Precision $exponent>]>(
+                            harmonic_sum,
+                            <[<Precision $exponent>] as Precision>::NumberOfRegisters::try_from_u64(
+                                u64::from(number_of_zero_registers)
+                            ).unwrap(),
+                        ),
+                    )*
+                    _ => unreachable!("The precision {} is not supported.", exponent),
+                }
+            }
+
+            #[cfg(feature = "beta")]
+            /// Computes the [`LogLogBeta`] estimate with the precision of the provided exponent.
+            fn beta_estimate<C: BetaCorrection>(
+                exponent: u8,
+                harmonic_sum: f64,
+                number_of_zero_registers: u32,
+            ) -> f64 {
+                match exponent {
+                    $(
+                        #[cfg(feature = "precision_" $exponent)]
+                        $exponent => C::estimate::<[<Precision $exponent>]>(
+                            harmonic_sum,
+                            <[<Precision $exponent>] as Precision>::NumberOfRegisters::try_from_u64(
+                                u64::from(number_of_zero_registers)
+                            ).unwrap(),
+                        ),
+                    )*
+                    _ => unreachable!("The precision {} is not supported.", exponent),
+                }
+            }
+        }
+    };
+}
+
+impl_dynamic_dispatch!(4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18);
+
+#[derive(Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A basic counter data structure for HyperLogLog-like counters with runtime configuration.
+pub(crate) struct DynamicLogLog<
+    Hasher: HasherType,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+> {
+    /// The exponent of the number of registers.
+    exponent: u8,
+    /// The number of bits per register.
+    bits: u8,
+    /// The registers of the counter, one per byte.
+    registers: Vec<u8>,
+    /// The number of registers with zero values.
+    number_of_zero_registers: u32,
+    /// The harmonic sum of the registers, i.e. the sum of 2^(-register_value) for all registers.
+    harmonic_sum: f64,
+    /// The builder of the hashers of the counter.
+    hasher_builder: Builder,
+    /// Phantom data to ensure the type parameters are used.
+    _phantom: PhantomData<Hasher>,
+}
+
+impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>> Debug
+    for DynamicLogLog<Hasher, Builder>
+{
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        formatter
+            .debug_struct("DynamicLogLog")
+            .field("exponent", &self.exponent)
+            .field("bits", &self.bits)
+            .field("registers", &self.registers)
+            .field("number_of_zero_registers", &self.number_of_zero_registers)
+            .field("harmonic_sum", &self.harmonic_sum)
+            .field("hasher_builder", &self.hasher_builder)
+            .finish()
+    }
+}
+
+/// Implementation of partial equality for the dynamic counter so as to compare two instances
+/// ignoring the harmonic sum.
+impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>> PartialEq
+    for DynamicLogLog<Hasher, Builder>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.exponent == other.exponent
+            && self.bits == other.bits
+            && self.registers == other.registers
+            && self.hasher_builder == other.hasher_builder
+    }
+}
+
+impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>> Eq
+    for DynamicLogLog<Hasher, Builder>
+{
+}
+
+impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>> DynamicLogLog<Hasher, Builder> {
+    /// Create a new counter with the provided precision exponent and number of bits, hashing
+    /// its elements with the hashers created by the provided builder.
+    fn new(exponent: u8, bits: u8, hasher_builder: Builder) -> Result<Self, Error> {
+        if !is_supported_precision(exponent) {
+            return Err(Error::UnsupportedPrecision(exponent));
+        }
+        if !(1..=8).contains(&bits) {
+            return Err(Error::UnsupportedBits(bits));
+        }
+        Ok(Self {
+            exponent,
+            bits,
+            registers: vec![0; 1 << exponent],
+            number_of_zero_registers: 1 << exponent,
+            harmonic_sum: f64::integer_exp2(exponent),
+            hasher_builder,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Create a new counter from a type-level [`HyperLogLog`] counter.
+    fn from_counter<H: HyperLogLog<Hasher = Hasher, HasherBuilder = Builder>>(counter: &H) -> Self {
+        Self {
+            exponent: H::Precision::EXPONENT,
+            bits: H::Bits::NUMBER_OF_BITS,
+            registers: counter.registers().iter_registers().collect(),
+            number_of_zero_registers: u32::try_from(
+                counter.get_number_of_zero_registers().to_usize(),
+            )
+            .unwrap(),
+            harmonic_sum: counter.harmonic_sum(),
+            hasher_builder: counter.hasher_builder().clone(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Folds the counter into a counter with the provided lower or equal precision exponent,
+    /// following the same procedure as [`HyperLogLog::fold_to`].
+    fn fold_to(&self, exponent: u8) -> Result<Self, Error> {
+        if exponent > self.exponent {
+            return Err(Error::FoldToHigherPrecision {
+                from: self.exponent,
+                to: exponent,
+            });
+        }
+        let mut folded = Self::new(exponent, self.bits, self.hasher_builder.clone())?;
+        let mask = (1_usize << exponent) - 1;
+        for (index, register) in self.registers.iter().enumerate() {
+            if *register > 0 {
+                folded.insert_register_value_and_index(*register, index & mask);
+            }
+        }
+        Ok(folded)
+    }
+
+    /// Converts the counter into a counter with the provided number of bits per register,
+    /// following the same procedure as [`HyperLogLog::convert_bits`].
+    fn convert_bits(&self, bits: u8) -> Result<Self, Error> {
+        let mut converted = Self::new(self.exponent, bits, self.hasher_builder.clone())?;
+        let maximum = converted.mask().min(65);
+        for (index, register) in self.registers.iter().enumerate() {
+            if *register > 0 {
+                converted.insert_register_value_and_index((*register).min(maximum), index);
+            }
+        }
+        Ok(converted)
+    }
+
+    /// Returns the largest value that may be stored in a register.
+    fn mask(&self) -> u8 {
+        u8::try_from((1_u16 << self.bits) - 1).unwrap()
+    }
+
+    /// Returns whether the two counters share the same precision and number of bits.
+    fn is_compatible(&self, other: &Self) -> bool {
+        self.exponent == other.exponent && self.bits == other.bits
+    }
+
+    /// Splits the hash into the register value and the index of the register,
+    /// following the same procedure as [`HyperLogLog::split_hash`].
+    fn split_hash(&self, hash: u64) -> (u8, usize) {
+        let index = usize::try_from(hash & ((1_u64 << self.exponent) - 1)).unwrap();
+
+        let mut censored_hash: u64 = hash;
+
+        // We need to add ones to the hash to make sure that the
+        // the number of zeros we obtain afterwards is never higher
+        // than the maximal value that may be represented in a register
+        // with the requested number of bits.
+        if self.bits < 6 {
+            censored_hash |= 1_u64 << (64 - u32::from(self.mask()));
+        }
+
+        let register_value = u8::try_from(censored_hash.leading_zeros() + 1).unwrap();
+
+        (register_value.min(self.mask()), index)
+    }
+
+    /// Hashes the element and returns the register value and the index of the register.
+    fn hash_and_index<T: Hash>(&self, element: &T) -> (u8, usize) {
+        self.split_hash(self.hasher_builder.hash_one(element))
+    }
+
+    /// Inserts the provided register value at the given index, if it is larger than the current one.
+    fn insert_register_value_and_index(&mut self, new_register_value: u8, index: usize) -> bool {
+        let old_register_value = self.registers[index];
+        let larger_register_value = old_register_value.max(new_register_value);
+        self.registers[index] = larger_register_value;
+
+        self.number_of_zero_registers -= u32::from(old_register_value == 0);
+
+        self.harmonic_sum += f64::integer_exp2_minus(larger_register_value)
+            - f64::integer_exp2_minus(old_register_value);
+
+        old_register_value != new_register_value
+    }
+
+    /// Returns the harmonic sum and the number of zero registers of the union of the two counters.
+    fn get_harmonic_sum_and_zeros(&self, other: &Self) -> (f64, u32) {
+        let mut harmonic_sum = f64::ZERO;
+        let mut union_zeros = 0_u32;
+
+        for (left, right) in self.registers.iter().zip(other.registers.iter()) {
+            let max_register = core::cmp::max(*left, *right);
+            harmonic_sum += f64::integer_exp2_minus(max_register);
+            union_zeros += u32::from(max_register == 0);
+        }
+
+        (harmonic_sum, union_zeros)
+    }
+
+    /// Empties the counter.
+    fn clear(&mut self) {
+        self.registers.fill(0);
+        self.number_of_zero_registers = 1 << self.exponent;
+        self.harmonic_sum = f64::integer_exp2(self.exponent);
+    }
+
+    /// Returns whether the counter is empty.
+    fn is_empty(&self) -> bool {
+        self.number_of_zero_registers == 1 << self.exponent
+    }
+
+    /// Returns whether the counter is full.
+    fn is_full(&self) -> bool {
+        self.harmonic_sum
+            <= f64::integer_exp2_minus_signed((1_i16 << self.bits) - i16::from(self.exponent) - 1)
+    }
+}
+
+impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>> BitOrAssign<&Self>
+    for DynamicLogLog<Hasher, Builder>
+{
+    fn bitor_assign(&mut self, rhs: &Self) {
+        assert!(
+            self.is_compatible(rhs),
+            "Cannot merge counters with different configurations: P{}, B{} and P{}, B{}.",
+            self.exponent,
+            self.bits,
+            rhs.exponent,
+            rhs.bits
+        );
+        debug_assert!(
+            self.hasher_builder == rhs.hasher_builder,
+            "The counters must hash their elements with the same hashers."
+        );
+
+        for (old_register, rhs_register) in self.registers.iter_mut().zip(rhs.registers.iter()) {
+            if *rhs_register > *old_register {
+                self.harmonic_sum +=
+                    f64::integer_exp2_minus(*rhs_register) - f64::integer_exp2_minus(*old_register);
+                self.number_of_zero_registers -= u32::from(*old_register == 0);
+                *old_register = *rhs_register;
+            }
+        }
+    }
+}
+
+/// Implements the public interface of a dynamic counter wrapping a [`DynamicLogLog`].
+macro_rules! dynamic_impl {
+    ($counter:ident, $static_counter:ident, $correction:ident, $estimate:ident) => {
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction>
+            $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            /// Creates a new empty counter with the provided precision exponent and number of bits per register.
+            ///
+            /// # Arguments
+            /// * `exponent` - The exponent of the number of registers, in the range 4..=18.
+            /// * `bits` - The number of bits per register, in the range 1..=8.
+            ///
+            /// # Errors
+            /// * [`Error::UnsupportedPrecision`] if the exponent is out of range or its feature is not enabled.
+            /// * [`Error::UnsupportedBits`] if the number of bits is out of range.
+            pub fn new(exponent: u8, bits: u8) -> Result<Self, Error> {
+                Self::with_hasher_builder(exponent, bits, Builder::default())
+            }
+
+            #[inline]
+            /// Creates a new empty counter with the provided precision exponent and number of bits per register,
+            /// hashing its elements with the hashers created by the provided builder.
+            ///
+            /// # Arguments
+            /// * `exponent` - The exponent of the number of registers, in the range 4..=18.
+            /// * `bits` - The number of bits per register, in the range 1..=8.
+            /// * `hasher_builder` - The builder of the hashers of the counter.
+            ///
+            /// # Errors
+            /// * [`Error::UnsupportedPrecision`] if the exponent is out of range or its feature is not enabled.
+            /// * [`Error::UnsupportedBits`] if the number of bits is out of range.
+            pub fn with_hasher_builder(
+                exponent: u8,
+                bits: u8,
+                hasher_builder: Builder,
+            ) -> Result<Self, Error> {
+                Ok(Self {
+                    counter: DynamicLogLog::new(exponent, bits, hasher_builder)?,
+                    correction: PhantomData,
+                })
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the builder of the hashers of the counter.
+            pub fn hasher_builder(&self) -> &Builder {
+                &self.counter.hasher_builder
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the exponent of the number of registers.
+            pub fn exponent(&self) -> u8 {
+                self.counter.exponent
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the number of bits per register.
+            pub fn bits(&self) -> u8 {
+                self.counter.bits
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the number of registers of the counter.
+            pub fn number_of_registers(&self) -> usize {
+                self.counter.registers.len()
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the registers of the counter, one per byte.
+            pub fn registers(&self) -> &[u8] {
+                &self.counter.registers
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the value of the register at the given index.
+            ///
+            /// # Panics
+            /// * If the index is not smaller than the number of registers.
+            pub fn get_register(&self, index: usize) -> u8 {
+                self.counter.registers[index]
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the harmonic sum of the registers.
+            pub fn harmonic_sum(&self) -> f64 {
+                self.counter.harmonic_sum
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the number of registers with zero values.
+            pub fn get_number_of_zero_registers(&self) -> usize {
+                self.counter.number_of_zero_registers as usize
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns whether the two counters share the same precision and number of bits,
+            /// and can therefore be merged or compared.
+            pub fn is_compatible(&self, other: &Self) -> bool {
+                self.counter.is_compatible(&other.counter)
+            }
+
+            #[inline]
+            /// Folds the counter into a counter with the provided lower or equal precision exponent.
+            ///
+            /// # Arguments
+            /// * `exponent` - The exponent of the number of registers of the folded counter.
+            ///
+            /// # Errors
+            /// * [`Error::FoldToHigherPrecision`] if the exponent is higher than the current one.
+            /// * [`Error::UnsupportedPrecision`] if the feature of the exponent is not enabled.
+            pub fn fold_to(&self, exponent: u8) -> Result<Self, Error> {
+                Ok(Self {
+                    counter: self.counter.fold_to(exponent)?,
+                    correction: PhantomData,
+                })
+            }
+
+            #[inline]
+            /// Converts the counter into a counter with the provided number of bits per register,
+            /// saturating the register values that do not fit in the new registers.
+            ///
+            /// # Arguments
+            /// * `bits` - The number of bits per register of the converted counter.
+            ///
+            /// # Errors
+            /// * [`Error::UnsupportedBits`] if the number of bits is out of range.
+            pub fn convert_bits(&self, bits: u8) -> Result<Self, Error> {
+                Ok(Self {
+                    counter: self.counter.convert_bits(bits)?,
+                    correction: PhantomData,
+                })
+            }
+
+            #[inline]
+            #[must_use]
+            /// Returns the union of the two counters, folding the one with the higher
+            /// precision to the lower precision of the other one.
+            ///
+            /// # Panics
+            /// * If the two counters have a different number of bits per register.
+            pub fn folded_union(&self, other: &Self) -> Self {
+                let exponent = self.exponent().min(other.exponent());
+                // Folding to a precision that is lower or equal than the current
+                // one of an existing counter cannot fail.
+                self.fold_to(exponent).unwrap() | other.fold_to(exponent).unwrap()
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction> Debug
+            for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+                formatter
+                    .debug_struct(stringify!($counter))
+                    .field("counter", &self.counter)
+                    .finish()
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction> PartialEq
+            for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.counter == other.counter
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction> Eq
+            for $counter<Hasher, Builder, C>
+        {
+        }
+
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                C: $correction,
+            > From<$static_counter<P, B, R, Hasher, Builder, C>> for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn from(counter: $static_counter<P, B, R, Hasher, Builder, C>) -> Self {
+                Self {
+                    counter: DynamicLogLog::from_counter(&counter),
+                    correction: PhantomData,
+                }
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction>
+            BitOrAssign for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.counter |= &rhs.counter;
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction> BitOr
+            for $counter<Hasher, Builder, C>
+        {
+            type Output = Self;
+
+            #[inline]
+            fn bitor(mut self, rhs: Self) -> Self::Output {
+                self.counter |= &rhs.counter;
+                self
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction> MutableSet
+            for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn clear(&mut self) {
+                self.counter.clear();
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction>
+            SetProperties for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn is_empty(&self) -> bool {
+                self.counter.is_empty()
+            }
+
+            #[inline]
+            fn is_full(&self) -> bool {
+                self.counter.is_full()
+            }
+        }
+
+        impl<
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                C: $correction,
+                T: Hash,
+            > ApproximatedSet<T> for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn may_contain(&self, element: &T) -> bool {
+                let (register, index) = self.counter.hash_and_index(element);
+                self.counter.registers[index] >= register
+            }
+        }
+
+        impl<
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                C: $correction,
+                T: Hash,
+            > ExtendableApproximatedSet<T> for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn insert(&mut self, element: &T) -> bool {
+                let (register, index) = self.counter.hash_and_index(element);
+                self.counter
+                    .insert_register_value_and_index(register, index)
+            }
+        }
+
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction>
+            Estimator<f64> for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn estimate_cardinality(&self) -> f64 {
+                $estimate::<C>(
+                    self.counter.exponent,
+                    self.counter.harmonic_sum,
+                    self.counter.number_of_zero_registers,
+                )
+            }
+
+            #[inline]
+            fn estimate_union_cardinality_with_cardinalities(
+                &self,
+                other: &Self,
+                self_cardinality: f64,
+                other_cardinality: f64,
+            ) -> f64 {
+                assert!(
+                    self.is_compatible(other),
+                    "Cannot estimate the union of counters with different configurations."
+                );
+                let (harmonic_sum, number_of_zero_registers) =
+                    self.counter.get_harmonic_sum_and_zeros(&other.counter);
+
+                correct_union_estimate(
+                    self_cardinality,
+                    other_cardinality,
+                    $estimate::<C>(
+                        self.counter.exponent,
+                        harmonic_sum,
+                        number_of_zero_registers,
+                    ),
+                )
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl<Hasher: HasherType, Builder: HasherBuilder<Hasher = Hasher>, C: $correction> Named
+            for $counter<Hasher, Builder, C>
+        {
+            #[inline]
+            fn name(&self) -> String {
+                format!(
+                    "{}<P{}, B{}, Dyn> + {}",
+                    C::estimator_name(),
+                    self.counter.exponent,
+                    self.counter.bits,
+                    type_name::<Hasher>().split("::").last().unwrap()
+                )
+            }
+        }
+    };
+}
+
+#[cfg(feature = "plusplus")]
+#[derive(Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A `HyperLogLog++` counter whose precision and number of bits are chosen at runtime.
+///
+/// Merging or estimating the union of two counters with different configurations panics:
+/// use [`DynamicPlusPlus::is_compatible`] to check beforehand.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// let mut dynamic = DynamicPlusPlus::<twox_hash::XxHash64>::new(8, 6).unwrap();
+/// let mut fixed: PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array> =
+///     Default::default();
+///
+/// for value in 0..1_000_u64 {
+///     dynamic.insert(&value);
+///     fixed.insert(&value);
+/// }
+///
+/// assert_eq!(dynamic.estimate_cardinality(), fixed.estimate_cardinality());
+/// assert_eq!(dynamic, DynamicPlusPlus::from(fixed));
+/// ```
+pub struct DynamicPlusPlus<
+    Hasher: HasherType = twox_hash::XxHash64,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+    C: PlusPlusCorrection = PlusPlusBias,
+> {
+    /// The underlying dynamic counter.
+    counter: DynamicLogLog<Hasher, Builder>,
+    /// The bias correction of the estimates.
+    correction: PhantomData<C>,
+}
+
+#[cfg(feature = "plusplus")]
+dynamic_impl!(
+    DynamicPlusPlus,
+    PlusPlus,
+    PlusPlusCorrection,
+    plusplus_estimate
+);
+
+#[cfg(feature = "beta")]
+#[derive(Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A [`LogLogBeta`] counter whose precision and number of bits are chosen at runtime.
+///
+/// Merging or estimating the union of two counters with different configurations panics:
+/// use [`DynamicLogLogBeta::is_compatible`] to check beforehand.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hyperloglog_rs::prelude::*;
+///
+/// let mut dynamic = DynamicLogLogBeta::<twox_hash::XxHash64>::new(6, 5).unwrap();
+/// let mut fixed: LogLogBeta<Precision6, Bits5, <Precision6 as ArrayRegister<Bits5>>::Packed> =
+///     Default::default();
+///
+/// for value in 0..1_000_u64 {
+///     dynamic.insert(&value);
+///     fixed.insert(&value);
+/// }
+///
+/// assert_eq!(dynamic.estimate_cardinality(), fixed.estimate_cardinality());
+/// assert_eq!(
+///     DynamicLogLogBeta::<twox_hash::XxHash64>::new(6, 9),
+///     Err(Error::UnsupportedBits(9))
+/// );
+/// ```
+pub struct DynamicLogLogBeta<
+    Hasher: HasherType = twox_hash::XxHash64,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+    C: BetaCorrection = BetaBias,
+> {
+    /// The underlying dynamic counter.
+    counter: DynamicLogLog<Hasher, Builder>,
+    /// The bias correction of the estimates.
+    correction: PhantomData<C>,
+}
+
+#[cfg(feature = "beta")]
+dynamic_impl!(DynamicLogLogBeta, LogLogBeta, BetaCorrection, beta_estimate);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsupported_configurations() {
+        assert_eq!(
+            DynamicLogLog::<twox_hash::XxHash64>::new(3, 6, UnseededBuilder::default())
+                .unwrap_err(),
+            Error::UnsupportedPrecision(3)
+        );
+        assert_eq!(
+            DynamicLogLog::<twox_hash::XxHash64>::new(19, 6, UnseededBuilder::default())
+                .unwrap_err(),
+            Error::UnsupportedPrecision(19)
+        );
+        #[cfg(feature = "precision_4")]
+        {
+            assert_eq!(
+                DynamicLogLog::<twox_hash::XxHash64>::new(4, 0, UnseededBuilder::default())
+                    .unwrap_err(),
+                Error::UnsupportedBits(0)
+            );
+            assert_eq!(
+                DynamicLogLog::<twox_hash::XxHash64>::new(4, 9, UnseededBuilder::default())
+                    .unwrap_err(),
+                Error::UnsupportedBits(9)
+            );
+            assert!(
+                DynamicLogLog::<twox_hash::XxHash64>::new(4, 1, UnseededBuilder::default()).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "precision_4")]
+    fn test_clear_and_properties() {
+        let mut counter =
+            DynamicLogLog::<twox_hash::XxHash64>::new(4, 6, UnseededBuilder::default()).unwrap();
+        assert!(counter.is_empty());
+        assert!(!counter.is_full());
+        let (register, index) = counter.split_hash(0xDEAD_BEEF);
+        counter.insert_register_value_and_index(register, index);
+        assert!(!counter.is_empty());
+        counter.clear();
+        assert!(counter.is_empty());
+        assert_eq!(counter.harmonic_sum.to_bits(), 16.0_f64.to_bits());
+    }
+}
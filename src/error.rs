@@ -0,0 +1,246 @@
+//! Submodule providing the error type returned by the fallible operations of the crate.
+use core::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+/// Errors that may be returned by the fallible operations of the crate.
+pub enum Error {
+    /// The requested precision exponent is not supported, either because it is out of
+    /// the range 4..=18 or because the corresponding `precision_N` feature is not enabled.
+    UnsupportedPrecision(u8),
+    /// The requested number of bits per register is not supported, i.e. it is not in the range 1..=8.
+    UnsupportedBits(u8),
+    /// The serialized counter does not start with the expected magic bytes.
+    InvalidMagic,
+    /// The serialized counter was written with an unsupported version of the binary format.
+    UnsupportedVersion(u8),
+    /// The serialized counter does not have the expected length in bytes.
+    UnexpectedLength {
+        /// The expected length.
+        expected: usize,
+        /// The length that was found.
+        found: usize,
+    },
+    /// The provided buffer is too small to contain the serialized counter.
+    BufferTooSmall {
+        /// The number of bytes required.
+        required: usize,
+        /// The number of bytes available.
+        available: usize,
+    },
+    /// The checksum of the serialized counter does not match its content.
+    ChecksumMismatch,
+    /// The serialized counter sets flags that are not assigned by the supported version of the
+    /// binary format.
+    UnknownFlags(u8),
+    /// The serialized counter sets the reserved bytes, which the supported version of the binary
+    /// format always leaves to zero.
+    NonZeroReserved(u16),
+    /// The serialized counter was written by a different estimator.
+    EstimatorMismatch {
+        /// The identifier of the estimator being loaded.
+        expected: u8,
+        /// The identifier of the estimator that wrote the counter.
+        found: u8,
+    },
+    /// The serialized counter has a different precision.
+    PrecisionMismatch {
+        /// The precision of the counter being loaded.
+        expected: u8,
+        /// The precision of the serialized counter.
+        found: u8,
+    },
+    /// The serialized counter has a different number of bits per register.
+    BitsMismatch {
+        /// The number of bits of the counter being loaded.
+        expected: u8,
+        /// The number of bits of the serialized counter.
+        found: u8,
+    },
+    /// The serialized counter has a different register layout, i.e. packed or not packed.
+    LayoutMismatch {
+        /// Whether the registers of the counter being loaded are packed.
+        expected_packed: bool,
+    },
+    /// The serialized counter was written using a different hasher.
+    HasherMismatch {
+        /// The identifier of the hasher of the counter being loaded.
+        expected: u64,
+        /// The identifier of the hasher of the serialized counter.
+        found: u64,
+    },
+    /// The serialized counter was written with hashers seeded differently, i.e. seeded or
+    /// not seeded.
+    SeedMismatch {
+        /// Whether the hashers of the counter being loaded are seeded.
+        expected_seeded: bool,
+    },
+    /// The serialized counter stores composite hashes of a different number of bits.
+    CompositeHashMismatch {
+        /// The number of bits of the composite hashes of the counter being loaded.
+        expected: u8,
+        /// The number of bits of the composite hashes of the serialized counter.
+        found: u8,
+    },
+    /// The serialized counter stores more hashes than its registers can hold.
+    InvalidNumberOfHashes {
+        /// The maximal number of hashes that may be stored.
+        capacity: usize,
+        /// The number of hashes of the serialized counter.
+        found: usize,
+    },
+    /// The serialized counter is in hybrid mode, which only the
+    /// [`Hybrid`](crate::prelude::Hybrid) counters support.
+    UnsupportedHybridMode,
+    /// The serialized counter in hybrid mode stores a composite hash that is not valid, or
+    /// that does not follow the previous one in strictly increasing order.
+    InvalidCompositeHash(u64),
+    /// The provided registers do not match the number of registers of the counter.
+    UnexpectedNumberOfRegisters {
+        /// The number of registers of the counter.
+        expected: usize,
+        /// The number of registers that was provided.
+        found: usize,
+    },
+    /// A register holds a value that the counter cannot produce.
+    InvalidRegisterValue {
+        /// The index of the register.
+        index: usize,
+        /// The value of the register.
+        value: u8,
+        /// The largest value the register may hold.
+        maximum: u8,
+    },
+    /// A counter cannot be folded to a precision higher than its own.
+    FoldToHigherPrecision {
+        /// The precision of the counter being folded.
+        from: u8,
+        /// The requested precision.
+        to: u8,
+    },
+    /// Too many counters were provided to an estimator whose cost grows exponentially with them.
+    TooManyCounters {
+        /// The largest number of counters supported by the estimator.
+        maximum: usize,
+        /// The number of counters that was provided.
+        found: usize,
+    },
+}
+
+impl Display for Error {
+    #[inline]
+    #[expect(
+        clippy::too_many_lines,
+        reason = "The message of each variant is written in its own arm"
+    )]
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::UnsupportedPrecision(exponent) => write!(
+                formatter,
+                "The precision {exponent} is not supported: it must be in the range 4..=18 and the feature `precision_{exponent}` must be enabled."
+            ),
+            Error::UnsupportedBits(bits) => write!(
+                formatter,
+                "The number of bits {bits} is not supported: it must be in the range 1..=8."
+            ),
+            Error::InvalidMagic => write!(
+                formatter,
+                "The provided bytes do not start with the expected magic bytes."
+            ),
+            Error::UnsupportedVersion(version) => write!(
+                formatter,
+                "The binary format version {version} is not supported."
+            ),
+            Error::UnexpectedLength { expected, found } => write!(
+                formatter,
+                "Expected {expected} bytes, but found {found}."
+            ),
+            Error::BufferTooSmall {
+                required,
+                available,
+            } => write!(
+                formatter,
+                "The buffer has {available} bytes, but {required} are required."
+            ),
+            Error::ChecksumMismatch => write!(
+                formatter,
+                "The checksum of the provided bytes does not match their content."
+            ),
+            Error::UnknownFlags(flags) => write!(
+                formatter,
+                "The flags {flags:#010b} are not supported by the binary format."
+            ),
+            Error::NonZeroReserved(reserved) => write!(
+                formatter,
+                "The reserved bytes {reserved:#06x} must be zero."
+            ),
+            Error::EstimatorMismatch { expected, found } => write!(
+                formatter,
+                "Expected a counter written by estimator {expected}, but found estimator {found}."
+            ),
+            Error::PrecisionMismatch { expected, found } => write!(
+                formatter,
+                "Expected a counter with precision {expected}, but found precision {found}."
+            ),
+            Error::BitsMismatch { expected, found } => write!(
+                formatter,
+                "Expected a counter with {expected} bits per register, but found {found}."
+            ),
+            Error::LayoutMismatch { expected_packed } => write!(
+                formatter,
+                "Expected a counter with {} registers, but found {} registers.",
+                if *expected_packed { "packed" } else { "not packed" },
+                if *expected_packed { "not packed" } else { "packed" }
+            ),
+            Error::HasherMismatch { expected, found } => write!(
+                formatter,
+                "Expected a counter written with hasher {expected:#018x}, but found hasher {found:#018x}."
+            ),
+            Error::SeedMismatch { expected_seeded } => write!(
+                formatter,
+                "Expected a counter written with {} hashers, but found {} hashers.",
+                if *expected_seeded { "seeded" } else { "unseeded" },
+                if *expected_seeded { "unseeded" } else { "seeded" }
+            ),
+            Error::CompositeHashMismatch { expected, found } => write!(
+                formatter,
+                "Expected composite hashes of {expected} bits, but found {found} bits."
+            ),
+            Error::InvalidNumberOfHashes { capacity, found } => write!(
+                formatter,
+                "The counter stores {found} hashes, but it can hold at most {capacity}."
+            ),
+            Error::UnsupportedHybridMode => write!(
+                formatter,
+                "The counter is in hybrid mode, which only hybrid counters support."
+            ),
+            Error::InvalidCompositeHash(hash) => write!(
+                formatter,
+                "The composite hash {hash:#x} is not valid or is not sorted in strictly increasing order."
+            ),
+            Error::UnexpectedNumberOfRegisters { expected, found } => write!(
+                formatter,
+                "Expected {expected} registers, but found {found}."
+            ),
+            Error::InvalidRegisterValue {
+                index,
+                value,
+                maximum,
+            } => write!(
+                formatter,
+                "The register {index} has value {value}, but the largest possible value is {maximum}."
+            ),
+            Error::FoldToHigherPrecision { from, to } => write!(
+                formatter,
+                "Cannot fold a counter with precision {from} to the higher precision {to}."
+            ),
+            Error::TooManyCounters { maximum, found } => write!(
+                formatter,
+                "Expected at most {maximum} counters, but found {found}."
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
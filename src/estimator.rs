@@ -38,6 +38,24 @@ pub trait ExtendableApproximatedSet<T: Hash> {
     }
 }
 
+/// Trait for a set that can be extended with pre-computed 64-bit hashes, skipping the hasher.
+///
+/// The hashes must be computed with a good 64-bit hash function: the counters use the lowest
+/// bits of the hash as the index of the register and its leading zeros as the register value,
+/// exactly as they do with the hashes they compute themselves.
+pub trait ExtendableHashes {
+    /// Insert a pre-computed hash into the set and return whether the set has changed.
+    fn insert_hash(&mut self, hash: u64) -> bool;
+
+    #[inline]
+    /// Extend the set with a batch of pre-computed hashes.
+    fn extend_hashes(&mut self, hashes: &[u64]) {
+        for &hash in hashes {
+            self.insert_hash(hash);
+        }
+    }
+}
+
 /// Trait for a cardinality estimator.
 pub trait Estimator<F: Number>: Sized + Send + Sync {
     /// Estimates the cardinality.
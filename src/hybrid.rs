@@ -1,13 +1,14 @@
 //! Marker struct for the hybrid approach, that keeps the hash explicit up until they fit into the registers.
 
 use crate::prelude::*;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+use alloc::vec::Vec;
 use core::cmp::Ordering;
-use core::hash::Hash;
+use core::hash::{BuildHasher, Hash};
 use core::marker::PhantomData;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A struct representing the hybrid for approximate set cardinality estimation,
 /// where the hash values are kept explicit up until they fit into the registers.
 pub struct Hybrid<H, CH = u32> {
@@ -17,6 +18,139 @@ pub struct Hybrid<H, CH = u32> {
     composite_hash: PhantomData<CH>,
 }
 
+#[inline]
+/// Returns the composite hash if it may follow the previous one in the sorted hashes of a
+/// counter in hybrid or sparse mode, i.e. if it is strictly larger, fits in the composite hash
+/// word and encodes a non-zero register value.
+///
+/// # Arguments
+/// * `hash` - The composite hash, as read from a serialized counter.
+/// * `previous_hash` - The previous composite hash, or zero for the first one.
+pub(crate) fn next_composite_hash<P: Precision, B: Bits, CH: CompositeHash<P, B>>(
+    hash: u64,
+    previous_hash: u64,
+) -> Option<CH::Word> {
+    CH::Word::try_from_u64(hash).ok().filter(|&composite_hash| {
+        hash > previous_hash && hash & !CH::MASK == 0 && CH::decode(composite_hash).0 > 0
+    })
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+/// Wrapper to serialize the sorted composite hashes of a counter in hybrid mode.
+struct SortedHashes<'counter, H, CH>(&'counter H, PhantomData<CH>);
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<H: Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>> serde::Serialize
+    for SortedHashes<'_, H, CH>
+{
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter_sorted_hashes().map(Into::<u64>::into))
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[derive(serde::Deserialize)]
+#[serde(rename = "Hybrid")]
+/// The serialized state of an [`Hybrid`] counter.
+enum HybridState<H> {
+    /// The counter is in hybrid mode, and stores the sorted composite hashes.
+    Hashes {
+        /// The number of composite hashes stored in the counter.
+        number_of_hashes: usize,
+        /// The composite hashes, sorted in strictly increasing order.
+        hashes: Vec<u64>,
+        #[serde(default)]
+        /// The seed of the hashers, if they are seeded.
+        seed: Option<u64>,
+    },
+    /// The counter has switched to the registers.
+    Registers(H),
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<H: Hybridazable<CH> + serde::Serialize, CH: CompositeHash<H::Precision, H::Bits>>
+    serde::Serialize for Hybrid<H, CH>
+{
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStructVariant;
+        if self.is_hybrid() {
+            let seed = self.inner.hasher_builder().seed();
+            let mut state = serializer.serialize_struct_variant(
+                "Hybrid",
+                0,
+                "Hashes",
+                2 + usize::from(seed.is_some()),
+            )?;
+            state.serialize_field("number_of_hashes", &self.inner.number_of_hashes())?;
+            state.serialize_field("hashes", &SortedHashes::<H, CH>(&self.inner, PhantomData))?;
+            match seed {
+                Some(seed) => state.serialize_field("seed", &seed)?,
+                None => state.skip_field("seed")?,
+            }
+            state.end()
+        } else {
+            serializer.serialize_newtype_variant("Hybrid", 1, "Registers", &self.inner)
+        }
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl<
+        'de,
+        H: Hybridazable<CH> + serde::Deserialize<'de>,
+        CH: CompositeHash<H::Precision, H::Bits>,
+    > serde::Deserialize<'de> for Hybrid<H, CH>
+{
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{Error, Unexpected};
+
+        let inner = match HybridState::<H>::deserialize(deserializer)? {
+            HybridState::Registers(inner) => inner,
+            HybridState::Hashes {
+                number_of_hashes,
+                hashes,
+                seed,
+            } => {
+                let hasher_builder = H::HasherBuilder::from_seed(seed).ok_or_else(|| {
+                    D::Error::custom(crate::error::Error::SeedMismatch {
+                        expected_seeded: seed.is_none(),
+                    })
+                })?;
+                let mut inner = H::with_hasher_builder(hasher_builder);
+                inner.clear_words();
+                if number_of_hashes != hashes.len() || number_of_hashes > inner.capacity() {
+                    return Err(D::Error::invalid_length(
+                        hashes.len(),
+                        &"as many composite hashes as the declared number of hashes, within the capacity of the counter",
+                    ));
+                }
+                let mut previous_hash = 0;
+                for hash in hashes {
+                    let composite_hash =
+                        next_composite_hash::<H::Precision, H::Bits, CH>(hash, previous_hash)
+                            .ok_or_else(|| {
+                                D::Error::invalid_value(
+                                    Unexpected::Unsigned(hash),
+                                    &"a strictly increasing sequence of valid composite hashes",
+                                )
+                            })?;
+                    previous_hash = hash;
+                    inner.insert_composite_hash(composite_hash);
+                }
+                inner
+            }
+        };
+
+        Ok(Self {
+            inner,
+            composite_hash: PhantomData,
+        })
+    }
+}
+
 impl<H: Hybridazable<CH>, CH> Default for Hybrid<H, CH>
 where
     H: Default,
@@ -82,9 +216,68 @@ impl<H: MutableSet + Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>>
     }
 }
 
+/// The number of elements hashed and merged at once by the batched insertion in hybrid mode.
+const HYBRID_BATCH_SIZE: usize = 256;
+
+impl<H: ExtendableHashes + Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>>
+    Hybrid<H, CH>
+{
+    /// Inserts the provided hashes in batches while the counter is in hybrid mode,
+    /// returning once the hashes are exhausted or the counter has been dehybridized.
+    ///
+    /// # Arguments
+    /// * `hashes` - The iterator of the hashes to be inserted.
+    ///
+    /// # Implementative details
+    /// Inserting the composite hashes one by one shifts the sorted list of hashes at
+    /// each insertion, which is quadratic in the number of hashes. Instead, each batch
+    /// of hashes is encoded, sorted and deduplicated, and then merged with the stored
+    /// hashes in a single linear pass. When the merged hashes would exceed the capacity,
+    /// the counter is dehybridized and the hashes of the batch are inserted in the registers.
+    fn extend_hybrid_hashes<I: Iterator<Item = u64>>(&mut self, hashes: &mut I) {
+        let mut batch = [0_u64; HYBRID_BATCH_SIZE];
+        let mut composite_hashes = [CH::Word::ZERO; HYBRID_BATCH_SIZE];
+
+        while self.inner.is_hybrid() {
+            let mut batch_size = 0;
+            for (slot, hash) in batch.iter_mut().zip(hashes.by_ref()) {
+                *slot = hash;
+                batch_size += 1;
+            }
+            if batch_size == 0 {
+                return;
+            }
+
+            for (composite_hash, &hash) in composite_hashes.iter_mut().zip(&batch[..batch_size]) {
+                let (register, index) = H::split_hash(hash);
+                *composite_hash = CH::encode(register, index, hash);
+            }
+            let composite_hashes = &mut composite_hashes[..batch_size];
+            composite_hashes.sort_unstable();
+
+            // We deduplicate the sorted composite hashes in place.
+            let mut number_of_unique_hashes = 1;
+            for position in 1..batch_size {
+                if composite_hashes[position] != composite_hashes[number_of_unique_hashes - 1] {
+                    composite_hashes[number_of_unique_hashes] = composite_hashes[position];
+                    number_of_unique_hashes += 1;
+                }
+            }
+
+            if !self
+                .inner
+                .insert_sorted_composite_hashes(&composite_hashes[..number_of_unique_hashes])
+            {
+                self.inner.dehybridize();
+                self.inner.extend_hashes(&batch[..batch_size]);
+            }
+        }
+    }
+}
+
 impl<
         T: Hash,
-        H: ExtendableApproximatedSet<T> + Hybridazable<CH>,
+        H: ExtendableApproximatedSet<T> + ExtendableHashes + Hybridazable<CH>,
         CH: CompositeHash<H::Precision, H::Bits>,
     > ExtendableApproximatedSet<T> for Hybrid<H, CH>
 {
@@ -96,6 +289,109 @@ impl<
             self.inner.insert(element)
         }
     }
+
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut elements = iter.into_iter();
+        if self.inner.is_hybrid() {
+            let hasher_builder = self.inner.hasher_builder().clone();
+            self.extend_hybrid_hashes(
+                &mut elements
+                    .by_ref()
+                    .map(|element| hasher_builder.hash_one(&element)),
+            );
+        }
+        for element in elements {
+            self.inner.insert(&element);
+        }
+    }
+}
+
+impl<H: ExtendableHashes + Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>>
+    ExtendableHashes for Hybrid<H, CH>
+{
+    #[inline]
+    fn insert_hash(&mut self, hash: u64) -> bool {
+        if self.inner.is_hybrid() {
+            if self.inner.number_of_hashes() == self.inner.capacity() {
+                self.inner.dehybridize();
+                return self.inner.insert_hash(hash);
+            }
+            let (register, index) = H::split_hash(hash);
+            self.inner
+                .insert_composite_hash(CH::encode(register, index, hash))
+        } else {
+            self.inner.insert_hash(hash)
+        }
+    }
+
+    #[inline]
+    fn extend_hashes(&mut self, hashes: &[u64]) {
+        // We merge the hashes in batches while in hybrid mode, and we
+        // switch to the batched insertion once the counter is dehybridized.
+        let mut hashes = hashes.iter();
+        self.extend_hybrid_hashes(&mut hashes.by_ref().copied());
+        if !self.inner.is_hybrid() {
+            self.inner.extend_hashes(hashes.as_slice());
+        }
+    }
+}
+
+impl<H: BitOrAssign + Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>> BitOrAssign
+    for Hybrid<H, CH>
+{
+    #[inline]
+    /// Merges the provided counter into the current one.
+    ///
+    /// # Implementative details
+    /// When both counters are in hybrid mode and the union of their composite hashes
+    /// fits in the capacity of the counter, the two sorted lists of hashes are merged
+    /// and the counter remains in hybrid mode, so its estimate is still exact. Otherwise,
+    /// the counters in hybrid mode are folded into their registers before merging them.
+    fn bitor_assign(&mut self, mut rhs: Self) {
+        debug_assert!(
+            self.inner.hasher_builder() == rhs.inner.hasher_builder(),
+            "The counters must hash their elements with the same hashers."
+        );
+
+        if self.is_hybrid() && rhs.is_hybrid() {
+            let number_of_unique_hashes = unique_count_from_sorted_iterators(
+                self.inner.iter_sorted_hashes(),
+                rhs.inner.iter_sorted_hashes(),
+            );
+            if number_of_unique_hashes as usize <= self.capacity() {
+                // We rebuild the list of hashes in increasing order, so that each
+                // composite hash is appended at the end of the list.
+                let mut merged = H::with_hasher_builder(self.inner.hasher_builder().clone());
+                merged.clear_words();
+                merge_sorted_iterators(
+                    self.inner.iter_sorted_hashes(),
+                    rhs.inner.iter_sorted_hashes(),
+                    |composite_hash| {
+                        merged.insert_composite_hash(composite_hash);
+                    },
+                );
+                self.inner = merged;
+                return;
+            }
+        }
+
+        self.inner.dehybridize();
+        rhs.inner.dehybridize();
+        self.inner |= rhs.inner;
+    }
+}
+
+impl<H: BitOrAssign + Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>> BitOr
+    for Hybrid<H, CH>
+{
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self {
+        self.bitor_assign(rhs);
+        self
+    }
 }
 
 #[allow(unsafe_code)]
@@ -159,6 +455,41 @@ pub fn unique_count_from_sorted_iterators<
     left_length + right_length - intersection
 }
 
+#[inline]
+/// Calls the provided closure on the unique values of the union of two sorted iterators,
+/// in ascending order.
+///
+/// # Arguments
+/// * `left` - The first iterator, sorted in ascending order.
+/// * `right` - The second iterator, sorted in ascending order.
+/// * `callback` - The closure to call on each unique value.
+fn merge_sorted_iterators<T: Ord, I: Iterator<Item = T>, J: Iterator<Item = T>>(
+    left: I,
+    right: J,
+    mut callback: impl FnMut(T),
+) {
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+    loop {
+        let value = match (left.peek(), right.peek()) {
+            (Some(left_value), Some(right_value)) => match left_value.cmp(right_value) {
+                Ordering::Less => left.next(),
+                Ordering::Greater => right.next(),
+                Ordering::Equal => {
+                    right.next();
+                    left.next()
+                }
+            },
+            (Some(_), None) => left.next(),
+            (None, Some(_)) => right.next(),
+            (None, None) => return,
+        };
+        if let Some(value) = value {
+            callback(value);
+        }
+    }
+}
+
 #[inline]
 /// Returns the union estimation from a sorted iterator and a counter.
 ///
@@ -254,6 +585,27 @@ pub trait Hybridazable<CH: CompositeHash<Self::Precision, Self::Bits>>: HyperLog
 
     /// Inserts a value into the counter.
     fn hybrid_insert<T: Hash>(&mut self, value: &T) -> bool;
+
+    /// Inserts an already encoded composite hash into the counter, returning whether it was new.
+    ///
+    /// # Arguments
+    /// * `composite_hash` - The composite hash to be inserted.
+    ///
+    /// # Implementation details
+    /// The counter must be in hybrid mode and must not be full: this method does not
+    /// dehybridize the counter, as the original hash is not available anymore.
+    fn insert_composite_hash(&mut self, composite_hash: CH::Word) -> bool;
+
+    /// Merges a batch of already encoded composite hashes into the counter in a single pass,
+    /// returning whether they fit in its capacity.
+    ///
+    /// # Arguments
+    /// * `composite_hashes` - The composite hashes to be inserted, sorted in strictly increasing order.
+    ///
+    /// # Implementation details
+    /// The counter must be in hybrid mode. If the union of the stored hashes and of the
+    /// provided ones exceeds the capacity, the counter is left unchanged.
+    fn insert_sorted_composite_hashes(&mut self, composite_hashes: &[CH::Word]) -> bool;
 }
 
 #[cfg(feature = "std")]
@@ -266,6 +618,22 @@ impl<H: Named, CH: Default + Named> Named for Hybrid<H, CH> {
 
 impl<H: Hybridazable<CH> + HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>>  Hybrid<H, CH>
 {
+    #[inline]
+    #[must_use]
+    /// Returns a new empty counter in hybrid mode, hashing its elements with the hashers
+    /// created by the provided builder.
+    ///
+    /// # Arguments
+    /// * `hasher_builder` - The builder of the hashers of the counter.
+    pub fn with_hasher_builder(hasher_builder: H::HasherBuilder) -> Self {
+        let mut inner = H::with_hasher_builder(hasher_builder);
+        inner.clear_words();
+        Self {
+            inner,
+            composite_hash: PhantomData,
+        }
+    }
+
     #[inline]
     /// Returns whether the counter is in hybrid mode.
     pub fn is_hybrid(&self) -> bool {
@@ -279,6 +647,83 @@ impl<H: Hybridazable<CH> + HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>
     }
 }
 
+impl<H: BinaryFormat + Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>> BinaryFormat
+    for Hybrid<H, CH>
+{
+    const ESTIMATOR: u8 = H::ESTIMATOR | HYBRID_ESTIMATOR_FLAG;
+
+    #[inline]
+    fn header(&self) -> BinaryHeader {
+        BinaryHeader {
+            estimator: Self::ESTIMATOR,
+            composite_hash_bits: CH::NUMBER_OF_BITS,
+            ..self.inner.header()
+        }
+    }
+
+    #[inline]
+    fn words(&self) -> &[u64] {
+        self.inner.words()
+    }
+
+    #[inline]
+    fn from_header_and_words(header: &BinaryHeader, words: &[u8]) -> Result<Self, Error> {
+        if header.estimator != Self::ESTIMATOR {
+            return Err(Error::EstimatorMismatch {
+                expected: Self::ESTIMATOR,
+                found: header.estimator,
+            });
+        }
+        if header.composite_hash_bits != CH::NUMBER_OF_BITS {
+            return Err(Error::CompositeHashMismatch {
+                expected: CH::NUMBER_OF_BITS,
+                found: header.composite_hash_bits,
+            });
+        }
+        // The number of hashes is checked before loading them, as the counter reads as many.
+        let capacity = H::new_hybrid().capacity();
+        if header.hybrid && header.number_of_hashes as usize > capacity {
+            return Err(Error::InvalidNumberOfHashes {
+                capacity,
+                found: header.number_of_hashes as usize,
+            });
+        }
+        let loaded = H::from_header_and_words(
+            &BinaryHeader {
+                estimator: H::ESTIMATOR,
+                composite_hash_bits: 0,
+                hybrid_allowed: true,
+                ..*header
+            },
+            words,
+        )?;
+        if !loaded.is_hybrid() {
+            return Ok(Self {
+                inner: loaded,
+                composite_hash: PhantomData,
+            });
+        }
+
+        // As when deserializing the counter with serde, the composite hashes are validated
+        // and inserted into an empty counter, so that the unused words are left cleared.
+        let mut inner = H::with_hasher_builder(loaded.hasher_builder().clone());
+        inner.clear_words();
+        let mut previous_hash = 0;
+        for composite_hash in loaded.iter_sorted_hashes() {
+            let hash: u64 = composite_hash.into();
+            let composite_hash =
+                next_composite_hash::<H::Precision, H::Bits, CH>(hash, previous_hash)
+                    .ok_or(Error::InvalidCompositeHash(hash))?;
+            previous_hash = hash;
+            inner.insert_composite_hash(composite_hash);
+        }
+        Ok(Self {
+            inner,
+            composite_hash: PhantomData,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test_hybrid_propertis {
     use super::*;
@@ -313,8 +758,12 @@ mod test_hybrid_propertis {
 
             let estimated_cardinality = hybrid.estimate_cardinality();
             let number_of_hashes = hybrid.inner.number_of_hashes();
-            assert_eq!(estimated_cardinality as usize, number_of_hashes);
-            assert!(estimated_cardinality <= count as f64);
+            assert_eq!(
+                estimated_cardinality.to_bits(),
+                W::collision_corrected_cardinality(u32::try_from(number_of_hashes).unwrap())
+                    .to_bits()
+            );
+            assert!(estimated_cardinality >= number_of_hashes as f64);
 
             let error = (estimated_cardinality - count as f64).abs();
             normalized_error += error / count as f64;
@@ -365,9 +814,13 @@ where
         if self.inner.is_hybrid() {
             // We can safely convert this usize to an u32 because the maximal value that
             // can be stored in an Hybrid counter with the largest possible number of words
-            // using the largest possible bit size (8) is 2**21 / 64 = 2**15, which fits
-            // cosily in an u16.
-            f64::from(u16::try_from(self.inner.number_of_hashes()).unwrap())
+            // using the largest possible bit size (8) and the narrowest composite hashes
+            // (24 bits) is 2**21 / 24, which is less than 2**17 and therefore does not fit
+            // in an u16, but cosily fits in an u32. Since distinct elements may share their
+            // composite hash, the number of hashes is corrected for the expected collisions.
+            CH::collision_corrected_cardinality(
+                u32::try_from(self.inner.number_of_hashes()).unwrap(),
+            )
         } else {
             self.inner.estimate_cardinality()
         }
@@ -390,8 +843,8 @@ where
             (true, true) => {
                 // In the case where both counters are in hybrid mode, we can
                 // simply iterate on the two sorted hash arrays and determine the number
-                // of unique hashes.
-                f64::from(unique_count_from_sorted_iterators(
+                // of unique hashes, which we correct for the expected collisions.
+                CH::collision_corrected_cardinality(unique_count_from_sorted_iterators(
                     self.inner.iter_sorted_hashes(),
                     other.inner.iter_sorted_hashes(),
                 ))
@@ -416,6 +869,73 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+impl<
+        H: Clone + Correction + EstimatorWithBounds + Hybridazable<CH> + Default,
+        CH: CompositeHash<H::Precision, H::Bits>,
+    > EstimatorWithBounds for Hybrid<H, CH>
+where
+    Hybrid<H, CH>: Default,
+{
+    #[inline]
+    fn estimate_with_bounds(&self, confidence: f64) -> EstimateWithBounds {
+        if self.inner.is_hybrid() {
+            // The estimate only errs when distinct hashes share their composite hash.
+            let estimate = self.estimate_cardinality();
+            EstimateWithBounds::new(
+                estimate,
+                CH::collision_corrected_standard_error(estimate),
+                two_sided_quantile(confidence),
+                f64::INFINITY,
+            )
+        } else {
+            self.inner.estimate_with_bounds(confidence)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<
+        H: Clone + Correction + SetEstimatorWithBounds + Hybridazable<CH> + Default,
+        CH: CompositeHash<H::Precision, H::Bits>,
+    > SetEstimatorWithBounds for Hybrid<H, CH>
+where
+    Hybrid<H, CH>: Default,
+{
+    #[inline]
+    fn estimate_regions_covariance(
+        &self,
+        other: &Self,
+        cardinalities: [f64; 3],
+    ) -> Option<[[f64; 3]; 3]> {
+        match (self.is_hybrid(), other.is_hybrid()) {
+            (true, true) => {
+                // The estimates of the two counters and of their union only err when distinct
+                // hashes share their composite hash, and we neglect the correlation of these
+                // collisions, as they are rare in hybrid mode.
+                let mut covariance = [[0.0; 3]; 3];
+                for (index, cardinality) in cardinalities.into_iter().enumerate() {
+                    covariance[index][index] =
+                        CH::collision_corrected_standard_error(cardinality.max(0.0)).powi(2);
+                }
+                Some(inclusion_exclusion_covariance(&covariance))
+            }
+            (false, false) => self
+                .inner
+                .estimate_regions_covariance(&other.inner, cardinalities),
+            _ => {
+                // The union is estimated from the registers, so we treat the counter in hybrid
+                // mode as a regular counter, which may only widen the intervals.
+                let mut left = self.inner.clone();
+                let mut right = other.inner.clone();
+                left.dehybridize();
+                right.dehybridize();
+                left.estimate_regions_covariance(&right, cardinalities)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod tests {
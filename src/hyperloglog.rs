@@ -1,6 +1,7 @@
 //! The `hyperloglog` module contains the [`HyperLogLog`] trait that defines the interface for [`HyperLogLog`] counters.
+use crate::likelihood::{maximum_likelihood_estimate, relative_error_limit};
 use crate::prelude::*;
-use core::hash::{Hash, Hasher};
+use core::hash::{BuildHasher, Hash};
 
 /// Trait for [`HyperLogLog`] counters.
 pub trait HyperLogLog:
@@ -23,12 +24,18 @@ pub trait HyperLogLog:
     /// The hasher used to hash the elements.
     type Hasher: HasherType + Default;
 
+    /// The builder of the hashers used to hash the elements.
+    type HasherBuilder: HasherBuilder<Hasher = Self::Hasher>;
+
     /// The type of the registers of the [`HyperLogLog`] counter.
     type Registers: Registers<Self::Precision, Self::Bits>;
 
     /// Returns a reference to the registers of the [`HyperLogLog`] counter.
     fn registers(&self) -> &Self::Registers;
 
+    /// Returns a reference to the builder of the hashers of the [`HyperLogLog`] counter.
+    fn hasher_builder(&self) -> &Self::HasherBuilder;
+
     /// Returns the harmonic sum of the registers.
     fn harmonic_sum(&self) -> f64;
 
@@ -59,6 +66,71 @@ pub trait HyperLogLog:
     /// ```
     fn get_number_of_zero_registers(&self) -> <Self::Precision as Precision>::NumberOfRegisters;
 
+    #[inline]
+    /// Returns the histogram of the values of the registers, i.e. the number of registers
+    /// holding each of the values from zero to `2^BITS - 1`.
+    ///
+    /// # Implementative details
+    /// With the `register_histogram` feature, the histogram is maintained incrementally as the
+    /// registers are updated and it is returned without reading the registers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll =
+    ///     PlusPlus::<Precision6, Bits5, <Precision6 as ArrayRegister<Bits5>>::Array>::default();
+    /// hll.insert(&1);
+    /// hll.insert(&2);
+    /// hll.insert(&3);
+    ///
+    /// let histogram = hll.register_histogram();
+    /// assert_eq!(histogram.len(), 32);
+    /// assert_eq!(histogram[0], 61);
+    /// assert_eq!(histogram.iter().sum::<u32>(), 64);
+    /// ```
+    fn register_histogram(&self) -> <Self::Bits as Bits>::Histogram {
+        self.registers().register_histogram()
+    }
+
+    #[inline]
+    /// Returns the maximum likelihood estimate of the cardinality by Ertl, computed from the
+    /// histogram of the values of the registers.
+    ///
+    /// # Implementative details
+    /// The likelihood equation is solved with the secant method, which stops once the relative
+    /// change of the estimate is at most `10^-ERROR / sqrt(m)`, where `m` is the number of
+    /// registers. The estimator requires neither empirical corrections nor allocations, and it
+    /// is available in `no_std` environments with the [`LookupLn`] logarithm `L`. It is the
+    /// cardinality estimate of the `MLE` counter, which uses its own `ERROR` and logarithm.
+    /// When all of the registers are saturated the likelihood has no finite maximum, and the
+    /// estimate is infinite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut hll =
+    ///     PlusPlus::<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>::default();
+    /// assert_eq!(hll.estimate_cardinality_mle::<2, LookupLn>(), 0.0);
+    ///
+    /// for i in 0..1000 {
+    ///     hll.insert(&i);
+    /// }
+    ///
+    /// let estimate = hll.estimate_cardinality_mle::<2, LookupLn>();
+    /// assert!(estimate > 900.0 && estimate < 1100.0, "Got {estimate}");
+    /// ```
+    fn estimate_cardinality_mle<const ERROR: i32, L: Logarithm>(&self) -> f64 {
+        maximum_likelihood_estimate::<L>(
+            self.register_histogram().as_ref(),
+            Self::maximal_register_value(),
+            relative_error_limit::<Self::Precision>(ERROR),
+        )
+    }
+
     #[inline]
     /// Returns whether the provided [`HyperLogLog`] counter may be fully contained in the current [`HyperLogLog`] counter.
     ///
@@ -104,13 +176,15 @@ pub trait HyperLogLog:
     #[inline]
     /// Slits the hash into two parts: the register value and the index of the register.
     fn split_hash(hash: u64) -> (u8, <Self::Precision as Precision>::NumberOfRegisters) {
+        // The masked hash is always smaller than the number of registers, and therefore
+        // it always fits in the type used to represent the number of registers.
         let index: <Self::Precision as Precision>::NumberOfRegisters =
             <Self::Precision as Precision>::NumberOfRegisters::try_from_u64(
                 hash & (<Self::Precision as Precision>::NUMBER_OF_REGISTERS
                     - <Self::Precision as Precision>::NumberOfRegisters::ONE)
                     .into(),
             )
-            .unwrap();
+            .unwrap_or_else(|_| unreachable!("The masked hash fits in the number of registers."));
 
         debug_assert!(
             index < <Self::Precision as Precision>::NUMBER_OF_REGISTERS,
@@ -131,7 +205,9 @@ pub trait HyperLogLog:
             censored_hash |= 1_u64 << (64_u64 - <Self::Bits as VariableWord>::MASK);
         }
 
-        let register_value = u8::try_from(censored_hash.leading_zeros() + 1).unwrap();
+        // The number of leading zeros of an u64 plus one is at most 65.
+        let register_value = u8::try_from(censored_hash.leading_zeros() + 1)
+            .unwrap_or_else(|_| unreachable!("The register value is at most 65."));
 
         debug_assert!(
             register_value <= u8::try_from(<Self::Bits as VariableWord>::MASK).unwrap(),
@@ -146,20 +222,403 @@ pub trait HyperLogLog:
     #[inline]
     /// Hashes the element and returns the register value and the index of the register.
     fn hash_and_index<T: Hash>(
+        &self,
         element: &T,
     ) -> (u8, <Self::Precision as Precision>::NumberOfRegisters) {
-        let mut hasher = Self::Hasher::default();
-        element.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        Self::split_hash(hash)
+        Self::split_hash(self.hasher_builder().hash_one(element))
     }
 
     /// Return the value of the register at the given index.
     fn get_register(&self, index: <Self::Precision as Precision>::NumberOfRegisters) -> u8;
 
+    /// Create a new [`HyperLogLog`] counter from an array of registers and the builder of
+    /// the hashers with which they were computed.
+    fn from_registers_with_hasher_builder(
+        registers: Self::Registers,
+        hasher_builder: Self::HasherBuilder,
+    ) -> Self;
+
+    #[inline]
     /// Create a new [`HyperLogLog`] counter from an array of registers.
-    fn from_registers(registers: Self::Registers) -> Self;
+    fn from_registers(registers: Self::Registers) -> Self {
+        Self::from_registers_with_hasher_builder(registers, Self::HasherBuilder::default())
+    }
+
+    #[inline]
+    #[must_use]
+    /// Create a new empty [`HyperLogLog`] counter hashing its elements with the hashers
+    /// created by the provided builder.
+    ///
+    /// # Arguments
+    /// * `hasher_builder` - The builder of the hashers of the counter.
+    fn with_hasher_builder(hasher_builder: Self::HasherBuilder) -> Self {
+        Self::from_registers_with_hasher_builder(Self::Registers::default(), hasher_builder)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Create a new empty [`HyperLogLog`] counter whose hashers are keyed by the provided seed.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the hashers of the counter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// type Counter = PlusPlus<
+    ///     Precision8,
+    ///     Bits6,
+    ///     <Precision8 as ArrayRegister<Bits6>>::Array,
+    ///     twox_hash::XxHash64,
+    ///     SeededBuilder<twox_hash::XxHash64>,
+    /// >;
+    ///
+    /// let mut counter = Counter::with_seed(42);
+    /// counter.insert(&1);
+    ///
+    /// assert_eq!(counter.hasher_builder().seed(), Some(42));
+    /// assert!(counter.may_contain(&1));
+    /// ```
+    fn with_seed(seed: u64) -> Self
+    where
+        Self: HyperLogLog<HasherBuilder = SeededBuilder<<Self as HyperLogLog>::Hasher>>,
+    {
+        Self::with_hasher_builder(SeededBuilder::new(seed))
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns the largest value that a register of the counter may hold.
+    ///
+    /// # Implementative details
+    /// The register values are the number of leading zeros of a 64-bit hash plus one,
+    /// and they are therefore at most 65, unless the number of bits per register
+    /// is not sufficient to represent such a value.
+    fn maximal_register_value() -> u8 {
+        <Self::Bits as VariableWord>::MASK.min(65) as u8
+    }
+
+    #[inline]
+    /// Create a new [`HyperLogLog`] counter from an array of registers, checking that
+    /// all of the registers hold values that the counter may produce.
+    ///
+    /// # Errors
+    /// * If any of the registers holds a value larger than [`HyperLogLog::maximal_register_value`].
+    fn try_from_registers(registers: Self::Registers) -> Result<Self, Error> {
+        let maximum = Self::maximal_register_value();
+        if let Some((index, value)) = registers
+            .iter_registers()
+            .enumerate()
+            .find(|(_, value)| *value > maximum)
+        {
+            return Err(Error::InvalidRegisterValue {
+                index,
+                value,
+                maximum,
+            });
+        }
+        Ok(Self::from_registers(registers))
+    }
+
+    #[inline]
+    /// Create a new [`HyperLogLog`] counter from the values of its registers, one per byte.
+    ///
+    /// # Arguments
+    /// * `values` - The values of the registers, which must be as many as the number of registers.
+    ///
+    /// # Errors
+    /// * If the number of values is not equal to the number of registers.
+    /// * If any of the values is larger than [`HyperLogLog::maximal_register_value`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// type Counter = PlusPlus<Precision4, Bits5, <Precision4 as ArrayRegister<Bits5>>::Array>;
+    ///
+    /// let mut counter = Counter::default();
+    /// counter.insert(&1);
+    /// counter.insert(&2);
+    ///
+    /// let values: Vec<u8> = counter.registers().iter_registers().collect();
+    /// assert_eq!(Counter::try_from_register_values(&values), Ok(counter));
+    ///
+    /// assert_eq!(
+    ///     Counter::try_from_register_values(&values[1..]),
+    ///     Err(Error::UnexpectedNumberOfRegisters { expected: 16, found: 15 })
+    /// );
+    /// assert_eq!(
+    ///     Counter::try_from_register_values(&[32; 16]),
+    ///     Err(Error::InvalidRegisterValue { index: 0, value: 32, maximum: 31 })
+    /// );
+    /// ```
+    fn try_from_register_values(values: &[u8]) -> Result<Self, Error> {
+        let expected = <Self::Precision as Precision>::NUMBER_OF_REGISTERS.to_usize();
+        if values.len() != expected {
+            return Err(Error::UnexpectedNumberOfRegisters {
+                expected,
+                found: values.len(),
+            });
+        }
+        let maximum = Self::maximal_register_value();
+        if let Some((index, &value)) = values
+            .iter()
+            .enumerate()
+            .find(|(_, value)| **value > maximum)
+        {
+            return Err(Error::InvalidRegisterValue {
+                index,
+                value,
+                maximum,
+            });
+        }
+        let mut registers = Self::Registers::default();
+        let mut values = values.iter().copied();
+        registers.apply_to_registers(|_| values.next().unwrap_or_default());
+        Ok(Self::from_registers(registers))
+    }
+
+    #[inline]
+    /// Folds the counter into a counter with a lower or equal precision.
+    ///
+    /// # Implementative details
+    /// The index of a register is given by the lowest bits of the hash, while the register value
+    /// is computed from the leading zeros of the whole hash. Reducing the precision from `P` to `Q`
+    /// therefore maps the register at index `i` to the register at index `i mod 2^Q`, keeping the
+    /// largest value: the resulting counter is identical to the one obtained by inserting the same
+    /// elements directly into a counter with precision `Q`.
+    ///
+    /// # Errors
+    /// * [`Error::FoldToHigherPrecision`] if the precision of `C` is higher than the current one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut high = PlusPlus::<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>::default();
+    /// let mut low = PlusPlus::<Precision6, Bits6, <Precision6 as ArrayRegister<Bits6>>::Array>::default();
+    ///
+    /// for value in 0..1_000_u64 {
+    ///     high.insert(&value);
+    ///     low.insert(&value);
+    /// }
+    ///
+    /// let folded: PlusPlus<Precision6, Bits6, <Precision6 as ArrayRegister<Bits6>>::Array> =
+    ///     high.fold_to().unwrap();
+    /// assert_eq!(folded, low);
+    ///
+    /// assert_eq!(
+    ///     low.fold_to::<PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>>(),
+    ///     Err(Error::FoldToHigherPrecision { from: 6, to: 8 })
+    /// );
+    /// ```
+    fn fold_to<
+        C: HyperLogLog<Bits = Self::Bits, Hasher = Self::Hasher, HasherBuilder = Self::HasherBuilder>,
+    >(
+        &self,
+    ) -> Result<C, Error> {
+        if C::Precision::EXPONENT > Self::Precision::EXPONENT {
+            return Err(Error::FoldToHigherPrecision {
+                from: Self::Precision::EXPONENT,
+                to: C::Precision::EXPONENT,
+            });
+        }
+
+        let mask =
+            C::Precision::NUMBER_OF_REGISTERS - <C::Precision as Precision>::NumberOfRegisters::ONE;
+        let mut folded_index = <C::Precision as Precision>::NumberOfRegisters::ZERO;
+        let mut registers = C::Registers::default();
+
+        for register in self.registers().iter_registers() {
+            if register > 0 {
+                registers.set_greater(folded_index, register);
+            }
+            folded_index =
+                (folded_index + <C::Precision as Precision>::NumberOfRegisters::ONE) & mask;
+        }
+
+        Ok(C::from_registers_with_hasher_builder(
+            registers,
+            self.hasher_builder().clone(),
+        ))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converts the counter into a counter with a different number of bits per register.
+    ///
+    /// # Implementative details
+    /// When the number of bits decreases, the register values larger than the largest value
+    /// that the new registers may hold are saturated to it: the resulting counter is identical
+    /// to the one obtained by inserting the same elements directly into a counter with fewer
+    /// bits per register. When the number of bits increases, the register values are kept as
+    /// they are, so that a conversion to fewer bits and back preserves the saturated values.
+    ///
+    /// # Impact on the estimates
+    /// A register with `B` bits saturates once the hash of an element has at least `2^B - 2`
+    /// leading zeros, an event with probability `2^(2 - 2^B)`. With four bits per register,
+    /// saturation only becomes relevant when the cardinality approaches `2^14` times the number
+    /// of registers, with five bits `2^30` times, and with six or more bits it is practically
+    /// impossible. Narrowing to fewer than four bits, instead, quickly degrades the estimates
+    /// of large cardinalities, which are underestimated. Widening a counter cannot recover the
+    /// ranks lost to saturation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut wide = PlusPlus::<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>::default();
+    /// let mut narrow = PlusPlus::<Precision8, Bits4, <Precision8 as ArrayRegister<Bits4>>::Packed>::default();
+    ///
+    /// for value in 0..1_000_u64 {
+    ///     wide.insert(&value);
+    ///     narrow.insert(&value);
+    /// }
+    ///
+    /// let converted: PlusPlus<Precision8, Bits4, <Precision8 as ArrayRegister<Bits4>>::Packed> =
+    ///     wide.convert_bits();
+    /// assert_eq!(converted, narrow);
+    /// ```
+    fn convert_bits<
+        C: HyperLogLog<
+            Precision = Self::Precision,
+            Hasher = Self::Hasher,
+            HasherBuilder = Self::HasherBuilder,
+        >,
+    >(
+        &self,
+    ) -> C {
+        let maximum = C::maximal_register_value();
+        let mut values = self.registers().iter_registers();
+        let mut registers = C::Registers::default();
+        registers.apply_to_registers(|_| values.next().unwrap_or_default().min(maximum));
+        C::from_registers_with_hasher_builder(registers, self.hasher_builder().clone())
+    }
+
+    #[inline]
+    /// Returns the union of the current counter and a counter with a possibly different
+    /// precision, folding both of them to the precision of the counter `C`.
+    ///
+    /// # Arguments
+    /// * `other` - The counter to merge with the current one.
+    ///
+    /// # Errors
+    /// * [`Error::FoldToHigherPrecision`] if the precision of `C` is higher than the precision
+    ///   of either of the two counters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// let mut left = PlusPlus::<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>::default();
+    /// let mut right = PlusPlus::<Precision6, Bits6, <Precision6 as ArrayRegister<Bits6>>::Array>::default();
+    /// let mut expected = PlusPlus::<Precision6, Bits6, <Precision6 as ArrayRegister<Bits6>>::Array>::default();
+    ///
+    /// for value in 0..1_000_u64 {
+    ///     left.insert(&value);
+    ///     right.insert(&(value + 500));
+    ///     expected.insert(&value);
+    ///     expected.insert(&(value + 500));
+    /// }
+    ///
+    /// let union: PlusPlus<Precision6, Bits6, <Precision6 as ArrayRegister<Bits6>>::Array> =
+    ///     left.folded_union(&right).unwrap();
+    /// assert_eq!(union, expected);
+    /// ```
+    fn folded_union<
+        Rhs: HyperLogLog<Bits = Self::Bits, Hasher = Self::Hasher, HasherBuilder = Self::HasherBuilder>,
+        C: HyperLogLog<Bits = Self::Bits, Hasher = Self::Hasher, HasherBuilder = Self::HasherBuilder>,
+    >(
+        &self,
+        other: &Rhs,
+    ) -> Result<C, Error> {
+        Ok(self.fold_to::<C>()? | other.fold_to::<C>()?)
+    }
+
+    #[inline]
+    /// Merges the provided counters into the current one, in place.
+    ///
+    /// # Arguments
+    /// * `counters` - The counters to merge into the current one.
+    ///
+    /// # Implementative details
+    /// The registers of the counters are merged into a copy of the registers of the current
+    /// counter, without cloning any of the counters, and the harmonic sum and the number of
+    /// zero registers are then computed only once for the merged registers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+    ///
+    /// let mut shards = [Counter::default(), Counter::default(), Counter::default()];
+    /// let mut expected = Counter::default();
+    /// for value in 0..1_000_u64 {
+    ///     shards[value as usize % 3].insert(&value);
+    ///     expected.insert(&value);
+    /// }
+    ///
+    /// let mut union = Counter::default();
+    /// union.union_many(&shards);
+    /// assert_eq!(union, expected);
+    /// ```
+    fn union_many<'counters, I>(&mut self, counters: I)
+    where
+        I: IntoIterator<Item = &'counters Self>,
+        Self: 'counters,
+    {
+        let mut registers = self.registers().clone();
+        for counter in counters {
+            debug_assert!(
+                counter.hasher_builder() == self.hasher_builder(),
+                "The counters must hash their elements with the same hashers."
+            );
+            registers.set_greater_registers(counter.registers(), |_, _| {});
+        }
+        *self = Self::from_registers_with_hasher_builder(registers, self.hasher_builder().clone());
+    }
+
+    #[inline]
+    /// Returns the harmonic sum and the number of zero registers of the union of the provided
+    /// counters, without building the union counter.
+    ///
+    /// # Arguments
+    /// * `counters` - The counters whose union is considered.
+    ///
+    /// # Implementative details
+    /// The registers of each counter are read once and merged into a single set of registers,
+    /// whose harmonic sum is accumulated in the same order as the one of a counter built from
+    /// them: the results are therefore identical to the ones of the counter obtained with
+    /// [`HyperLogLog::union_many`].
+    fn union_harmonic_sum_and_zeros<'counters, I>(
+        counters: I,
+    ) -> (f64, <Self::Precision as Precision>::NumberOfRegisters)
+    where
+        I: IntoIterator<Item = &'counters Self>,
+        Self: 'counters,
+    {
+        let mut registers = Self::Registers::default();
+        for counter in counters {
+            registers.set_greater_registers(counter.registers(), |_, _| {});
+        }
+
+        let mut harmonic_sum = f64::ZERO;
+        let mut number_of_zero_registers = <Self::Precision as Precision>::NumberOfRegisters::ZERO;
+        for register in registers.iter_registers() {
+            number_of_zero_registers +=
+                <Self::Precision as Precision>::NumberOfRegisters::from(register == 0);
+            harmonic_sum += f64::integer_exp2_minus(register);
+        }
+        (harmonic_sum, number_of_zero_registers)
+    }
 }
 
 /// Trait for the correction of an hyperloglog counter.
@@ -169,8 +628,128 @@ pub trait Correction: HyperLogLog {
         harmonic_sum: f64,
         number_of_zero_registers: <Self::Precision as Precision>::NumberOfRegisters,
     ) -> f64;
+
+    #[inline]
+    /// Returns the estimated cardinality of the union of the provided counters, without
+    /// building the union counter.
+    ///
+    /// # Arguments
+    /// * `counters` - The counters whose union cardinality is estimated.
+    ///
+    /// # Implementative details
+    /// The estimate is identical to the one of the counter obtained by merging all of the
+    /// counters with [`HyperLogLog::union_many`], and it is therefore corrected as the
+    /// estimate of a single counter. Differently from [`Estimator::estimate_union_cardinality`],
+    /// the cardinalities of the individual counters are not taken into account.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+    ///
+    /// let mut shards = vec![Counter::default(); 100];
+    /// for value in 0..10_000_u64 {
+    ///     shards[value as usize % 100].insert(&value);
+    /// }
+    ///
+    /// let mut union = Counter::default();
+    /// union.union_many(&shards);
+    ///
+    /// assert_eq!(Counter::estimate_union_of(&shards), union.estimate_cardinality());
+    ///
+    /// // Slices of references are supported by copying the references.
+    /// let selected: Vec<&Counter> = vec![&shards[0], &shards[2]];
+    /// let expected = shards[0].clone() | shards[2].clone();
+    /// assert_eq!(
+    ///     Counter::estimate_union_of(selected.iter().copied()),
+    ///     Counter::estimate_union_of([&expected])
+    /// );
+    /// ```
+    fn estimate_union_of<'counters, I>(counters: I) -> f64
+    where
+        I: IntoIterator<Item = &'counters Self>,
+        Self: 'counters,
+    {
+        let (harmonic_sum, number_of_zero_registers) = Self::union_harmonic_sum_and_zeros(counters);
+        Self::correction(harmonic_sum, number_of_zero_registers)
+    }
+
+    #[inline]
+    /// Returns the estimated cardinality of the intersection of the provided counters.
+    ///
+    /// # Arguments
+    /// * `counters` - The counters whose intersection cardinality is estimated.
+    ///
+    /// # Implementative details
+    /// The intersection cardinality is computed with the inclusion–exclusion principle, as the
+    /// alternating sum of the union estimates of all of the non-empty subsets of the counters,
+    /// and negative results are clamped to zero. Since the number of subsets doubles with
+    /// each counter, at most 16 counters are supported. The intersection of no counters, or
+    /// of counters whose union is empty, is empty.
+    ///
+    /// # Errors
+    /// * [`Error::TooManyCounters`] if more than 16 counters are provided.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hyperloglog_rs::prelude::*;
+    ///
+    /// type Counter = PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>;
+    ///
+    /// let mut counters = [Counter::default(), Counter::default(), Counter::default()];
+    /// for value in 0..30_000_u64 {
+    ///     // Every value is in at least two of the three counters, and half are in all of them.
+    ///     for (i, counter) in counters.iter_mut().enumerate() {
+    ///         if value % 3 != i as u64 || value % 2 == 0 {
+    ///             counter.insert(&value);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let [first, second, third] = &counters;
+    /// let intersection = Counter::estimate_intersection_of(&[first, second, third]).unwrap();
+    /// assert!((intersection - 15_000.0).abs() < 15_000.0 * 0.1, "{intersection}");
+    /// ```
+    fn estimate_intersection_of(counters: &[&Self]) -> Result<f64, Error> {
+        if counters.len() > MAXIMUM_NUMBER_OF_INCLUSION_EXCLUSION_COUNTERS {
+            return Err(Error::TooManyCounters {
+                maximum: MAXIMUM_NUMBER_OF_INCLUSION_EXCLUSION_COUNTERS,
+                found: counters.len(),
+            });
+        }
+
+        let (_, union_zeros) = Self::union_harmonic_sum_and_zeros(counters.iter().copied());
+        if union_zeros == Self::Precision::NUMBER_OF_REGISTERS {
+            return Ok(f64::ZERO);
+        }
+
+        let mut intersection_cardinality = f64::ZERO;
+        for subset in 1_usize..(1 << counters.len()) {
+            let union_cardinality = Self::estimate_union_of(
+                counters
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| subset & (1 << i) != 0)
+                    .map(|(_, counter)| *counter),
+            );
+            if subset.count_ones() % 2 == 1 {
+                intersection_cardinality += union_cardinality;
+            } else {
+                intersection_cardinality -= union_cardinality;
+            }
+        }
+
+        Ok(intersection_cardinality.max(f64::ZERO))
+    }
 }
 
+/// The largest number of counters whose intersection is estimated with the inclusion–exclusion
+/// principle, which requires the union estimates of all of their subsets.
+const MAXIMUM_NUMBER_OF_INCLUSION_EXCLUSION_COUNTERS: usize = 16;
+
 impl<H> SetProperties for H
 where
     H: HyperLogLog,
@@ -197,7 +776,7 @@ where
     H: HyperLogLog,
 {
     fn may_contain(&self, element: &T) -> bool {
-        let (register, index) = Self::hash_and_index::<T>(element);
+        let (register, index) = self.hash_and_index(element);
         self.get_register(index) >= register
     }
 }
@@ -1,20 +1,44 @@
 //! This module contains the macro used to implement the [`HyperLogLog`] trait for a given counter.
 
 #[macro_export]
-/// Implements the [`HyperLogLog`] trait for a given counter.
+/// Implements the [`HyperLogLog`] trait for a given counter, generic over the provided
+/// additional type parameters and their bounds.
 macro_rules! hll_impl {
-    ($counter:ty) => {
-        impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> PartialEq for $counter {
+    ($counter:ty $(, $generic:ident: $bound:path)*) => {
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > PartialEq for $counter
+        {
             #[inline]
             fn eq(&self, other: &Self) -> bool {
                 self.counter == other.counter
             }
         }
 
-        impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Eq for $counter {}
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > Eq for $counter
+        {
+        }
 
-        impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> BitOrAssign
-            for $counter
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > BitOrAssign for $counter
         {
             #[inline]
             fn bitor_assign(&mut self, rhs: Self) {
@@ -23,46 +47,102 @@ macro_rules! hll_impl {
         }
 
         #[cfg(feature = "serde")]
-        impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> serde::Serialize
-            for $counter
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > serde::Serialize for $counter
         {
             #[inline]
             fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-                use serde::ser::SerializeSeq;
-                let mut seq = serializer.serialize_seq(Some(1 << P::EXPONENT))?;
-                for register in self.registers().iter_registers() {
-                    seq.serialize_element(&register)?;
+                use serde::ser::SerializeTuple;
+                let values = $crate::serde::RegisterValues::<P, B, R>::new(self.registers());
+                // The seed of the hashers, if any, precedes the values of the registers.
+                match self.hasher_builder().seed() {
+                    Some(seed) => {
+                        let mut tuple = serializer.serialize_tuple(2)?;
+                        tuple.serialize_element(&seed)?;
+                        tuple.serialize_element(&values)?;
+                        tuple.end()
+                    }
+                    None => serde::Serialize::serialize(&values, serializer),
                 }
-                seq.end()
             }
         }
 
         #[cfg(feature = "serde")]
-        impl<'de, P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType>
-            serde::Deserialize<'de> for $counter
+        impl<
+                'de,
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > serde::Deserialize<'de> for $counter
         {
             #[inline]
             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
             where
                 D: serde::Deserializer<'de>,
             {
-                let mut registers: R = R::default();
-                let visitor = $crate::serde::RegisterVisitor::<u8>::new(1 << P::EXPONENT);
-                let mut iter = deserializer.deserialize_seq(visitor)?.into_iter();
-                registers.apply_to_registers(|_| iter.next().unwrap());
-                debug_assert_eq!(iter.next(), None);
-                Ok(Self::from_registers(registers))
+                let (hasher_builder, values) = if Builder::default().seed().is_some() {
+                    let (seed, values) =
+                        deserializer.deserialize_tuple(2, $crate::serde::SeededRegisterVisitor)?;
+                    let hasher_builder = Builder::from_seed(Some(seed)).ok_or_else(|| {
+                        serde::de::Error::invalid_value(
+                            serde::de::Unexpected::Unsigned(seed),
+                            &"a seed supported by the builder of the hashers",
+                        )
+                    })?;
+                    (hasher_builder, values)
+                } else {
+                    let visitor = $crate::serde::RegisterVisitor::<u8>::new(1 << P::EXPONENT);
+                    (Builder::default(), deserializer.deserialize_seq(visitor)?)
+                };
+                let counter =
+                    Self::try_from_register_values(&values).map_err(serde::de::Error::custom)?;
+                Ok(Self::from_registers_with_hasher_builder(
+                    counter.registers().clone(),
+                    hasher_builder,
+                ))
+            }
+        }
+
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > TryFrom<&[u8]> for $counter
+        {
+            type Error = $crate::prelude::Error;
+
+            #[inline]
+            fn try_from(values: &[u8]) -> Result<Self, Self::Error> {
+                Self::try_from_register_values(values)
             }
         }
 
-        impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> BitOr for $counter {
+        impl<
+                P: Precision,
+                B: Bits,
+                R: Registers<P, B>,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                $($generic: $bound,)*
+            > BitOr for $counter
+        {
             type Output = Self;
 
             #[inline]
             fn bitor(self, rhs: Self) -> Self::Output {
-                Self {
-                    counter: self.counter | rhs.counter,
-                }
+                Self::from(self.counter | rhs.counter)
             }
         }
 
@@ -70,7 +150,9 @@ macro_rules! hll_impl {
                 P: Precision,
                 B: Bits,
                 Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
                 R: Registers<P, B>,
+                $($generic: $bound,)*
             > MutableSet for $counter
         {
             #[inline]
@@ -83,8 +165,10 @@ macro_rules! hll_impl {
                 P: Precision,
                 B: Bits,
                 Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
                 R: Registers<P, B>,
                 T: core::hash::Hash,
+                $($generic: $bound,)*
             > ExtendableApproximatedSet<T> for $counter
         {
             #[inline]
@@ -93,10 +177,41 @@ macro_rules! hll_impl {
             }
         }
 
-        impl<P: Precision, B: Bits, Hasher: HasherType, R: Registers<P, B> + VariableWords<CH>, CH: CompositeHash<P, B>>
-            Hybridazable<CH> for $counter
+        impl<
+                P: Precision,
+                B: Bits,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                R: Registers<P, B>,
+                $($generic: $bound,)*
+            > ExtendableHashes for $counter
+        {
+            #[inline]
+            fn insert_hash(&mut self, hash: u64) -> bool {
+                self.counter.insert_hash(hash)
+            }
+
+            #[inline]
+            fn extend_hashes(&mut self, hashes: &[u64]) {
+                self.counter.extend_hashes(hashes)
+            }
+        }
+
+        impl<
+                P: Precision,
+                B: Bits,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                R: Registers<P, B> + VariableWords<CH>,
+                CH: CompositeHash<P, B>,
+                $($generic: $bound,)*
+            > Hybridazable<CH> for $counter
         {
-            type IterSortedHashes<'words> = <R as VariableWords<CH>>::Words<'words> where Self: 'words, CH: 'words;
+            type IterSortedHashes<'words>
+                = <R as VariableWords<CH>>::Words<'words>
+            where
+                Self: 'words,
+                CH: 'words;
 
             #[inline]
             fn dehybridize(&mut self) {
@@ -105,9 +220,11 @@ macro_rules! hll_impl {
 
             #[inline]
             fn new_hybrid() -> Self {
-                Self {
-                    counter: Hybridazable::new_hybrid(),
-                }
+                Self::from(
+                    <$crate::basicloglog::BasicLogLog<P, B, R, Hasher, Builder> as Hybridazable<
+                        CH,
+                    >>::new_hybrid(),
+                )
             }
 
             #[inline]
@@ -144,6 +261,34 @@ macro_rules! hll_impl {
             fn hybrid_insert<T: core::hash::Hash>(&mut self, element: &T) -> bool {
                 self.counter.hybrid_insert(element)
             }
+
+            #[inline]
+            fn insert_composite_hash(&mut self, composite_hash: CH::Word) -> bool {
+                self.counter.insert_composite_hash(composite_hash)
+            }
+
+            #[inline]
+            fn insert_sorted_composite_hashes(&mut self, composite_hashes: &[CH::Word]) -> bool {
+                self.counter
+                    .insert_sorted_composite_hashes(composite_hashes)
+            }
+        }
+
+        impl<
+                P: Precision,
+                B: Bits,
+                Hasher: HasherType,
+                Builder: HasherBuilder<Hasher = Hasher>,
+                R: Registers<P, B> + VariableWords<CH> + VariableWords<NCH>,
+                CH: CompositeHash<P, B>,
+                NCH: CompositeHash<P, B>,
+                $($generic: $bound,)*
+            > ShrinkableHybrid<CH, NCH> for $counter
+        {
+            #[inline]
+            fn shrink_composite_hashes(&mut self) {
+                ShrinkableHybrid::<CH, NCH>::shrink_composite_hashes(&mut self.counter)
+            }
         }
     };
 }
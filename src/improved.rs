@@ -0,0 +1,343 @@
+//! Submodule implementing [`Improved`], the improved raw estimator by Ertl.
+//!
+//! Differently from [`PlusPlus`](crate::prelude::PlusPlus), the estimator does not require any
+//! empirical table of estimates and biases, and it is therefore available for any precision
+//! and number of bits. Its estimate is `m^2 / (2 ln 2 z)`, where `z` is computed from the
+//! histogram of the values of the registers: the registers with value zero are weighted by the
+//! function `σ`, the registers holding the maximal value by the function `τ`, while all of the
+//! other registers contribute to `z` as they contribute to the harmonic sum.
+use crate::basicloglog::BasicLogLog;
+use crate::hll_impl;
+use crate::prelude::*;
+use crate::utils::math::sqrt;
+
+#[cfg(feature = "std")]
+use core::any::type_name;
+
+/// The constant `1 / (2 ln 2)` of the improved raw estimator.
+const ALPHA_INFINITY: f64 = 0.721_347_520_444_481_7;
+
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A struct implementing the improved raw estimator by Ertl.
+pub struct Improved<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    Hasher: HasherType = twox_hash::XxHash64,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+> {
+    /// The underlying `BasicLogLog` counter.
+    counter: BasicLogLog<P, B, R, Hasher, Builder>,
+}
+
+hll_impl!(Improved<P, B, R, Hasher, Builder>);
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > From<BasicLogLog<P, B, R, Hasher, Builder>> for Improved<P, B, R, Hasher, Builder>
+{
+    #[inline]
+    fn from(counter: BasicLogLog<P, B, R, Hasher, Builder>) -> Self {
+        Self { counter }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<
+        P: Precision + Named,
+        B: Bits + Named,
+        R: Registers<P, B> + Named,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Named for Improved<P, B, R, Hasher, Builder>
+{
+    #[inline]
+    fn name(&self) -> String {
+        format!(
+            "IMP<{}, {}, {}> + {}",
+            P::default().name(),
+            B::default().name(),
+            self.registers().name(),
+            type_name::<Hasher>().split("::").last().unwrap()
+        )
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > HyperLogLog for Improved<P, B, R, Hasher, Builder>
+{
+    type Registers = R;
+    type Precision = P;
+    type Bits = B;
+    type Hasher = Hasher;
+    type HasherBuilder = Builder;
+
+    #[inline]
+    fn registers(&self) -> &Self::Registers {
+        self.counter.registers()
+    }
+
+    #[inline]
+    fn hasher_builder(&self) -> &Builder {
+        self.counter.hasher_builder()
+    }
+
+    #[inline]
+    fn get_number_of_zero_registers(&self) -> <P as Precision>::NumberOfRegisters {
+        self.counter.get_number_of_zero_registers()
+    }
+
+    #[inline]
+    fn get_register(&self, index: P::NumberOfRegisters) -> u8 {
+        self.counter.get_register(index)
+    }
+
+    #[inline]
+    fn harmonic_sum(&self) -> f64 {
+        self.counter.harmonic_sum()
+    }
+
+    #[inline]
+    fn register_histogram(&self) -> <Self::Bits as Bits>::Histogram {
+        self.counter.register_histogram()
+    }
+
+    #[inline]
+    fn from_registers_with_hasher_builder(registers: R, hasher_builder: Builder) -> Self {
+        Self {
+            counter: HyperLogLog::from_registers_with_hasher_builder(registers, hasher_builder),
+        }
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType + HasherId,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > BinaryFormat for Improved<P, B, R, Hasher, Builder>
+{
+    const ESTIMATOR: u8 = IMPROVED_ESTIMATOR;
+
+    #[inline]
+    fn header(&self) -> BinaryHeader {
+        self.counter.binary_header(Self::ESTIMATOR)
+    }
+
+    #[inline]
+    fn words(&self) -> &[u64] {
+        self.registers().words()
+    }
+
+    #[inline]
+    fn from_header_and_words(header: &BinaryHeader, words: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            counter: BasicLogLog::from_binary(header, words, Self::ESTIMATOR)?,
+        })
+    }
+}
+
+#[expect(
+    clippy::float_cmp,
+    reason = "The series is summed until its terms no longer change the result."
+)]
+/// Returns the function `σ(x) = x + Σ_{k ≥ 1} x^(2^k) 2^(k-1)` weighting the zero registers.
+fn sigma(mut fraction_of_zeros: f64) -> f64 {
+    if fraction_of_zeros >= 1.0 {
+        return f64::INFINITY;
+    }
+    let mut weight = 1.0;
+    let mut sigma = fraction_of_zeros;
+    loop {
+        fraction_of_zeros *= fraction_of_zeros;
+        let previous = sigma;
+        sigma += fraction_of_zeros * weight;
+        weight += weight;
+        if sigma == previous {
+            return sigma;
+        }
+    }
+}
+
+#[expect(
+    clippy::float_cmp,
+    reason = "The series is summed until its terms no longer change the result."
+)]
+/// Returns the function `τ(x) = (1 - x - Σ_{k ≥ 1} (1 - x^(2^-k))^2 2^-k) / 3` weighting the
+/// registers holding the maximal value, where `x` is the fraction of the other registers.
+fn tau(mut fraction_of_non_saturated: f64) -> f64 {
+    if fraction_of_non_saturated <= 0.0 || fraction_of_non_saturated >= 1.0 {
+        return 0.0;
+    }
+    let mut weight = 1.0;
+    let mut tau = 1.0 - fraction_of_non_saturated;
+    loop {
+        fraction_of_non_saturated = sqrt(fraction_of_non_saturated);
+        let previous = tau;
+        weight *= 0.5;
+        let complement = 1.0 - fraction_of_non_saturated;
+        tau -= complement * complement * weight;
+        if tau == previous {
+            return tau / 3.0;
+        }
+    }
+}
+
+/// Returns the improved raw estimate of the cardinality.
+///
+/// # Arguments
+/// * `harmonic_sum` - The harmonic sum of the registers, including the zero and saturated ones.
+/// * `number_of_zero_registers` - The number of registers with value zero.
+/// * `number_of_saturated_registers` - The number of registers holding the maximal value.
+/// * `maximal_register_value` - The maximal value that the registers may hold.
+fn improved_estimate<P: Precision>(
+    harmonic_sum: f64,
+    number_of_zero_registers: P::NumberOfRegisters,
+    number_of_saturated_registers: P::NumberOfRegisters,
+    maximal_register_value: u8,
+) -> f64 {
+    let number_of_registers = f64::integer_exp2(P::EXPONENT);
+    let zeros = number_of_zero_registers.to_f64();
+    let saturated = number_of_saturated_registers.to_f64();
+
+    // The registers that are neither zero nor saturated contribute as in the harmonic sum.
+    let intermediate =
+        (harmonic_sum - zeros - saturated * f64::integer_exp2_minus(maximal_register_value))
+            .max(0.0);
+    let z = number_of_registers
+        * tau(1.0 - saturated / number_of_registers)
+        * f64::integer_exp2_minus(maximal_register_value - 1)
+        + intermediate
+        + number_of_registers * sigma(zeros / number_of_registers);
+
+    ALPHA_INFINITY * number_of_registers * number_of_registers / z
+}
+
+/// Returns the improved raw estimate of the cardinality from the values of the registers.
+fn improved_estimate_from_registers<H: HyperLogLog>(registers: impl Iterator<Item = u8>) -> f64 {
+    let maximal_register_value = H::maximal_register_value();
+    let mut harmonic_sum = f64::ZERO;
+    let mut number_of_zero_registers = <H::Precision as Precision>::NumberOfRegisters::ZERO;
+    let mut number_of_saturated_registers = <H::Precision as Precision>::NumberOfRegisters::ZERO;
+    for register in registers {
+        number_of_zero_registers +=
+            <H::Precision as Precision>::NumberOfRegisters::from(register == 0);
+        number_of_saturated_registers += <H::Precision as Precision>::NumberOfRegisters::from(
+            register == maximal_register_value,
+        );
+        harmonic_sum += f64::integer_exp2_minus(register);
+    }
+    improved_estimate::<H::Precision>(
+        harmonic_sum,
+        number_of_zero_registers,
+        number_of_saturated_registers,
+        maximal_register_value,
+    )
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Correction for Improved<P, B, R, Hasher, Builder>
+{
+    #[inline]
+    /// Returns the improved raw estimate of the cardinality.
+    ///
+    /// # Implementative details
+    /// Since the number of registers holding the maximal value cannot be derived from the
+    /// harmonic sum, they are treated as any other non-zero register. This only affects the
+    /// estimates of counters with few bits per register close to saturation, and the estimates
+    /// computed from the registers of the counters, such as [`Estimator::estimate_cardinality`],
+    /// always account for them.
+    fn correction(
+        harmonic_sum: f64,
+        number_of_zero_registers: <Self::Precision as Precision>::NumberOfRegisters,
+    ) -> f64 {
+        improved_estimate::<P>(
+            harmonic_sum,
+            number_of_zero_registers,
+            P::NumberOfRegisters::ZERO,
+            Self::maximal_register_value(),
+        )
+    }
+
+    #[inline]
+    fn estimate_union_of<'counters, I>(counters: I) -> f64
+    where
+        I: IntoIterator<Item = &'counters Self>,
+        Self: 'counters,
+    {
+        let mut registers = R::default();
+        for counter in counters {
+            registers.set_greater_registers(counter.registers(), |_, _| {});
+        }
+        improved_estimate_from_registers::<Self>(registers.iter_registers())
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+    > Estimator<f64> for Improved<P, B, R, Hasher, Builder>
+where
+    Self: HyperLogLog<Precision = P, Bits = B, Registers = R, Hasher = Hasher>,
+{
+    #[inline]
+    fn estimate_cardinality(&self) -> f64 {
+        improved_estimate_from_registers::<Self>(self.registers().iter_registers())
+    }
+
+    #[inline]
+    fn estimate_union_cardinality_with_cardinalities(
+        &self,
+        other: &Self,
+        self_cardinality: f64,
+        other_cardinality: f64,
+    ) -> f64 {
+        correct_union_estimate(
+            self_cardinality,
+            other_cardinality,
+            improved_estimate_from_registers::<Self>(
+                self.registers()
+                    .iter_registers_zipped(other.registers())
+                    .map(|[left, right]| left.max(right)),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sigma_and_tau() {
+        assert!(sigma(0.0).abs() < f64::EPSILON);
+        assert!(sigma(1.0).is_infinite());
+        assert!(tau(0.0).abs() < f64::EPSILON);
+        assert!(tau(1.0).abs() < f64::EPSILON);
+        // The series at one half is 0.5 + 0.5^2 + 2 0.5^4 + 4 0.5^8 + ...
+        let expected: f64 = 0.5
+            + (1..10)
+                .map(|k| 0.5_f64.powi(1 << k) * f64::from(1 << (k - 1)))
+                .sum::<f64>();
+        assert!((sigma(0.5) - expected).abs() < 1e-15);
+    }
+}
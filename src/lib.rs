@@ -6,21 +6,46 @@
 #![warn(clippy::pedantic)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod adaptive_hybrid;
+#[cfg(all(feature = "plusplus", target_has_atomic = "64"))]
+mod atomic;
 mod basicloglog;
+mod binary_format;
 mod bits;
+mod bucketed;
+#[cfg(feature = "std")]
+mod confidence;
+#[cfg(any(feature = "beta", feature = "plusplus"))]
+mod corrections;
+#[cfg(feature = "alloc")]
+mod dynamic;
+mod error;
 mod estimator;
 pub mod hybrid;
 mod hyperloglog;
 mod hyperloglog_macro;
+#[cfg(feature = "improved")]
+mod improved;
+mod likelihood;
+mod logarithm;
 #[cfg(feature = "beta")]
 mod loglogbeta;
 #[cfg(feature = "mle")]
 mod mle;
 #[cfg(feature = "plusplus")]
 mod plusplus;
+#[cfg(any(feature = "std", feature = "mle"))]
+mod poisson;
 mod precisions;
 mod registers;
 pub mod sketches;
+#[cfg(feature = "alloc")]
+mod sliding_window;
+#[cfg(feature = "alloc")]
+mod sparse;
 mod utils;
 
 #[cfg(feature = "serde")]
@@ -31,10 +56,25 @@ mod hashset;
 
 /// Re-exports of the most important traits and structs.
 pub mod prelude {
+    pub use crate::adaptive_hybrid::*;
+    #[cfg(all(feature = "plusplus", target_has_atomic = "64"))]
+    pub use crate::atomic::*;
+    pub use crate::binary_format::*;
     pub use crate::bits::*;
+    pub use crate::bucketed::*;
+    #[cfg(feature = "std")]
+    pub use crate::confidence::*;
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    pub use crate::corrections::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::dynamic::*;
+    pub use crate::error::Error;
     pub use crate::estimator::*;
     pub use crate::hybrid::*;
     pub use crate::hyperloglog::*;
+    #[cfg(feature = "improved")]
+    pub use crate::improved::*;
+    pub use crate::logarithm::*;
     #[cfg(feature = "beta")]
     pub use crate::loglogbeta::*;
     #[cfg(feature = "mle")]
@@ -44,6 +84,10 @@ pub mod prelude {
     pub use crate::precisions::*;
     pub use crate::registers::*;
     pub use crate::sketches::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::sliding_window::*;
+    #[cfg(feature = "alloc")]
+    pub use crate::sparse::*;
     pub use crate::utils::*;
     pub use core::ops::{BitOr, BitOrAssign};
 }
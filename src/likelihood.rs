@@ -0,0 +1,127 @@
+//! Submodule implementing the maximum likelihood estimation of the cardinality of a counter.
+//!
+//! The estimator by Ertl maximizes the likelihood of the histogram of the values of the registers
+//! under the Poisson model, solving the resulting equation with the secant method. It only
+//! requires the histogram and a few floating point operations per value of the registers, and
+//! therefore works in `no_std` environments without any allocation.
+use crate::prelude::*;
+use crate::utils::math::floor_log2;
+use crate::utils::{FloatOps, Zero};
+
+/// Returns the relative error limit `10^-error / sqrt(m)` of the maximum likelihood estimates.
+///
+/// # Arguments
+/// * `error` - The exponent of the relative error limit, as the `ERROR` of the `MLE` counter.
+pub(crate) fn relative_error_limit<P: Precision>(error: i32) -> f64 {
+    let mut limit = f64::integer_exp2_minus(P::EXPONENT / 2);
+    if P::EXPONENT % 2 == 1 {
+        limit *= core::f64::consts::FRAC_1_SQRT_2;
+    }
+    for _ in 0..error.unsigned_abs() {
+        if error > 0 {
+            limit /= 10.0;
+        } else {
+            limit *= 10.0;
+        }
+    }
+    limit
+}
+
+/// Returns the maximum likelihood estimate of the cardinality from the histogram of the values
+/// of the registers.
+///
+/// # Arguments
+/// * `histogram` - The number of registers holding each value.
+/// * `maximal_register_value` - The maximal value that the registers may hold.
+/// * `relative_error_limit` - The relative change of the estimate below which the solver stops.
+///
+/// The logarithm `L` is only employed to compute the starting point of the solver.
+///
+/// # Implementative details
+/// This is the algorithm 8 of "New cardinality estimation algorithms for `HyperLogLog` sketches"
+/// by Ertl. The derivative of the log-likelihood is expressed with the function
+/// `h(x) = 1 - x / (e^x - 1)`, which is evaluated with its Taylor expansion for the smallest
+/// argument and then with the recursion from `h(x)` to `h(2x)`, so that no exponential is
+/// required. The secant method starts from a lower bound of the estimate and converges to it
+/// monotonically from below.
+pub(crate) fn maximum_likelihood_estimate<L: Logarithm>(
+    histogram: &[u32],
+    maximal_register_value: u8,
+    relative_error_limit: f64,
+) -> f64 {
+    let histogram = &histogram[..=usize::from(maximal_register_value)];
+    let count = |register_value: u8| f64::from(histogram[usize::from(register_value)]);
+    let number_of_registers: u32 = histogram.iter().sum();
+
+    if histogram[usize::from(maximal_register_value)] == number_of_registers {
+        return f64::INFINITY;
+    }
+
+    // The registers are either zero, saturated, or hold a value from one to `q`, and only the
+    // values between the smallest and largest ones observed contribute to the harmonic sum.
+    let q = maximal_register_value - 1;
+    let smallest_observed_value =
+        u8::try_from(histogram.iter().position(|&count| count > 0).unwrap())
+            .unwrap()
+            .max(1);
+    let largest_observed_value =
+        u8::try_from(histogram.iter().rposition(|&count| count > 0).unwrap())
+            .unwrap()
+            .min(q);
+
+    let mut harmonic_sum = f64::ZERO;
+    for register_value in (smallest_observed_value..=largest_observed_value).rev() {
+        harmonic_sum = 0.5 * harmonic_sum + count(register_value);
+    }
+    harmonic_sum *= f64::integer_exp2_minus(smallest_observed_value);
+
+    // The saturated registers share the term of the largest observed value, which is `q`
+    // whenever there are saturated registers.
+    let mut largest_count = count(maximal_register_value);
+    if q >= 1 {
+        largest_count += count(largest_observed_value);
+    }
+
+    let zeros_weight = harmonic_sum + count(0);
+    let saturated_weight =
+        harmonic_sum + count(maximal_register_value) * f64::integer_exp2_minus(q);
+    let non_zero_registers = f64::from(number_of_registers) - count(0);
+
+    let mut x = if saturated_weight <= 1.5 * zeros_weight {
+        non_zero_registers / (0.5 * saturated_weight + zeros_weight)
+    } else {
+        non_zero_registers / saturated_weight * L::ln_1p(saturated_weight / zeros_weight)
+    };
+    let mut delta_x = x;
+    let mut previous_g = f64::ZERO;
+
+    while delta_x > x * relative_error_limit {
+        let kappa = 2 + floor_log2(x);
+        let mut x_prime =
+            x * f64::integer_exp2_minus_signed(kappa.max(i16::from(largest_observed_value)) + 1);
+        let x_second = x_prime * x_prime;
+        let mut h =
+            x_prime - x_second / 3.0 + x_second * x_second * (1.0 / 45.0 - x_second / 472.5);
+        for _ in i16::from(largest_observed_value)..kappa {
+            h = (x_prime + h * (1.0 - h)) / (x_prime + (1.0 - h));
+            x_prime += x_prime;
+        }
+        let mut g = largest_count * h;
+        for register_value in (smallest_observed_value..largest_observed_value).rev() {
+            h = (x_prime + h * (1.0 - h)) / (x_prime + (1.0 - h));
+            g += count(register_value) * h;
+            x_prime += x_prime;
+        }
+        g += x * zeros_weight;
+
+        delta_x = if g > previous_g && non_zero_registers >= g {
+            delta_x * (non_zero_registers - g) / (g - previous_g)
+        } else {
+            f64::ZERO
+        };
+        x += delta_x;
+        previous_g = g;
+    }
+
+    f64::from(number_of_registers) * x
+}
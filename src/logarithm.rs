@@ -0,0 +1,130 @@
+//! Submodule providing the logarithms employed by the estimators.
+//!
+//! The logarithms are marker types provided to the counters as type parameters, like the bias
+//! corrections of the [`PlusPlus`](crate::prelude::PlusPlus) and
+//! [`LogLogBeta`](crate::prelude::LogLogBeta) counters, so that no feature enabled by a dependency
+//! changes the estimates. The default [`LookupLn`] is available in `no_std`, while [`StdLn`]
+//! requires the `std` feature.
+use core::fmt::Debug;
+use core::hash::Hash;
+
+use crate::utils::math;
+
+#[cfg(any(feature = "beta", feature = "plusplus"))]
+use crate::prelude::*;
+#[cfg(any(feature = "beta", feature = "plusplus"))]
+use crate::utils::{FloatOps, PositiveInteger};
+
+#[cfg(all(feature = "std", any(feature = "beta", feature = "plusplus")))]
+use crate::utils::ToF64;
+
+#[cfg(any(feature = "beta", feature = "plusplus"))]
+include!(concat!(env!("OUT_DIR"), "/ln_values.rs"));
+
+/// Trait for the logarithms employed by the bias corrections and by the maximum likelihood
+/// estimates.
+pub trait Logarithm: Default + Copy + Eq + Hash + Debug + Send + Sync {
+    /// The suffix of the name of the estimators employing the logarithm.
+    const SUFFIX: &'static str;
+
+    /// Returns the natural logarithm of the provided value.
+    fn ln(value: f64) -> f64;
+
+    #[inline]
+    #[must_use]
+    /// Returns the natural logarithm of one plus the provided value, accurately also for small
+    /// values.
+    fn ln_1p(value: f64) -> f64 {
+        let argument = 1.0 + value;
+        let difference = argument - 1.0;
+        if difference == 0.0 {
+            return value;
+        }
+        // The rounding error of the argument is compensated by the one of the difference.
+        Self::ln(argument) * (value / difference)
+    }
+
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    /// Returns the natural logarithm of one plus the provided number of zero registers.
+    fn ln_1p_zeros<P: Precision>(number_of_zero_registers: P::NumberOfRegisters) -> f64;
+
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    /// Returns the linear counting estimate for the provided number of zero registers.
+    fn linear_counting<P: Precision>(number_of_zero_registers: P::NumberOfRegisters) -> f64;
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Logarithms available in `no_std`, read from a table precomputed by the build script for the
+/// numbers of registers and otherwise computed with the series of the crate.
+pub struct LookupLn;
+
+impl Logarithm for LookupLn {
+    const SUFFIX: &'static str = "";
+
+    #[inline]
+    fn ln(value: f64) -> f64 {
+        math::ln(value)
+    }
+
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    #[inline]
+    fn ln_1p_zeros<P: Precision>(number_of_zero_registers: P::NumberOfRegisters) -> f64 {
+        LN_VALUES[1 + number_of_zero_registers.to_usize()]
+    }
+
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    #[inline]
+    fn linear_counting<P: Precision>(number_of_zero_registers: P::NumberOfRegisters) -> f64 {
+        f64::integer_exp2(P::EXPONENT)
+            * (f64::from(P::EXPONENT) * LN_2 - LN_VALUES[number_of_zero_registers.to_usize()])
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Logarithms computed with the functions of the standard library.
+pub struct StdLn;
+
+#[cfg(feature = "std")]
+impl Logarithm for StdLn {
+    const SUFFIX: &'static str = "-std-ln";
+
+    #[inline]
+    fn ln(value: f64) -> f64 {
+        value.ln()
+    }
+
+    #[inline]
+    fn ln_1p(value: f64) -> f64 {
+        value.ln_1p()
+    }
+
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    #[inline]
+    fn ln_1p_zeros<P: Precision>(number_of_zero_registers: P::NumberOfRegisters) -> f64 {
+        f64::ln_1p(number_of_zero_registers.to_f64())
+    }
+
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
+    #[inline]
+    fn linear_counting<P: Precision>(number_of_zero_registers: P::NumberOfRegisters) -> f64 {
+        f64::integer_exp2(P::EXPONENT)
+            * f64::ln_1p(
+                (f64::integer_exp2(P::EXPONENT) - number_of_zero_registers.to_f64())
+                    / number_of_zero_registers.to_f64(),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_ln_1p() {
+        for value in [1e-20_f64, 1e-10, 1e-3, 0.1, 0.5, 1.0, 10.0, 1e10] {
+            let expected = value.ln_1p();
+            assert!((LookupLn::ln_1p(value) - expected).abs() <= 4.0 * f64::EPSILON * expected);
+        }
+    }
+}
@@ -2,6 +2,7 @@
 use crate::basicloglog::BasicLogLog;
 use crate::hll_impl;
 use crate::prelude::*;
+use core::marker::PhantomData;
 
 #[cfg(feature = "std")]
 use core::any::type_name;
@@ -12,42 +13,58 @@ use crate::utils::Named;
 #[derive(Debug, Clone, Copy, Default)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 /// A struct implementing the [`LogLogBeta`] algorithm.
+///
+/// The bias correction of the estimates is selected with the `C` type parameter, see
+/// [`BetaBias`] for the available strategies.
 pub struct LogLogBeta<
     P: Precision,
     B: Bits,
     R: Registers<P, B>,
     Hasher: HasherType = twox_hash::XxHash64,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+    C: BetaCorrection = BetaBias,
 > {
     /// The underlying `BasicLogLog` counter.
-    counter: BasicLogLog<P, B, R, Hasher>,
+    counter: BasicLogLog<P, B, R, Hasher, Builder>,
+    /// The bias correction of the estimates.
+    correction: PhantomData<C>,
 }
 
-hll_impl!(LogLogBeta<P, B, R, Hasher>);
+hll_impl!(LogLogBeta<P, B, R, Hasher, Builder, C>, C: BetaCorrection);
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType>
-    From<BasicLogLog<P, B, R, Hasher>> for LogLogBeta<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > From<BasicLogLog<P, B, R, Hasher, Builder>> for LogLogBeta<P, B, R, Hasher, Builder, C>
 {
     #[inline]
-    fn from(counter: BasicLogLog<P, B, R, Hasher>) -> Self {
-        Self { counter }
+    fn from(counter: BasicLogLog<P, B, R, Hasher, Builder>) -> Self {
+        Self {
+            counter,
+            correction: PhantomData,
+        }
     }
 }
 
 #[cfg(feature = "std")]
-impl<P: Precision + Named, B: Bits + Named, R: Registers<P, B> + Named, Hasher: HasherType> Named
-    for LogLogBeta<P, B, R, Hasher>
+impl<
+        P: Precision + Named,
+        B: Bits + Named,
+        R: Registers<P, B> + Named,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > Named for LogLogBeta<P, B, R, Hasher, Builder, C>
 {
     #[inline]
     fn name(&self) -> String {
-        #[cfg(feature = "precomputed_beta")]
-        let estimator_name = "LLPB";
-        #[cfg(not(feature = "precomputed_beta"))]
-        let estimator_name = "LLB";
-        #[cfg(feature = "std_ln")]
-        let estimator_name = format!("{estimator_name}-std-ln");
-
         format!(
-            "{estimator_name}<{}, {}, {}> + {}",
+            "{}<{}, {}, {}> + {}",
+            C::estimator_name(),
             P::default().name(),
             B::default().name(),
             self.registers().name(),
@@ -56,19 +73,31 @@ impl<P: Precision + Named, B: Bits + Named, R: Registers<P, B> + Named, Hasher:
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
-    for LogLogBeta<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > HyperLogLog for LogLogBeta<P, B, R, Hasher, Builder, C>
 {
     type Registers = R;
     type Precision = P;
     type Bits = B;
     type Hasher = Hasher;
+    type HasherBuilder = Builder;
 
     #[inline]
     fn registers(&self) -> &Self::Registers {
         self.counter.registers()
     }
 
+    #[inline]
+    fn hasher_builder(&self) -> &Builder {
+        self.counter.hasher_builder()
+    }
+
     #[inline]
     fn get_number_of_zero_registers(&self) -> <P as Precision>::NumberOfRegisters {
         self.counter.get_number_of_zero_registers()
@@ -85,33 +114,82 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
     }
 
     #[inline]
-    fn from_registers(registers: R) -> Self {
-        Self {
-            counter: HyperLogLog::from_registers(registers),
-        }
+    fn register_histogram(&self) -> <Self::Bits as Bits>::Histogram {
+        self.counter.register_histogram()
+    }
+
+    #[inline]
+    fn from_registers_with_hasher_builder(registers: R, hasher_builder: Builder) -> Self {
+        Self::from(BasicLogLog::from_registers_with_hasher_builder(
+            registers,
+            hasher_builder,
+        ))
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType + HasherId,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > BinaryFormat for LogLogBeta<P, B, R, Hasher, Builder, C>
+{
+    const ESTIMATOR: u8 = LOGLOGBETA_ESTIMATOR;
+
+    #[inline]
+    fn header(&self) -> BinaryHeader {
+        self.counter.binary_header(Self::ESTIMATOR)
+    }
+
+    #[inline]
+    fn words(&self) -> &[u64] {
+        self.registers().words()
+    }
+
+    #[inline]
+    fn from_header_and_words(header: &BinaryHeader, words: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from(BasicLogLog::from_binary(
+            header,
+            words,
+            Self::ESTIMATOR,
+        )?))
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Correction
-    for LogLogBeta<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > Correction for LogLogBeta<P, B, R, Hasher, Builder, C>
 {
     #[inline]
     fn correction(
         harmonic_sum: f64,
         number_of_zero_registers: <Self::Precision as Precision>::NumberOfRegisters,
     ) -> f64 {
-        P::plusplus_estimate(harmonic_sum, number_of_zero_registers)
+        C::estimate::<P>(harmonic_sum, number_of_zero_registers)
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Estimator<f64>
-    for LogLogBeta<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: BetaCorrection,
+    > Estimator<f64> for LogLogBeta<P, B, R, Hasher, Builder, C>
 where
     Self: HyperLogLog<Precision = P, Bits = B, Registers = R, Hasher = Hasher>,
 {
     #[inline]
     fn estimate_cardinality(&self) -> f64 {
-        P::beta_estimate(self.harmonic_sum(), self.get_number_of_zero_registers())
+        C::estimate::<P>(self.harmonic_sum(), self.get_number_of_zero_registers())
     }
 
     #[inline]
@@ -128,7 +206,7 @@ where
         correct_union_estimate(
             self_cardinality,
             other_cardinality,
-            P::beta_estimate(harmonic_sum, number_of_zero_registers),
+            C::estimate::<P>(harmonic_sum, number_of_zero_registers),
         )
     }
 }
@@ -1,20 +1,36 @@
 //! Struct marker MLE.
 
+use crate::likelihood::{maximum_likelihood_estimate, relative_error_limit};
+use crate::poisson::{contains_counter, iter_terms, Term};
 use crate::prelude::*;
-use core::cmp::Ordering;
+use crate::utils::math::{exp, exp_m1, sqrt};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::hash::Hash;
+use core::marker::PhantomData;
 use core::ops::{Add, Mul, Sub};
 
 #[derive(Debug, Clone, Copy, Hash, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 /// A struct representing the Maximum Likelihood Estimation.
-pub struct MLE<H, const ERROR: i32 = 2> {
+///
+/// The cardinality of the counter is estimated with the secant method on the histogram of its
+/// registers, while the cardinalities of set operations are estimated with the joint likelihood
+/// of the registers of the counters. The `ERROR` sets the relative error limit `10^-ERROR / sqrt(m)`
+/// at which the solvers stop, and the logarithm `L` is the one employed by the estimates.
+///
+/// When all of the registers are saturated the likelihood has no finite maximum, and the
+/// cardinality estimate falls back to the one of the wrapped counter.
+pub struct MLE<H, const ERROR: i32 = 2, L = LookupLn> {
     /// The underlying counter.
     counter: H,
+    /// The logarithm employed by the estimates.
+    logarithm: PhantomData<L>,
 }
 
-impl<H: ExtendableApproximatedSet<T>, T: Hash, const ERROR: i32> ExtendableApproximatedSet<T>
-    for MLE<H, ERROR>
+impl<H: ExtendableApproximatedSet<T>, T: Hash, const ERROR: i32, L: Logarithm>
+    ExtendableApproximatedSet<T> for MLE<H, ERROR, L>
 {
     #[inline]
     fn insert(&mut self, element: &T) -> bool {
@@ -22,8 +38,24 @@ impl<H: ExtendableApproximatedSet<T>, T: Hash, const ERROR: i32> ExtendableAppro
     }
 }
 
-impl<H: Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>, const ERROR: i32>
-    Hybridazable<CH> for MLE<H, ERROR>
+impl<H: ExtendableHashes, const ERROR: i32, L: Logarithm> ExtendableHashes for MLE<H, ERROR, L> {
+    #[inline]
+    fn insert_hash(&mut self, hash: u64) -> bool {
+        self.counter.insert_hash(hash)
+    }
+
+    #[inline]
+    fn extend_hashes(&mut self, hashes: &[u64]) {
+        self.counter.extend_hashes(hashes);
+    }
+}
+
+impl<
+        H: Hybridazable<CH>,
+        CH: CompositeHash<H::Precision, H::Bits>,
+        const ERROR: i32,
+        L: Logarithm,
+    > Hybridazable<CH> for MLE<H, ERROR, L>
 {
     type IterSortedHashes<'words> = H::IterSortedHashes<'words> where Self: 'words, CH: 'words;
 
@@ -46,6 +78,7 @@ impl<H: Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>, const ERROR:
     fn new_hybrid() -> Self {
         Self {
             counter: Hybridazable::new_hybrid(),
+            logarithm: PhantomData,
         }
     }
 
@@ -73,44 +106,73 @@ impl<H: Hybridazable<CH>, CH: CompositeHash<H::Precision, H::Bits>, const ERROR:
     fn hybrid_insert<T: core::hash::Hash>(&mut self, element: &T) -> bool {
         self.counter.hybrid_insert(element)
     }
+
+    #[inline]
+    fn insert_composite_hash(&mut self, composite_hash: CH::Word) -> bool {
+        self.counter.insert_composite_hash(composite_hash)
+    }
+
+    #[inline]
+    fn insert_sorted_composite_hashes(&mut self, composite_hashes: &[CH::Word]) -> bool {
+        self.counter
+            .insert_sorted_composite_hashes(composite_hashes)
+    }
 }
 
-impl<H: BitOr<Output = H>, const ERROR: i32> BitOr for MLE<H, ERROR> {
+impl<
+        H: ShrinkableHybrid<CH, NCH>,
+        CH: CompositeHash<H::Precision, H::Bits>,
+        NCH: CompositeHash<H::Precision, H::Bits>,
+        const ERROR: i32,
+        L: Logarithm,
+    > ShrinkableHybrid<CH, NCH> for MLE<H, ERROR, L>
+{
+    #[inline]
+    fn shrink_composite_hashes(&mut self) {
+        ShrinkableHybrid::<CH, NCH>::shrink_composite_hashes(&mut self.counter);
+    }
+}
+
+impl<H: BitOr<Output = H>, const ERROR: i32, L: Logarithm> BitOr for MLE<H, ERROR, L> {
     type Output = Self;
 
     #[inline]
     fn bitor(self, rhs: Self) -> Self::Output {
-        Self {
-            counter: self.counter | rhs.counter,
-        }
+        Self::from(self.counter | rhs.counter)
     }
 }
 
-impl<H: BitOrAssign, const ERROR: i32> BitOrAssign for MLE<H, ERROR> {
+impl<H: BitOrAssign, const ERROR: i32, L: Logarithm> BitOrAssign for MLE<H, ERROR, L> {
     #[inline]
     fn bitor_assign(&mut self, rhs: Self) {
         self.counter |= rhs.counter;
     }
 }
 
-impl<H: MutableSet, const ERROR: i32> MutableSet for MLE<H, ERROR> {
+impl<H: MutableSet, const ERROR: i32, L: Logarithm> MutableSet for MLE<H, ERROR, L> {
     #[inline]
     fn clear(&mut self) {
         self.counter.clear();
     }
 }
 
-impl<H: HyperLogLog, const ERROR: i32> HyperLogLog for MLE<H, ERROR> {
+impl<H: HyperLogLog, const ERROR: i32, L: Logarithm> HyperLogLog for MLE<H, ERROR, L> {
     type Registers = H::Registers;
     type Precision = H::Precision;
     type Bits = H::Bits;
     type Hasher = H::Hasher;
+    type HasherBuilder = H::HasherBuilder;
 
     #[inline]
     fn registers(&self) -> &Self::Registers {
         self.counter.registers()
     }
 
+    #[inline]
+    fn hasher_builder(&self) -> &Self::HasherBuilder {
+        self.counter.hasher_builder()
+    }
+
     #[inline]
     fn get_number_of_zero_registers(&self) -> <H::Precision as Precision>::NumberOfRegisters {
         self.counter.get_number_of_zero_registers()
@@ -127,70 +189,109 @@ impl<H: HyperLogLog, const ERROR: i32> HyperLogLog for MLE<H, ERROR> {
     }
 
     #[inline]
-    fn from_registers(registers: H::Registers) -> Self {
+    fn register_histogram(&self) -> <Self::Bits as Bits>::Histogram {
+        self.counter.register_histogram()
+    }
+
+    #[inline]
+    fn from_registers_with_hasher_builder(
+        registers: H::Registers,
+        hasher_builder: H::HasherBuilder,
+    ) -> Self {
+        Self::from(H::from_registers_with_hasher_builder(
+            registers,
+            hasher_builder,
+        ))
+    }
+}
+
+impl<H, const ERROR: i32, L: Logarithm> From<H> for MLE<H, ERROR, L> {
+    #[inline]
+    fn from(counter: H) -> Self {
         Self {
-            counter: HyperLogLog::from_registers(registers),
+            counter,
+            logarithm: PhantomData,
         }
     }
 }
 
-impl<H, const ERROR: i32> From<H> for MLE<H, ERROR> {
+impl<H: HyperLogLog, const ERROR: i32, L: Logarithm> TryFrom<&[u8]> for MLE<H, ERROR, L> {
+    type Error = Error;
+
     #[inline]
-    fn from(counter: H) -> Self {
-        Self { counter }
+    fn try_from(values: &[u8]) -> Result<Self, Self::Error> {
+        H::try_from_register_values(values).map(Self::from)
+    }
+}
+
+impl<H: BinaryFormat, const ERROR: i32, L: Logarithm> BinaryFormat for MLE<H, ERROR, L> {
+    const ESTIMATOR: u8 = H::ESTIMATOR | MLE_ESTIMATOR_FLAG;
+
+    #[inline]
+    fn header(&self) -> BinaryHeader {
+        BinaryHeader {
+            estimator: Self::ESTIMATOR,
+            ..self.counter.header()
+        }
+    }
+
+    #[inline]
+    fn words(&self) -> &[u64] {
+        self.counter.words()
+    }
+
+    #[inline]
+    fn from_header_and_words(header: &BinaryHeader, words: &[u8]) -> Result<Self, Error> {
+        if header.estimator != Self::ESTIMATOR {
+            return Err(Error::EstimatorMismatch {
+                expected: Self::ESTIMATOR,
+                found: header.estimator,
+            });
+        }
+        let header = BinaryHeader {
+            estimator: H::ESTIMATOR,
+            ..*header
+        };
+        Ok(Self::from(H::from_header_and_words(&header, words)?))
     }
 }
 
 #[cfg(feature = "std")]
-impl<const ERROR: i32, H: Named> Named for MLE<H, ERROR>
+impl<const ERROR: i32, L: Logarithm, H: Named> Named for MLE<H, ERROR, L>
 where
     Self: Default,
 {
     #[inline]
     fn name(&self) -> String {
-        format!("MLE{}{}", ERROR, self.counter.name())
+        format!("MLE{}{}{}", ERROR, self.counter.name(), L::SUFFIX)
     }
 }
 
 /// Compute the union cardinality using the Maximum Likelihood Estimation.
-fn mle_union_cardinality<
-    P: Precision,
-    B: Bits,
-    I: ExactSizeIterator<Item = [u8; 2]>,
-    const ERROR: i32,
->(
-    registers: I,
+fn mle_union_cardinality<P: Precision, B: Bits, const ERROR: i32, L: Logarithm>(
+    joint_histogram: &JointRegisterHistogram<B>,
     left_cardinality: f64,
     right_cardinality: f64,
     estimate: fn(f64, P::NumberOfRegisters) -> f64,
 ) -> f64 {
-    let mut left_multiplicities_larger = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
-    let mut left_multiplicities_smaller = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
-    let mut right_multiplicities_larger = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
-    let mut right_multiplicities_smaller = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
-    let mut joint_multiplicities = vec![f64::ZERO; 1 << B::NUMBER_OF_BITS];
-    let mut union_harmonic_sum = f64::ZERO;
-    let mut union_zeros = P::NumberOfRegisters::ZERO;
-
-    for [left_register, right_register] in registers {
-        let cmp = left_register.cmp(&right_register);
-
-        let larger_register = if cmp == Ordering::Greater || cmp == Ordering::Equal {
-            left_register
-        } else {
-            right_register
-        };
-        let left_register = usize::from(left_register);
-        let right_register = usize::from(right_register);
-        left_multiplicities_smaller[left_register] += f64::from(cmp == Ordering::Less);
-        right_multiplicities_larger[right_register] += f64::from(cmp == Ordering::Less);
-        left_multiplicities_larger[left_register] += f64::from(cmp == Ordering::Greater);
-        right_multiplicities_smaller[right_register] += f64::from(cmp == Ordering::Greater);
-        joint_multiplicities[left_register] += f64::from(cmp == Ordering::Equal);
-
-        union_harmonic_sum += f64::integer_exp2_minus(larger_register);
-        union_zeros += P::NumberOfRegisters::from(larger_register.is_zero());
-    }
+    let multiplicity = |histogram: &B::Histogram, register_value: usize| -> f64 {
+        f64::from(histogram.as_ref()[register_value])
+    };
+    let left_multiplicities_larger = &joint_histogram.left_larger;
+    let left_multiplicities_smaller = &joint_histogram.left_smaller;
+    let right_multiplicities_larger = &joint_histogram.right_larger;
+    let right_multiplicities_smaller = &joint_histogram.right_smaller;
+    let joint_multiplicities = &joint_histogram.equal;
+
+    let union_histogram = joint_histogram.union();
+    let union_harmonic_sum: f64 = (0..=u8::try_from(B::MASK).unwrap())
+        .map(|register_value| {
+            multiplicity(&union_histogram, usize::from(register_value))
+                * f64::integer_exp2_minus(register_value)
+        })
+        .sum();
+    let union_zeros =
+        P::NumberOfRegisters::try_from_u64(u64::from(union_histogram.as_ref()[0])).unwrap();
 
     // We get the best estimates from HyperLogLog++
     let union_cardinality = estimate(union_harmonic_sum, union_zeros);
@@ -210,20 +311,20 @@ fn mle_union_cardinality<
 
     let right_difference: f64 = (union_cardinality - left_cardinality).max(f64::EPSILON);
 
-    let relative_error_limit = 10.0_f64.powi(-ERROR) / f64::integer_exp2(P::EXPONENT).sqrt();
+    let relative_error_limit = relative_error_limit::<P>(ERROR);
 
     // we introdce the following expressions to simplify the computation
     // of the gradient.
     let x = |phi: [f64; 3], two_to_minus_register: f64| -> [f64; 3] {
         [
-            (phi[0].exp() * two_to_minus_register).max(f64::EPSILON),
-            (phi[1].exp() * two_to_minus_register).max(f64::EPSILON),
-            (phi[2].exp() * two_to_minus_register).max(f64::EPSILON),
+            (exp(phi[0]) * two_to_minus_register).max(f64::EPSILON),
+            (exp(phi[1]) * two_to_minus_register).max(f64::EPSILON),
+            (exp(phi[2]) * two_to_minus_register).max(f64::EPSILON),
         ]
     };
 
     let yz = |x: [f64; 3]| -> ([f64; 3], [f64; 3]) {
-        let exp_m1 = [(-x[0]).exp_m1(), (-x[1]).exp_m1(), (-x[2]).exp_m1()];
+        let exp_m1 = [exp_m1(-x[0]), exp_m1(-x[1]), exp_m1(-x[2])];
 
         (
             [
@@ -241,24 +342,30 @@ fn mle_union_cardinality<
 
     // We initialize the vectors for the Adam optimizer.
     let mut phis = [
-        left_difference.ln(),
-        right_difference.ln(),
-        intersection.ln(),
+        L::ln(left_difference),
+        L::ln(right_difference),
+        L::ln(intersection),
     ];
     let mut gradients: [f64; 3] = [f64::ZERO, f64::ZERO, f64::ZERO];
 
     let mut optimizer: Adam<3> = Adam::default();
 
     let zeros_0: [f64; 3] = [
-        left_multiplicities_smaller[0] + left_multiplicities_larger[0] + joint_multiplicities[0],
-        right_multiplicities_smaller[0] + right_multiplicities_larger[0] + joint_multiplicities[0],
-        right_multiplicities_smaller[0] + left_multiplicities_smaller[0] + joint_multiplicities[0],
+        multiplicity(left_multiplicities_smaller, 0)
+            + multiplicity(left_multiplicities_larger, 0)
+            + multiplicity(joint_multiplicities, 0),
+        multiplicity(right_multiplicities_smaller, 0)
+            + multiplicity(right_multiplicities_larger, 0)
+            + multiplicity(joint_multiplicities, 0),
+        multiplicity(right_multiplicities_smaller, 0)
+            + multiplicity(left_multiplicities_smaller, 0)
+            + multiplicity(joint_multiplicities, 0),
     ];
 
     let zeros_q: [f64; 3] = [
-        left_multiplicities_larger[usize::from(q_plus_one)],
-        right_multiplicities_larger[usize::from(q_plus_one)],
-        joint_multiplicities[usize::from(q_plus_one)],
+        multiplicity(left_multiplicities_larger, usize::from(q_plus_one)),
+        multiplicity(right_multiplicities_larger, usize::from(q_plus_one)),
+        multiplicity(joint_multiplicities, usize::from(q_plus_one)),
     ];
 
     let two_to_zero: f64 = f64::integer_exp2_minus(P::EXPONENT);
@@ -286,11 +393,12 @@ fn mle_union_cardinality<
             let x_register = x(phis, two_to_minus_register);
             let (y_register, z_register) = yz(x_register);
 
-            let joint_k = joint_multiplicities[usize::from(register_value)];
-            let left_smaller_k = left_multiplicities_smaller[usize::from(register_value)];
-            let left_larger_k = left_multiplicities_larger[usize::from(register_value)];
-            let right_smaller_k = right_multiplicities_smaller[usize::from(register_value)];
-            let right_larger_k = right_multiplicities_larger[usize::from(register_value)];
+            let register_value = usize::from(register_value);
+            let joint_k = multiplicity(joint_multiplicities, register_value);
+            let left_smaller_k = multiplicity(left_multiplicities_smaller, register_value);
+            let left_larger_k = multiplicity(left_multiplicities_larger, register_value);
+            let right_smaller_k = multiplicity(right_multiplicities_smaller, register_value);
+            let right_larger_k = multiplicity(right_multiplicities_larger, register_value);
 
             let yjoint_right_zleft = y_register[2] * z_register[0] * y_register[1];
             let yjoint_left_zright = y_register[2] * z_register[1] * y_register[0];
@@ -334,7 +442,7 @@ fn mle_union_cardinality<
         }
     }
 
-    phis[0].exp() + phis[1].exp() + phis[2].exp()
+    exp(phis[0]) + exp(phis[1]) + exp(phis[2])
 }
 
 /// Trait for element-wise multiplication.
@@ -401,22 +509,248 @@ impl<const N: usize, T: Default + Copy + Add<T, Output = T>> ElementWiseAddition
     }
 }
 
-impl<const ERROR: i32, H: Correction> Correction for MLE<H, ERROR> {
+impl<const ERROR: i32, L: Logarithm, H: Correction> Correction for MLE<H, ERROR, L> {
     fn correction(
         harmonic_sum: f64,
         number_of_zero_registers: <Self::Precision as Precision>::NumberOfRegisters,
     ) -> f64 {
         H::correction(harmonic_sum, number_of_zero_registers)
     }
+
+    #[inline]
+    fn estimate_union_of<'counters, I>(counters: I) -> f64
+    where
+        I: IntoIterator<Item = &'counters Self>,
+        Self: 'counters,
+    {
+        // The union is estimated from the histogram of the maximal registers, as the
+        // cardinality of a single counter.
+        let mut registers = H::Registers::default();
+        for counter in counters {
+            registers.set_greater_registers(counter.registers(), |_, _| {});
+        }
+        maximum_likelihood_estimate::<L>(
+            registers.register_histogram().as_ref(),
+            Self::maximal_register_value(),
+            relative_error_limit::<H::Precision>(ERROR),
+        )
+    }
+
+    #[inline]
+    /// Returns the estimated cardinality of the intersection of the provided counters, using
+    /// the joint Maximum Likelihood Estimation of the cardinalities of all of the regions of
+    /// their Venn diagram.
+    ///
+    /// # Implementative details
+    /// The number of regions doubles with each counter, and therefore at most 5 counters are
+    /// supported. The estimate of a single counter is the one of the underlying counter, and
+    /// the intersection of no counters is considered empty. With the `alloc` feature, the
+    /// registers holding the same values in all of the counters are grouped before the
+    /// optimization, which is otherwise considerably slower.
+    ///
+    /// # Errors
+    /// * [`Error::TooManyCounters`] if more than 5 counters are provided.
+    fn estimate_intersection_of(counters: &[&Self]) -> Result<f64, Error> {
+        match counters.len() {
+            0 => Ok(f64::ZERO),
+            1 => Ok(Self::estimate_union_of(counters.iter().copied())),
+            2 => Ok(mle_intersection_cardinality::<Self, 3, ERROR, L>(counters)),
+            3 => Ok(mle_intersection_cardinality::<Self, 7, ERROR, L>(counters)),
+            4 => Ok(mle_intersection_cardinality::<Self, 15, ERROR, L>(counters)),
+            5 => Ok(mle_intersection_cardinality::<Self, 31, ERROR, L>(counters)),
+            found => Err(Error::TooManyCounters { maximum: 5, found }),
+        }
+    }
 }
 
-impl<const ERROR: i32, H> Estimator<f64> for MLE<H, ERROR>
+#[cfg(feature = "alloc")]
+/// Groups the registers of the counters by the values they hold, returning the number of
+/// registers in each group and the terms of the probability of their values.
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "The number of registers is at most 2^18, and is exactly represented in an f64."
+)]
+fn group_registers<H: HyperLogLog, const REGIONS: usize>(
+    counters: &[&H],
+) -> Vec<(f64, Vec<Term<REGIONS>>)> {
+    // The values of a register in the counters are packed in the bytes of an u64.
+    let mut registers: Vec<_> = counters
+        .iter()
+        .map(|counter| counter.registers().iter_registers())
+        .collect();
+    let mut observations: Vec<u64> = (0..H::Precision::NUMBER_OF_REGISTERS.to_usize())
+        .map(|_| {
+            registers
+                .iter_mut()
+                .enumerate()
+                .fold(0, |observation, (index, registers)| {
+                    observation | u64::from(registers.next().unwrap_or(0)) << (8 * index)
+                })
+        })
+        .collect();
+    observations.sort_unstable();
+
+    let mut groups: Vec<(f64, Vec<Term<REGIONS>>)> = Vec::new();
+    for group in observations.chunk_by(|left, right| left == right) {
+        let values = group[0].to_le_bytes();
+        let terms = iter_terms::<H, REGIONS>(&values[..counters.len()]).collect();
+        groups.push((group.len() as f64, terms));
+    }
+    groups
+}
+
+/// Adds the gradients of the log-likelihood of a group of registers holding the same values
+/// with respect to the logarithms of the cardinalities of the regions.
+///
+/// # Arguments
+/// * `gradients` - The gradients to update.
+/// * `cardinalities` - The cardinalities of the regions.
+/// * `multiplicity` - The number of registers in the group.
+/// * `terms` - The terms of the probability of the values of the registers.
+fn add_register_gradients<const REGIONS: usize>(
+    gradients: &mut [f64; REGIONS],
+    cardinalities: &[f64; REGIONS],
+    multiplicity: f64,
+    terms: impl IntoIterator<Item = impl Borrow<Term<REGIONS>>>,
+) {
+    let mut probability = f64::ZERO;
+    let mut derivatives = [f64::ZERO; REGIONS];
+    for term in terms {
+        let term = term.borrow();
+        let value = term.value(cardinalities);
+        probability += value;
+        for (derivative, rate) in derivatives.iter_mut().zip(term.rates.iter()) {
+            *derivative -= value * rate;
+        }
+    }
+
+    // The derivatives are taken with respect to the logarithms of the cardinalities.
+    let weight = multiplicity / probability.max(f64::MIN_POSITIVE);
+    for ((gradient, derivative), cardinality) in gradients
+        .iter_mut()
+        .zip(derivatives.iter())
+        .zip(cardinalities.iter())
+    {
+        *gradient += weight * derivative * cardinality;
+    }
+}
+
+/// Compute the intersection cardinality of two or more counters using the joint Maximum
+/// Likelihood Estimation.
+///
+/// # Implementative details
+/// The cardinalities of the `REGIONS = 2^N - 1` regions of the Venn diagram of the `N` counters
+/// are the parameters of the Poisson model by Ertl, described in the [`poisson`](crate::poisson)
+/// submodule. The log-likelihood of all of the registers is maximized with the Adam optimizer,
+/// starting from the region cardinalities obtained from the union estimates, and the cardinality
+/// of the region shared by all of the counters is returned.
+fn mle_intersection_cardinality<
+    H: Correction,
+    const REGIONS: usize,
+    const ERROR: i32,
+    L: Logarithm,
+>(
+    counters: &[&H],
+) -> f64 {
+    debug_assert_eq!(REGIONS, (1 << counters.len()) - 1);
+
+    let (_, union_zeros) = H::union_harmonic_sum_and_zeros(counters.iter().copied());
+    if union_zeros == H::Precision::NUMBER_OF_REGISTERS {
+        return f64::ZERO;
+    }
+
+    // We estimate the union cardinality of each subset of counters, indexed by its bitmask.
+    let mut unions = [f64::ZERO; 32];
+    for (subset, union) in unions.iter_mut().enumerate().take(REGIONS + 1).skip(1) {
+        *union = H::estimate_union_of(
+            counters
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| contains_counter(subset, *index))
+                .map(|(_, counter)| *counter),
+        );
+    }
+
+    // The elements of the union that are not in the union of the complement of a subset are
+    // the ones of the regions contained in the subset, and the cardinality of each region
+    // is obtained from them with the Möbius inversion over the subsets of the region.
+    let mut phis = [f64::ZERO; REGIONS];
+    for (region, phi) in (1..=REGIONS).zip(phis.iter_mut()) {
+        let cardinality: f64 = (1..=REGIONS)
+            .filter(|subset| subset & !region == 0)
+            .map(|subset| {
+                let contained = unions[REGIONS] - unions[REGIONS ^ subset];
+                if (region ^ subset).count_ones() % 2 == 0 {
+                    contained
+                } else {
+                    -contained
+                }
+            })
+            .sum();
+        // Regions estimated as empty start from a single element, as their logarithm
+        // would otherwise be arbitrarily small.
+        *phi = L::ln(cardinality.max(f64::ONE));
+    }
+
+    #[cfg(feature = "alloc")]
+    let groups = group_registers::<H, REGIONS>(counters);
+
+    let relative_error_limit = relative_error_limit::<H::Precision>(ERROR);
+    let mut optimizer: Adam<REGIONS> = Adam::default();
+
+    for _ in 0_u16..10_000_u16 {
+        let cardinalities = phis.map(exp);
+        let mut gradients = [f64::ZERO; REGIONS];
+
+        #[cfg(feature = "alloc")]
+        for (multiplicity, terms) in &groups {
+            add_register_gradients(&mut gradients, &cardinalities, *multiplicity, terms);
+        }
+
+        // Without allocations, the terms of each register are computed at each iteration.
+        #[cfg(not(feature = "alloc"))]
+        for index in 0..H::Precision::NUMBER_OF_REGISTERS.to_usize() {
+            let index = <H::Precision as Precision>::NumberOfRegisters::try_from_u64(
+                u64::try_from(index).unwrap(),
+            )
+            .unwrap();
+            let mut values = [0_u8; 5];
+            for (value, counter) in values.iter_mut().zip(counters) {
+                *value = counter.get_register(index);
+            }
+            add_register_gradients(
+                &mut gradients,
+                &cardinalities,
+                f64::ONE,
+                iter_terms::<H, REGIONS>(&values[..counters.len()]),
+            );
+        }
+
+        optimizer.apply(&mut gradients, &mut phis);
+
+        if gradients
+            .iter()
+            .all(|gradient| gradient.abs() <= relative_error_limit)
+        {
+            break;
+        }
+    }
+
+    exp(phis[REGIONS - 1])
+}
+
+impl<const ERROR: i32, L: Logarithm, H> Estimator<f64> for MLE<H, ERROR, L>
 where
     H: Estimator<f64> + Correction,
 {
     #[inline]
     fn estimate_cardinality(&self) -> f64 {
-        self.counter.estimate_cardinality()
+        let estimate = self.counter.estimate_cardinality_mle::<ERROR, L>();
+        if estimate.is_finite() {
+            estimate
+        } else {
+            self.counter.estimate_cardinality()
+        }
     }
 
     #[inline]
@@ -431,18 +765,11 @@ where
         self_cardinality: f64,
         other_cardinality: f64,
     ) -> f64 {
-        mle_union_cardinality::<
-            <H as HyperLogLog>::Precision,
-            <H as HyperLogLog>::Bits,
-            <<H as HyperLogLog>::Registers as Registers<
-                <H as HyperLogLog>::Precision,
-                <H as HyperLogLog>::Bits,
-            >>::IterZipped<'_>,
-            ERROR,
-        >(
-            self.counter
+        mle_union_cardinality::<<H as HyperLogLog>::Precision, <H as HyperLogLog>::Bits, ERROR, L>(
+            &self
+                .counter
                 .registers()
-                .iter_registers_zipped(other.counter.registers()),
+                .joint_register_histogram(other.counter.registers()),
             self_cardinality,
             other_cardinality,
             <H as Correction>::correction,
@@ -456,8 +783,10 @@ struct Adam<const N: usize> {
     first_moments: [f64; N],
     /// Second moments.
     second_moments: [f64; N],
-    /// Current time.
-    time: i32,
+    /// First order decay factor raised to the current time.
+    first_order_decay_power: f64,
+    /// Second order decay factor raised to the current time.
+    second_order_decay_power: f64,
     /// Learning rate.
     learning_rate: f64,
     /// First order decay factor.
@@ -471,7 +800,8 @@ impl<const N: usize> Default for Adam<N> {
         Adam {
             first_moments: [0.0; N],
             second_moments: [0.0; N],
-            time: 0,
+            first_order_decay_power: 1.0,
+            second_order_decay_power: 1.0,
             learning_rate: 0.1,
             first_order_decay_factor: 0.9,
             second_order_decay_factor: 0.999,
@@ -482,7 +812,8 @@ impl<const N: usize> Default for Adam<N> {
 impl<const N: usize> Adam<N> {
     /// Apply the Adam optimizer to the gradients and weights.
     fn apply(&mut self, gradients: &mut [f64; N], phis: &mut [f64; N]) {
-        self.time += 1_i32;
+        self.first_order_decay_power *= self.first_order_decay_factor;
+        self.second_order_decay_power *= self.second_order_decay_factor;
         self.first_moments
             .iter_mut()
             .zip(self.second_moments.iter_mut())
@@ -491,11 +822,11 @@ impl<const N: usize> Adam<N> {
                 *first_moment = self.first_order_decay_factor * *first_moment
                     + (f64::ONE - self.first_order_decay_factor) * *gradient;
                 *second_moment = self.second_order_decay_factor * *second_moment
-                    + (f64::ONE - self.second_order_decay_factor) * (*gradient).powi(2);
+                    + (f64::ONE - self.second_order_decay_factor) * *gradient * *gradient;
                 let adaptative_learning_rate = self.learning_rate
-                    * (f64::ONE - self.second_order_decay_factor.powi(self.time)).sqrt()
-                    / (f64::ONE - self.first_order_decay_factor.powi(self.time));
-                let second_moment_root = (*second_moment).sqrt();
+                    * sqrt(f64::ONE - self.second_order_decay_power)
+                    / (f64::ONE - self.first_order_decay_power);
+                let second_moment_root = sqrt(*second_moment);
                 *gradient = adaptative_learning_rate * (*first_moment)
                     / if second_moment_root > f64::EPSILON {
                         second_moment_root
@@ -510,7 +841,10 @@ impl<const N: usize> Adam<N> {
 // Minimal test suite with trivial functions to optimize
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
     use super::*;
+    use std::println;
 
     // Test function: f(x) = -(x1 - 1)^2 - (x2 + 2)^2
     fn quadratic_function(phis: &[f64; 2]) -> (f64, [f64; 2]) {
@@ -2,6 +2,7 @@
 use crate::basicloglog::BasicLogLog;
 use crate::hll_impl;
 use crate::prelude::*;
+use core::marker::PhantomData;
 
 #[cfg(feature = "std")]
 use core::any::type_name;
@@ -9,35 +10,38 @@ use core::any::type_name;
 #[derive(Debug, Clone, Copy, Default)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 /// A struct implementing the `HyperLogLog++` algorithm.
+///
+/// The bias correction of the estimates is selected with the `C` type parameter, see
+/// [`PlusPlusBias`] for the available strategies.
 pub struct PlusPlus<
     P: Precision,
     B: Bits,
     R: Registers<P, B>,
     Hasher: HasherType = twox_hash::XxHash64,
+    Builder: HasherBuilder<Hasher = Hasher> = UnseededBuilder<Hasher>,
+    C: PlusPlusCorrection = PlusPlusBias,
 > {
     /// The underlying `BasicLogLog` counter.
-    counter: BasicLogLog<P, B, R, Hasher>,
+    counter: BasicLogLog<P, B, R, Hasher, Builder>,
+    /// The bias correction of the estimates.
+    correction: PhantomData<C>,
 }
 
 #[cfg(feature = "std")]
-impl<P: Precision + Named, B: Bits + Named, R: Registers<P, B> + Named, Hasher: HasherType> Named
-    for PlusPlus<P, B, R, Hasher>
+impl<
+        P: Precision + Named,
+        B: Bits + Named,
+        R: Registers<P, B> + Named,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > Named for PlusPlus<P, B, R, Hasher, Builder, C>
 {
     #[inline]
     fn name(&self) -> String {
-        #[cfg(all(feature = "integer_plusplus", not(feature = "plusplus_kmeans")))]
-        let estimator_name = "PPI";
-        #[cfg(all(feature = "integer_plusplus", feature = "plusplus_kmeans"))]
-        let estimator_name = "PPIK";
-        #[cfg(all(not(feature = "integer_plusplus"), not(feature = "plusplus_kmeans")))]
-        let estimator_name = "PP";
-        #[cfg(all(not(feature = "integer_plusplus"), feature = "plusplus_kmeans"))]
-        let estimator_name = "PPK";
-        #[cfg(feature = "std_ln")]
-        let estimator_name = format!("{estimator_name}-std-ln");
-
         format!(
-            "{estimator_name}<{}, {}, {}> + {}",
+            "{}<{}, {}, {}> + {}",
+            C::estimator_name(),
             P::default().name(),
             B::default().name(),
             self.registers().name(),
@@ -46,30 +50,51 @@ impl<P: Precision + Named, B: Bits + Named, R: Registers<P, B> + Named, Hasher:
     }
 }
 
-hll_impl!(PlusPlus<P, B, R, Hasher>);
+hll_impl!(PlusPlus<P, B, R, Hasher, Builder, C>, C: PlusPlusCorrection);
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType>
-    From<BasicLogLog<P, B, R, Hasher>> for PlusPlus<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > From<BasicLogLog<P, B, R, Hasher, Builder>> for PlusPlus<P, B, R, Hasher, Builder, C>
 {
     #[inline]
-    fn from(counter: BasicLogLog<P, B, R, Hasher>) -> Self {
-        Self { counter }
+    fn from(counter: BasicLogLog<P, B, R, Hasher, Builder>) -> Self {
+        Self {
+            counter,
+            correction: PhantomData,
+        }
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
-    for PlusPlus<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > HyperLogLog for PlusPlus<P, B, R, Hasher, Builder, C>
 {
     type Registers = R;
     type Precision = P;
     type Bits = B;
     type Hasher = Hasher;
+    type HasherBuilder = Builder;
 
     #[inline]
     fn registers(&self) -> &Self::Registers {
         self.counter.registers()
     }
 
+    #[inline]
+    fn hasher_builder(&self) -> &Builder {
+        self.counter.hasher_builder()
+    }
+
     #[inline]
     fn get_number_of_zero_registers(&self) -> <P as Precision>::NumberOfRegisters {
         self.counter.get_number_of_zero_registers()
@@ -86,33 +111,82 @@ impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> HyperLogLog
     }
 
     #[inline]
-    fn from_registers(registers: R) -> Self {
-        Self {
-            counter: HyperLogLog::from_registers(registers),
-        }
+    fn register_histogram(&self) -> <Self::Bits as Bits>::Histogram {
+        self.counter.register_histogram()
+    }
+
+    #[inline]
+    fn from_registers_with_hasher_builder(registers: R, hasher_builder: Builder) -> Self {
+        Self::from(BasicLogLog::from_registers_with_hasher_builder(
+            registers,
+            hasher_builder,
+        ))
+    }
+}
+
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType + HasherId,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > BinaryFormat for PlusPlus<P, B, R, Hasher, Builder, C>
+{
+    const ESTIMATOR: u8 = PLUSPLUS_ESTIMATOR;
+
+    #[inline]
+    fn header(&self) -> BinaryHeader {
+        self.counter.binary_header(Self::ESTIMATOR)
+    }
+
+    #[inline]
+    fn words(&self) -> &[u64] {
+        self.registers().words()
+    }
+
+    #[inline]
+    fn from_header_and_words(header: &BinaryHeader, words: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from(BasicLogLog::from_binary(
+            header,
+            words,
+            Self::ESTIMATOR,
+        )?))
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Correction
-    for PlusPlus<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > Correction for PlusPlus<P, B, R, Hasher, Builder, C>
 {
     #[inline]
     fn correction(
         harmonic_sum: f64,
         number_of_zero_registers: <Self::Precision as Precision>::NumberOfRegisters,
     ) -> f64 {
-        P::plusplus_estimate(harmonic_sum, number_of_zero_registers)
+        C::estimate::<P>(harmonic_sum, number_of_zero_registers)
     }
 }
 
-impl<P: Precision, B: Bits, R: Registers<P, B>, Hasher: HasherType> Estimator<f64>
-    for PlusPlus<P, B, R, Hasher>
+impl<
+        P: Precision,
+        B: Bits,
+        R: Registers<P, B>,
+        Hasher: HasherType,
+        Builder: HasherBuilder<Hasher = Hasher>,
+        C: PlusPlusCorrection,
+    > Estimator<f64> for PlusPlus<P, B, R, Hasher, Builder, C>
 where
     Self: HyperLogLog<Precision = P, Bits = B, Registers = R, Hasher = Hasher>,
 {
     #[inline]
     fn estimate_cardinality(&self) -> f64 {
-        P::plusplus_estimate(self.harmonic_sum(), self.get_number_of_zero_registers())
+        C::estimate::<P>(self.harmonic_sum(), self.get_number_of_zero_registers())
     }
 
     #[inline]
@@ -129,7 +203,7 @@ where
         correct_union_estimate(
             self_cardinality,
             other_cardinality,
-            P::plusplus_estimate(harmonic_sum, number_of_zero_registers),
+            C::estimate::<P>(harmonic_sum, number_of_zero_registers),
         )
     }
 }
@@ -0,0 +1,82 @@
+//! Submodule providing the Poisson model of the registers of one or more counters, by Ertl.
+//!
+//! The elements inserted into `N` counters are partitioned into the `2^N - 1` regions of their
+//! Venn diagram, each identified by the non-empty subset of counters its elements are inserted
+//! into, as a bitmask. Under the Poisson model, the largest value that the elements of a region
+//! with cardinality `λ` set in a register is at most `k` with probability `exp(-λ 2^(-k) / m)`,
+//! independently of the other regions and registers, while each register of a counter holds the
+//! largest value among the regions including the counter. The probability that the registers
+//! of the counters are at most `a_1, ..., a_N` is therefore `exp(-Σ_S λ_S 2^(-min_{j ∈ S} a_j) / m)`,
+//! and the probability of the values observed in a register follows by inclusion–exclusion
+//! over the counters whose value is decreased by one.
+use crate::prelude::*;
+use crate::utils::math::exp;
+
+/// Returns whether the counter with the provided index is in the subset of counters.
+pub(crate) const fn contains_counter(subset: usize, index: usize) -> bool {
+    subset & (1 << index) != 0
+}
+
+/// A term of the probability of the values of a register in the counters.
+pub(crate) struct Term<const REGIONS: usize> {
+    /// Whether the term is added or subtracted.
+    pub(crate) sign: f64,
+    /// The rate of each region, divided by its cardinality, in the exponent of the term.
+    pub(crate) rates: [f64; REGIONS],
+}
+
+impl<const REGIONS: usize> Term<REGIONS> {
+    #[inline]
+    /// Returns the value of the term for the provided cardinalities of the regions.
+    pub(crate) fn value(&self, cardinalities: &[f64; REGIONS]) -> f64 {
+        let exponent: f64 = self
+            .rates
+            .iter()
+            .zip(cardinalities.iter())
+            .map(|(rate, cardinality)| rate * cardinality)
+            .sum();
+        self.sign * exp(-exponent)
+    }
+}
+
+/// Returns the terms of the probability of the provided values of a register in the counters.
+///
+/// # Arguments
+/// * `values` - The values of the register, one per counter, with `REGIONS = 2^N - 1`.
+pub(crate) fn iter_terms<H: HyperLogLog, const REGIONS: usize>(
+    values: &[u8],
+) -> impl Iterator<Item = Term<REGIONS>> + '_ {
+    debug_assert_eq!(REGIONS, (1 << values.len()) - 1);
+    let maximal_register_value = H::maximal_register_value();
+    let rate = move |value: u8| -> f64 {
+        if value < maximal_register_value {
+            f64::integer_exp2_minus(H::Precision::EXPONENT + value)
+        } else {
+            f64::ZERO
+        }
+    };
+
+    (0..=REGIONS)
+        .filter(|decreased| {
+            (0..values.len()).all(|index| !contains_counter(*decreased, index) || values[index] > 0)
+        })
+        .map(move |decreased| {
+            let mut rates = [f64::ZERO; REGIONS];
+            for (region, rate_of_region) in (1..=REGIONS).zip(rates.iter_mut()) {
+                let minimum = (0..values.len())
+                    .filter(|index| contains_counter(region, *index))
+                    .map(|index| values[index] - u8::from(contains_counter(decreased, index)))
+                    .min()
+                    .unwrap_or(0);
+                *rate_of_region = rate(minimum);
+            }
+            Term {
+                sign: if decreased.count_ones() % 2 == 0 {
+                    f64::ONE
+                } else {
+                    -f64::ONE
+                },
+                rates,
+            }
+        })
+}
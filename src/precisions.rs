@@ -9,13 +9,7 @@ use core::{f64, fmt::Debug};
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::{MemDbg, MemSize};
 
-use crate::utils::{FloatOps, Number, One, PositiveInteger, ToF64, VariableWord};
-
-#[cfg(feature = "plusplus")]
-use crate::utils::Two;
-
-#[cfg(all(feature = "beta", not(feature = "precomputed_beta")))]
-use crate::utils::Zero;
+use crate::utils::{FloatOps, One, PositiveInteger, ToF64, VariableWord};
 
 include!(concat!(env!("OUT_DIR"), "/alpha_values.rs"));
 include!(concat!(env!("OUT_DIR"), "/number_of_registers.rs"));
@@ -23,88 +17,15 @@ include!(concat!(env!("OUT_DIR"), "/number_of_registers.rs"));
 #[cfg(feature = "plusplus")]
 include!(concat!(env!("OUT_DIR"), "/weights.rs"));
 
-#[cfg(feature = "zero_count_correction")]
+#[cfg(any(feature = "beta", feature = "plusplus"))]
 include!(concat!(env!("OUT_DIR"), "/linear_count_zeros.rs"));
 
-#[cfg(all(
-    not(feature = "std_ln"),
-    any(
-        all(feature = "beta", not(feature = "precomputed_beta")),
-        feature = "plusplus"
-    )
-))]
-include!(concat!(env!("OUT_DIR"), "/ln_values.rs"));
-
-#[cfg(all(feature = "beta", not(feature = "precomputed_beta")))]
+#[cfg(feature = "beta")]
 include!(concat!(env!("OUT_DIR"), "/beta.rs"));
 
-#[cfg(feature = "precomputed_beta")]
+#[cfg(feature = "beta")]
 include!(concat!(env!("OUT_DIR"), "/beta_horner.rs"));
 
-#[cfg(feature = "plusplus_kmeans")]
-fn kmeans_bias<V: PartialOrd + Number + Two, W: Number>(
-    estimates: &'static [V],
-    biases: &'static [W],
-    estimate: V,
-) -> f64
-where
-    f64: From<W>,
-{
-    let index = estimates
-        .partition_point(|estimate_centroid| estimate_centroid <= &estimate)
-        .max(1)
-        - 1;
-
-    let mut min = if index > 6 { index - 6 } else { 0 };
-    let mut max = core::cmp::min(index + 6, estimates.len());
-
-    while max - min != 6 {
-        let (min_val, max_val) = (estimates[min], estimates[max - 1]);
-        if V::TWO * estimate - min_val > max_val {
-            min += 1;
-        } else {
-            max -= 1;
-        }
-    }
-    biases[min..max].iter().map(|b| f64::from(*b)).sum::<f64>() / 6.0
-}
-
-#[cfg(feature = "plusplus")]
-/// Computes the bias correction factor for the estimate using either
-/// the k-means algorithm or the simpler linear interpolation.
-fn bias<V: PartialOrd + Two + Number, W: Number>(
-    estimates: &'static [V],
-    biases: &'static [W],
-    estimate: V,
-) -> f64
-where
-    f64: From<V> + From<W>,
-{
-    #[cfg(feature = "plusplus_kmeans")]
-    return kmeans_bias(estimates, biases, estimate);
-
-    #[cfg(not(feature = "plusplus_kmeans"))]
-    return {
-        let index = estimates.partition_point(|estimate_centroid| estimate_centroid <= &estimate);
-
-        if index == 0 {
-            return f64::from(biases[0]);
-        }
-
-        if index == estimates.len() {
-            return f64::from(biases[estimates.len() - 1]);
-        }
-
-        let x0 = f64::from(estimates[index - 1]);
-        let x1 = f64::from(estimates[index]);
-
-        let y0 = f64::from(biases[index - 1]);
-        let y1 = f64::from(biases[index]);
-
-        y0 + (y1 - y0) * (f64::from(estimate) - x0) / (x1 - x0)
-    };
-}
-
 /// The precision of the [`HyperLogLog`] counter.
 pub trait Precision: Default + Copy + Eq + Debug + Send + Sync {
     /// The data type to use for the number of zeros registers counter.
@@ -149,122 +70,29 @@ pub trait Precision: Default + Copy + Eq + Debug + Send + Sync {
 
     /// The alpha constant for the precision, used in the estimation of the cardinality.
     const ALPHA: f64;
-    #[cfg(all(feature = "beta", not(feature = "precomputed_beta")))]
+    #[cfg(feature = "beta")]
     /// Beta constants for the LogLog-Beta bias correction.
     const BETA: [f64; 8];
 
-    #[cfg(feature = "precomputed_beta")]
+    #[cfg(feature = "beta")]
     /// Returns the precomputed beta value for the given number of zero registers.
     fn const_beta_horner(number_of_zero_registers: Self::NumberOfRegisters) -> f64;
 
-    #[cfg(feature = "zero_count_correction")]
+    #[cfg(any(feature = "beta", feature = "plusplus"))]
     /// The number of zero registers over which the counter should switch to the linear counting.
     const LINEAR_COUNT_ZEROS: Self::NumberOfRegisters;
-    #[cfg(all(feature = "plusplus", not(feature = "integer_plusplus")))]
+    #[cfg(feature = "plusplus")]
     /// The estimate centroids for the [`PlusPlus`] bias correction.
     const ESTIMATES: &'static [f64];
-    #[cfg(all(feature = "plusplus", not(feature = "integer_plusplus")))]
+    #[cfg(feature = "plusplus")]
     /// The bias values for the [`PlusPlus`] bias correction.
     const BIASES: &'static [f64];
-    #[cfg(all(feature = "plusplus", feature = "integer_plusplus"))]
-    /// The estimate centroids for the [`PlusPlus`] bias correction.
-    const ESTIMATES: &'static [u32];
-    #[cfg(all(feature = "plusplus", feature = "integer_plusplus"))]
-    /// The bias values for the [`PlusPlus`] bias correction.
-    const BIASES: &'static [i32];
-
-    #[cfg(feature = "beta")]
-    #[inline]
-    /// Computes LogLog-Beta estimate bias correction using Horner's method.
-    ///
-    /// Paper: <https://arxiv.org/pdf/1612.02284.pdf>
-    /// Wikipedia: <https://en.wikipedia.org/wiki/Horner%27s_method>
-    fn beta_estimate(harmonic_sum: f64, number_of_zero_registers: Self::NumberOfRegisters) -> f64 {
-        #[cfg(feature = "zero_count_correction")]
-        if number_of_zero_registers >= Self::LINEAR_COUNT_ZEROS {
-            return Self::small_correction(number_of_zero_registers);
-        }
-        #[cfg(not(feature = "precomputed_beta"))]
-        let beta_horner = {
-            #[cfg(not(feature = "std_ln"))]
-            let number_of_zero_registers_ln = LN_VALUES[1 + number_of_zero_registers.to_usize()];
-            #[cfg(feature = "std_ln")]
-            let number_of_zero_registers_ln = f64::ln_1p(number_of_zero_registers.to_f64());
-            let mut res = f64::ZERO;
-            for i in (1..8).rev() {
-                res = res * number_of_zero_registers_ln + Self::BETA[i];
-            }
-            res * number_of_zero_registers_ln + Self::BETA[0] * number_of_zero_registers.to_f64()
-        };
-
-        #[cfg(feature = "precomputed_beta")]
-        let beta_horner = Self::const_beta_horner(number_of_zero_registers);
-
-        Self::ALPHA
-            * f64::integer_exp2(Self::EXPONENT)
-            * (f64::integer_exp2(Self::EXPONENT) - number_of_zero_registers.to_f64())
-            / (harmonic_sum + beta_horner)
-            + 0.5
-    }
-
-    /// Computes the small correction factor for the estimate.
-    #[inline]
-    #[cfg(feature = "zero_count_correction")]
-    fn small_correction(number_of_zero_registers: Self::NumberOfRegisters) -> f64 {
-        #[cfg(not(feature = "std_ln"))]
-        return f64::integer_exp2(Self::EXPONENT)
-            * (f64::from(Self::EXPONENT) * core::f64::consts::LN_2
-                - LN_VALUES[number_of_zero_registers.to_usize()]);
-        #[cfg(feature = "std_ln")]
-        return f64::integer_exp2(Self::EXPONENT)
-            * f64::ln_1p(
-                (f64::integer_exp2(Self::EXPONENT) - number_of_zero_registers.to_f64())
-                    / number_of_zero_registers.to_f64(),
-            );
-    }
-
-    #[must_use]
-    #[inline]
     #[cfg(feature = "plusplus")]
-    #[cfg_attr(
-        feature = "integer_plusplus",
-        expect(clippy::cast_sign_loss, reason = "Cardinality is always positive.")
-    )]
-    #[cfg_attr(
-        feature = "integer_plusplus",
-        expect(
-            clippy::cast_possible_truncation,
-            reason = "Bias is only applied to values smaller than 2**21."
-        )
-    )]
-    /// Computes the bias correction factor for the estimate using the [`PlusPlus`] algorithm.
-    fn bias(estimate: f64) -> f64 {
-        #[cfg(not(feature = "integer_plusplus"))]
-        return bias(Self::ESTIMATES, Self::BIASES, estimate);
-        #[cfg(feature = "integer_plusplus")]
-        return bias(Self::ESTIMATES, Self::BIASES, estimate as u32);
-    }
-
+    /// The integer estimate centroids for the [`PlusPlus`] bias correction.
+    const INTEGER_ESTIMATES: &'static [u32];
     #[cfg(feature = "plusplus")]
-    #[inline]
-    /// Computes the estimate of the cardinality using the [`PlusPlus`] algorithm.
-    fn plusplus_estimate(harmonic_sum: f64, number_of_zeros: Self::NumberOfRegisters) -> f64 {
-        #[cfg(feature = "zero_count_correction")]
-        if number_of_zeros >= Self::LINEAR_COUNT_ZEROS {
-            return Self::small_correction(number_of_zeros);
-        }
-
-        let estimate =
-            Self::ALPHA * f64::integer_exp2(Self::EXPONENT + Self::EXPONENT) / harmonic_sum;
-
-        // Apply the small range correction factor if the raw estimate is below the threshold
-        // and there are zero registers in the counter.
-        if estimate <= 5.0_f64 * f64::integer_exp2(Self::EXPONENT) {
-            estimate - Self::bias(estimate)
-        } else {
-            estimate
-        }
-    }
+    /// The integer bias values for the [`PlusPlus`] bias correction.
+    const INTEGER_BIASES: &'static [i32];
 }
 
 /// Macro to implement the Precision trait for a given precision.
@@ -291,24 +119,24 @@ macro_rules! impl_precision {
                 const EXPONENT: u8 = $exponent;
                 const NUMBER_OF_REGISTERS: Self::NumberOfRegisters = [<NumberOfRegisters $exponent>]::ONE << $exponent;
                 const ALPHA: f64 = [<ALPHA_ $exponent>];
-                #[cfg(all(feature = "beta", not(feature = "precomputed_beta")))]
+                #[cfg(feature = "beta")]
                 const BETA: [f64; 8] = [<BETA_ $exponent>];
-                #[cfg(feature = "precomputed_beta")]
+                #[cfg(feature = "beta")]
                 fn const_beta_horner(number_of_zero_registers: Self::NumberOfRegisters) -> f64 {
                     [<BETA_HORNER_ $exponent>][number_of_zero_registers.to_usize()]
                 }
 
-                #[cfg(feature = "zero_count_correction")]
+                #[cfg(any(feature = "beta", feature = "plusplus"))]
                 const LINEAR_COUNT_ZEROS: Self::NumberOfRegisters = [<LINEAR_COUNT_ZEROS_ $exponent>];
 
-                #[cfg(all(feature = "plusplus", not(feature = "integer_plusplus")))]
+                #[cfg(feature = "plusplus")]
                 const ESTIMATES: &'static [f64] = &[<ESTIMATES_ $exponent>];
-                #[cfg(all(feature = "plusplus", not(feature = "integer_plusplus")))]
+                #[cfg(feature = "plusplus")]
                 const BIASES: &'static [f64] = &[<BIAS_ $exponent>];
-                #[cfg(all(feature = "plusplus", feature = "integer_plusplus"))]
-                const ESTIMATES: &'static [u32] = &[<ESTIMATES_ $exponent>];
-                #[cfg(all(feature = "plusplus", feature = "integer_plusplus"))]
-                const BIASES: &'static [i32] = &[<BIAS_ $exponent>];
+                #[cfg(feature = "plusplus")]
+                const INTEGER_ESTIMATES: &'static [u32] = &[<INTEGER_ESTIMATES_ $exponent>];
+                #[cfg(feature = "plusplus")]
+                const INTEGER_BIASES: &'static [i32] = &[<INTEGER_BIAS_ $exponent>];
             }
         }
     };
@@ -355,6 +183,11 @@ mod tests {
                             assert!(*estimate >= last, "Estimate: {}, Last: {}", *estimate, last);
                             last = *estimate;
                         }
+                        let mut last = [<INTEGER_ESTIMATES_ $exponent>][0];
+                        for estimate in [<INTEGER_ESTIMATES_ $exponent>].iter() {
+                            assert!(*estimate >= last, "Estimate: {}, Last: {}", *estimate, last);
+                            last = *estimate;
+                        }
                     }
                 }
             )*
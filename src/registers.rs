@@ -1,11 +1,18 @@
 //! Submodule providing the trait registers
 
+use core::cmp::Ordering;
 use core::fmt::Debug;
 
 use crate::prelude::*;
 use crate::utils::{FloatOps, Zero};
+#[cfg(target_has_atomic = "64")]
+mod atomic_array;
 mod packed_array;
+#[cfg(feature = "swar")]
+mod swar;
 
+#[cfg(target_has_atomic = "64")]
+pub use atomic_array::{AtomicArray, AtomicRegisters};
 pub use packed_array::{AllArrays, Array, ArrayRegister};
 
 /// Trait for a register word.
@@ -25,6 +32,9 @@ impl<B: Bits> RegisterWord<B> for u64 {
 pub trait Registers<P: Precision, B: Bits>:
     Eq + PartialEq + Clone + Debug + Send + Sync + Default
 {
+    /// Whether the registers are packed, i.e. whether a register may span two words.
+    const PACKED: bool;
+
     /// Iterator over the registers.
     type Iter<'register>: ExactSizeIterator<Item = u8>
     where
@@ -47,6 +57,16 @@ pub trait Registers<P: Precision, B: Bits>:
     /// Returns the harmonic sum of the maximum value of the registers and the number of zero registers.
     fn get_harmonic_sum_and_zeros(&self, other: &Self) -> (f64, P::NumberOfRegisters);
 
+    /// Sets each register to the maximum between its value and the value of the same register in `other`.
+    ///
+    /// # Arguments
+    /// * `other` - The registers to be merged into these registers.
+    /// * `on_increase` - Function called with the previous and the new value of each increased
+    ///   register, following the order of the registers.
+    fn set_greater_registers<F>(&mut self, other: &Self, on_increase: F)
+    where
+        F: FnMut(u8, u8);
+
     /// Applies a function to each register.
     fn apply_to_registers<F>(&mut self, f: F)
     where
@@ -68,4 +88,108 @@ pub trait Registers<P: Precision, B: Bits>:
 
     /// Clears the registers to zero.
     fn clear_registers(&mut self);
+
+    /// Returns the words in which the registers are stored.
+    fn words(&self) -> &[u64];
+
+    /// Returns the mutable words in which the registers are stored.
+    fn words_mut(&mut self) -> &mut [u64];
+
+    #[inline]
+    #[must_use]
+    /// Returns the histogram of the values of the registers.
+    fn register_histogram(&self) -> B::Histogram {
+        let mut histogram = B::Histogram::ZERO;
+        let counts = histogram.as_mut();
+        for register in self.iter_registers() {
+            counts[usize::from(register)] += 1;
+        }
+        histogram
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the joint histogram of the values of the registers and of the registers of `other`.
+    ///
+    /// # Arguments
+    /// * `other` - The other registers.
+    fn joint_register_histogram(&self, other: &Self) -> JointRegisterHistogram<B> {
+        let mut joint = JointRegisterHistogram::<B>::default();
+        for [left, right] in self.iter_registers_zipped(other) {
+            match left.cmp(&right) {
+                Ordering::Less => {
+                    joint.left_smaller.as_mut()[usize::from(left)] += 1;
+                    joint.right_larger.as_mut()[usize::from(right)] += 1;
+                }
+                Ordering::Greater => {
+                    joint.left_larger.as_mut()[usize::from(left)] += 1;
+                    joint.right_smaller.as_mut()[usize::from(right)] += 1;
+                }
+                Ordering::Equal => {
+                    joint.equal.as_mut()[usize::from(left)] += 1;
+                }
+            }
+        }
+        joint
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The joint histogram of the values of the registers of two counters, as defined by Ertl.
+///
+/// Each register is counted in the histograms of exactly one of the three cases: either its
+/// value in the left counter is larger, smaller or equal to its value in the right counter.
+pub struct JointRegisterHistogram<B: Bits> {
+    /// The values of the left registers larger than the corresponding right registers.
+    pub left_larger: B::Histogram,
+    /// The values of the left registers smaller than the corresponding right registers.
+    pub left_smaller: B::Histogram,
+    /// The values of the right registers larger than the corresponding left registers.
+    pub right_larger: B::Histogram,
+    /// The values of the right registers smaller than the corresponding left registers.
+    pub right_smaller: B::Histogram,
+    /// The values of the registers equal in the two counters.
+    pub equal: B::Histogram,
+}
+
+impl<B: Bits> Default for JointRegisterHistogram<B> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            left_larger: B::Histogram::ZERO,
+            left_smaller: B::Histogram::ZERO,
+            right_larger: B::Histogram::ZERO,
+            right_smaller: B::Histogram::ZERO,
+            equal: B::Histogram::ZERO,
+        }
+    }
+}
+
+impl<B: Bits> JointRegisterHistogram<B> {
+    #[inline]
+    #[must_use]
+    /// Returns the histogram of the registers of the left counter.
+    pub fn left(&self) -> B::Histogram {
+        self.left_larger
+            .add_histogram(&self.left_smaller)
+            .add_histogram(&self.equal)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the histogram of the registers of the right counter.
+    pub fn right(&self) -> B::Histogram {
+        self.right_larger
+            .add_histogram(&self.right_smaller)
+            .add_histogram(&self.equal)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the histogram of the registers of the union of the two counters.
+    pub fn union(&self) -> B::Histogram {
+        self.left_larger
+            .add_histogram(&self.right_larger)
+            .add_histogram(&self.equal)
+    }
 }
@@ -0,0 +1,133 @@
+//! Atomic array for registers.
+//!
+//! The atomic array stores the registers with the same layout of the unpacked [`Array`], i.e. as
+//! many registers as they fit in a word starting from its most significant bits, but it wraps each
+//! word in an [`AtomicU64`]. Since no register spans two words, a register can be updated with a
+//! compare-and-swap loop on the single word containing it, and therefore many threads may update
+//! the same registers through a shared reference. The packed arrays are not supported, as updating
+//! a bridge register would require updating two words at once.
+
+use super::packed_array::{
+    extract_value_from_word, insert_value_into_word, split_not_packed_index,
+};
+use super::{Array, Bits, Precision, Registers};
+use crate::utils::{PositiveInteger, VariableWord};
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Trait for registers that may be updated concurrently through a shared reference.
+pub trait AtomicRegisters<P: Precision, B: Bits>: Debug + Default + Send + Sync {
+    /// The registers holding a copy of the values of the atomic registers.
+    type Registers: Registers<P, B>;
+
+    /// Creates the atomic registers holding the values of the provided registers.
+    fn from_registers(registers: &Self::Registers) -> Self;
+
+    /// Returns a copy of the current values of the registers.
+    ///
+    /// # Implementative details
+    /// Each word is loaded atomically, but the words are loaded one after the other: when other
+    /// threads are updating the registers while the snapshot is taken, the snapshot may include
+    /// only part of their updates. Once the updating threads are joined, the snapshot includes
+    /// all of them.
+    fn snapshot(&self) -> Self::Registers;
+
+    /// Updates the register at the given index with the given value,
+    /// if the value is greater than the current value in the register.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the register to be updated.
+    /// * `value` - The value to be set in the register.
+    ///
+    /// # Returns
+    /// The previous value of the register, and the larger of the two values.
+    fn set_greater(&self, index: P::NumberOfRegisters, value: u8) -> (u8, u8);
+
+    /// Returns the value of the register at the given index.
+    fn get_register(&self, index: P::NumberOfRegisters) -> u8;
+
+    /// Clears the registers to zero.
+    fn clear_registers(&self);
+}
+
+#[derive(Debug)]
+/// Register implementation for the atomic array registers.
+pub struct AtomicArray<const N: usize, V> {
+    /// The atomic words storing the registers.
+    words: [AtomicU64; N],
+    /// Phantom data to keep track of the variable word type.
+    _phantom: PhantomData<V>,
+}
+
+impl<const N: usize, V> Default for AtomicArray<N, V> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            words: core::array::from_fn(|_| AtomicU64::new(0)),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P: Precision, B: Bits + VariableWord<Word = u8>, const N: usize> AtomicRegisters<P, B>
+    for AtomicArray<N, B>
+where
+    Array<N, false, B>: Registers<P, B>,
+{
+    type Registers = Array<N, false, B>;
+
+    #[inline]
+    fn from_registers(registers: &Self::Registers) -> Self {
+        let words = registers.words();
+        Self {
+            words: core::array::from_fn(|i| AtomicU64::new(words[i])),
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn snapshot(&self) -> Self::Registers {
+        let mut registers = Self::Registers::default();
+        for (word, atomic_word) in registers.words_mut().iter_mut().zip(self.words.iter()) {
+            *word = atomic_word.load(Ordering::Relaxed);
+        }
+        registers
+    }
+
+    #[inline]
+    fn set_greater(&self, index: P::NumberOfRegisters, value: u8) -> (u8, u8) {
+        let (word_index, offset) = split_not_packed_index::<B>(index.to_usize());
+
+        // The registers only ever increase and the maximum is commutative, so the final
+        // value of each register does not depend on the order of the updates: the relaxed
+        // ordering is sufficient, and the synchronization with the readers is left to
+        // whatever mechanism is used to wait for the updating threads, e.g. joining them.
+        let previous_word = self.words[word_index]
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |word| {
+                if extract_value_from_word::<B>(word, offset) >= value {
+                    return None;
+                }
+                let mut word = word;
+                insert_value_into_word::<B>(&mut word, offset, u64::from(value));
+                Some(word)
+            })
+            .unwrap_or_else(|word| word);
+
+        let previous = extract_value_from_word::<B>(previous_word, offset);
+        (previous, previous.max(value))
+    }
+
+    #[inline]
+    fn get_register(&self, index: P::NumberOfRegisters) -> u8 {
+        let (word_index, offset) = split_not_packed_index::<B>(index.to_usize());
+        extract_value_from_word::<B>(self.words[word_index].load(Ordering::Relaxed), offset)
+    }
+
+    #[inline]
+    fn clear_registers(&self) {
+        for word in &self.words {
+            word.store(0, Ordering::Relaxed);
+        }
+    }
+}
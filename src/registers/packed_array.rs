@@ -9,6 +9,8 @@
 //! it will also make it slower, as we need to perform more operations to extract the registers from the
 //! packed array, expecially in the case of bridge registers, i.e. registers that span two words.
 
+#[cfg(target_has_atomic = "64")]
+use super::atomic_array::{AtomicArray, AtomicRegisters};
 use super::{
     Bits, Bits1, Bits2, Bits3, Bits4, Bits5, Bits6, Bits7, Bits8, FloatOps, Matrix, Precision,
     Registers, Zero,
@@ -73,7 +75,7 @@ use crate::prelude::Precision9;
 /// whether the value extracted from the word is a valid value for the register type.
 /// This is okay because we apply a mask to the value, and it is not possible for the
 /// value we cast to be greater than the mask.
-fn extract_value_from_word<V: VariableWord>(word: u64, offset: u8) -> V::Word {
+pub(super) fn extract_value_from_word<V: VariableWord>(word: u64, offset: u8) -> V::Word {
     debug_assert!(
         offset + V::NUMBER_OF_BITS <= 64,
         "The offset ({offset} + {}) should be less than or equal to 64",
@@ -89,7 +91,7 @@ fn extract_value_from_word<V: VariableWord>(word: u64, offset: u8) -> V::Word {
 /// * `word` - The word in which the value is to be inserted.
 /// * `offset` - The offset (from the right) at which the value is to be inserted.
 /// * `value` - The value to be inserted.
-fn insert_value_into_word<V: VariableWord>(word: &mut u64, offset: u8, value: u64) {
+pub(super) fn insert_value_into_word<V: VariableWord>(word: &mut u64, offset: u8, value: u64) {
     debug_assert!(
         offset + V::NUMBER_OF_BITS <= 64,
         "The offset ({offset} + {}) should be less than or equal to 64",
@@ -623,12 +625,57 @@ mod test_clear_array {
     }
 }
 
+#[cfg(test)]
+mod test_apply_array {
+    use super::*;
+    use crate::prelude::*;
+    use hyperloglog_derive::test_array;
+
+    #[test_array]
+    /// Test that applying a function visits every value of the array in order, including
+    /// the values ending exactly at the end of a word, which are not bridge values.
+    fn test_apply_array<const M: usize, const N: usize, const PACKED: bool, V: VariableWord>(
+        reference: [V::Word; M],
+    ) {
+        let mut array = Array::<N, PACKED, V>::default();
+
+        // We populate the array with the values from the reference.
+        for (i, value) in reference.iter().enumerate() {
+            array.set(i, *value);
+        }
+
+        // We replace each value with the value at the mirrored position.
+        let mut i = 0;
+        array.apply(
+            |value| {
+                assert_eq!(
+                    value, reference[i],
+                    "The value at position ({i}) should be visited in order."
+                );
+                i += 1;
+                reference[M - i]
+            },
+            M as u64,
+        );
+
+        assert_eq!(i, M, "All of the values should be visited.");
+        for (i, value) in reference.iter().rev().enumerate() {
+            assert_eq!(
+                array.get(i),
+                *value,
+                "The value at position ({i}) should be the mirrored one."
+            );
+        }
+    }
+}
+
 impl<const N: usize, const PACKED: bool, V: VariableWord> Array<N, PACKED, V> {
     #[inline]
     /// Returns whether a given offset is a bridge offset.
     const fn is_bridge_offset(offset: u8) -> bool {
         PACKED
             && (V::NUMBER_OF_BITS_U64 * V::NUMBER_OF_ENTRIES_U64 < 64)
+            && offset < 64
             && (offset + V::NUMBER_OF_BITS > 64)
     }
 
@@ -805,6 +852,72 @@ impl<const N: usize, const PACKED: bool, V: VariableWord> Array<N, PACKED, V> {
     }
 }
 
+impl<const N: usize, const PACKED: bool, V: VariableWord<Word = u8>> Array<N, PACKED, V> {
+    #[inline]
+    /// Returns the harmonic sum and the number of zeros of the maximum of the first `len` values
+    /// of the two arrays.
+    ///
+    /// # Implementative details
+    /// When the `swar` feature is enabled and the array is not packed, the maximum and the number
+    /// of zeros are computed one word at a time, otherwise the values are extracted one at a time.
+    /// In both cases, the harmonic sum is accumulated one value at a time.
+    fn harmonic_sum_and_zeros<C: PositiveInteger>(&self, other: &Self, len: usize) -> (f64, C) {
+        #[cfg(feature = "swar")]
+        if !PACKED {
+            let (harmonic_sum, zeros) =
+                super::swar::harmonic_sum_and_zeros::<V>(&self.words, &other.words, len);
+            return (
+                harmonic_sum,
+                C::try_from_u64(zeros)
+                    .expect("The number of zeros fits in the number of registers"),
+            );
+        }
+
+        let mut harmonic_sum = f64::ZERO;
+        let mut zeros = C::ZERO;
+
+        for [left, right] in self.iter_values_zipped(other, len) {
+            let max_register = core::cmp::max(left, right);
+            harmonic_sum += f64::integer_exp2_minus(max_register);
+            zeros += C::from(max_register.is_zero());
+        }
+
+        (harmonic_sum, zeros)
+    }
+
+    #[inline]
+    /// Sets each of the first `len` values to the maximum between itself and the value in `other`,
+    /// calling `on_increase` with the previous and the new value of each increased value.
+    ///
+    /// # Implementative details
+    /// When the `swar` feature is enabled and the array is not packed, the values are processed
+    /// one word at a time, otherwise they are extracted one at a time.
+    fn set_greater_values<F>(&mut self, other: &Self, len: usize, mut on_increase: F)
+    where
+        F: FnMut(u8, u8),
+    {
+        #[cfg(feature = "swar")]
+        if !PACKED {
+            super::swar::set_greater::<V, F>(&mut self.words, &other.words, len, on_increase);
+            return;
+        }
+
+        let mut other_values = other.iter_values(len);
+        self.apply(
+            |value| {
+                let other_value = other_values.next().unwrap();
+                if other_value > value {
+                    on_increase(value, other_value);
+                    other_value
+                } else {
+                    value
+                }
+            },
+            len as u64,
+        );
+    }
+}
+
 impl<const N: usize, const PACKED: bool, V: VariableWord> Default for Array<N, PACKED, V> {
     #[inline]
     fn default() -> Self {
@@ -850,6 +963,10 @@ pub trait ArrayRegister<B: Bits>: Precision {
     #[cfg(not(feature = "std"))]
     /// The type of the packed array register.
     type Packed: Registers<Self, B>;
+
+    #[cfg(target_has_atomic = "64")]
+    /// The type of the atomic array register, sharing the layout of the array register.
+    type Atomic: AtomicRegisters<Self, B, Registers = <Self as ArrayRegister<B>>::Array>;
 }
 
 /// Trait marker to associate a precision to all possible packed array registers.
@@ -918,7 +1035,7 @@ const fn split_packed_index<V: VariableWord>(index: usize) -> (usize, u8) {
 /// # Safety
 /// This method employs unsafe code to convert a usize to a u8, as it guarantees
 /// that the value is less than 256.
-const fn split_not_packed_index<V: VariableWord>(index: usize) -> (usize, u8) {
+pub(super) const fn split_not_packed_index<V: VariableWord>(index: usize) -> (usize, u8) {
     let word_index: usize = index / V::NUMBER_OF_ENTRIES_USIZE;
     let relative_register_offset: u8 =
         V::NUMBER_OF_BITS * unsafe { usize_to_u8(index - word_index * V::NUMBER_OF_ENTRIES_USIZE) };
@@ -1005,10 +1122,14 @@ macro_rules! impl_packed_array_register_for_precision_and_bits {
                 impl ArrayRegister<[<Bits $bits>]> for [<Precision $exponent>] {
                     type Array = Array<{crate::utils::ceil(usize::pow(2, $exponent), 64 / $bits)}, false, [<Bits $bits>]>;
                     type Packed = Array<{crate::utils::ceil(usize::pow(2, $exponent) * $bits, 64)}, true, [<Bits $bits>]>;
+                    #[cfg(target_has_atomic = "64")]
+                    type Atomic = AtomicArray<{crate::utils::ceil(usize::pow(2, $exponent), 64 / $bits)}, [<Bits $bits>]>;
                 }
 
                 #[cfg(feature = "precision_" $exponent)]
                 impl Registers<[<Precision $exponent>], [<Bits $bits>]> for Array<{crate::utils::ceil(usize::pow(2, $exponent) * $bits, 64)}, true, [<Bits $bits>]> {
+                    const PACKED: bool = true;
+
                     type Iter<'words> = ArrayIter<&'words Self, 1> where Self: 'words;
                     type IterZipped<'words> = ArrayIter<&'words Self, 2>
                         where
@@ -1030,16 +1151,15 @@ macro_rules! impl_packed_array_register_for_precision_and_bits {
                         other: &Self,
                     ) -> (f64, <[<Precision $exponent>] as Precision>::NumberOfRegisters)
                     {
-                        let mut harmonic_sum = f64::ZERO;
-                        let mut union_zeros = <[<Precision $exponent>] as Precision>::NumberOfRegisters::ZERO;
-
-                        for [left, right] in <Self as Registers<[<Precision $exponent>], [<Bits $bits>]>>::iter_registers_zipped(self, other) {
-                            let max_register = core::cmp::max(left, right);
-                            harmonic_sum += f64::integer_exp2_minus(max_register);
-                            union_zeros += <[<Precision $exponent>] as Precision>::NumberOfRegisters::from(max_register.is_zero());
-                        }
+                        self.harmonic_sum_and_zeros(other, 1 << [<Precision $exponent>]::EXPONENT)
+                    }
 
-                        (harmonic_sum, union_zeros)
+                    #[inline]
+                    fn set_greater_registers<F>(&mut self, other: &Self, on_increase: F)
+                    where
+                        F: FnMut(u8, u8),
+                    {
+                        self.set_greater_values(other, 1 << [<Precision $exponent>]::EXPONENT, on_increase);
                     }
 
                     #[inline]
@@ -1065,10 +1185,22 @@ macro_rules! impl_packed_array_register_for_precision_and_bits {
                     fn clear_registers(&mut self) {
                         self.clear();
                     }
+
+                    #[inline]
+                    fn words(&self) -> &[u64] {
+                        &self.words
+                    }
+
+                    #[inline]
+                    fn words_mut(&mut self) -> &mut [u64] {
+                        &mut self.words
+                    }
                 }
 
                 #[cfg(feature = "precision_" $exponent)]
                 impl Registers<[<Precision $exponent>], [<Bits $bits>]> for Array<{crate::utils::ceil(usize::pow(2, $exponent), 64 / $bits)}, false, [<Bits $bits>]> {
+                    const PACKED: bool = false;
+
                     type Iter<'words> = ArrayIter<&'words Self, 1> where Self: 'words;
                     type IterZipped<'words> = ArrayIter<&'words Self, 2>
                         where
@@ -1090,16 +1222,15 @@ macro_rules! impl_packed_array_register_for_precision_and_bits {
                         other: &Self,
                     ) -> (f64, <[<Precision $exponent>] as Precision>::NumberOfRegisters)
                     {
-                        let mut harmonic_sum = f64::ZERO;
-                        let mut union_zeros = <[<Precision $exponent>] as Precision>::NumberOfRegisters::ZERO;
-
-                        for [left, right] in <Self as Registers<[<Precision $exponent>], [<Bits $bits>]>>::iter_registers_zipped(self, other) {
-                            let max_register = core::cmp::max(left, right);
-                            harmonic_sum += f64::integer_exp2_minus(max_register);
-                            union_zeros += <[<Precision $exponent>] as Precision>::NumberOfRegisters::from(max_register.is_zero());
-                        }
+                        self.harmonic_sum_and_zeros(other, 1 << [<Precision $exponent>]::EXPONENT)
+                    }
 
-                        (harmonic_sum, union_zeros)
+                    #[inline]
+                    fn set_greater_registers<F>(&mut self, other: &Self, on_increase: F)
+                    where
+                        F: FnMut(u8, u8),
+                    {
+                        self.set_greater_values(other, 1 << [<Precision $exponent>]::EXPONENT, on_increase);
                     }
 
                     #[inline]
@@ -1125,6 +1256,16 @@ macro_rules! impl_packed_array_register_for_precision_and_bits {
                     fn clear_registers(&mut self) {
                         self.clear();
                     }
+
+                    #[inline]
+                    fn words(&self) -> &[u64] {
+                        &self.words
+                    }
+
+                    #[inline]
+                    fn words_mut(&mut self) -> &mut [u64] {
+                        &mut self.words
+                    }
                 }
             }
         )*
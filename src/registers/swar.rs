@@ -0,0 +1,278 @@
+//! Word-level operations for the unpacked array registers.
+//!
+//! When the registers are not packed, each word stores `V::NUMBER_OF_ENTRIES` registers starting
+//! from its most significant bits, and no register spans two words. This allows us to process all
+//! the registers of a word at once with a handful of integer operations, i.e. SIMD within a
+//! register (SWAR), instead of extracting and inserting the registers one at a time. Since the
+//! approach only employs integer operations on `u64` words, it is portable and does not require
+//! any target-specific instruction.
+//!
+//! The harmonic sum is the exception: its terms are still added one register at a time, as
+//! summing the registers of each word first would change the rounding of the floating-point
+//! result, which must not depend on whether the `swar` feature is enabled.
+
+use crate::utils::{FloatOps, VariableWord, Zero};
+
+/// Trait providing the masks employed in the word-level operations.
+trait SwarWord: VariableWord {
+    /// The mask with the most significant bit of each register of a word.
+    const HIGH: u64 = high_bits::<Self>(Self::NUMBER_OF_ENTRIES_USIZE);
+    /// The mask with all the bits of the registers of a word, excluding the padding.
+    const REGISTERS: u64 = (Self::HIGH >> (Self::NUMBER_OF_BITS - 1)) * Self::MASK;
+    /// The mask with all the bits of the registers of a word except the most significant ones.
+    const LOW: u64 = Self::REGISTERS & !Self::HIGH;
+}
+
+impl<V: VariableWord> SwarWord for V {}
+
+/// Returns the mask with the most significant bit of each of the first `entries` registers of a word.
+const fn high_bits<V: VariableWord>(entries: usize) -> u64 {
+    let mut mask = 0_u64;
+    let mut entry = 0;
+    while entry < entries {
+        mask |= 1 << (63 - entry * V::NUMBER_OF_BITS_USIZE);
+        entry += 1;
+    }
+    mask
+}
+
+#[inline]
+/// Returns the mask with all the bits of the registers whose most significant bit is set in `high`.
+const fn spread<V: VariableWord>(high: u64) -> u64 {
+    (high >> (V::NUMBER_OF_BITS - 1)) * V::MASK
+}
+
+#[inline]
+/// Returns the most significant bit of each register of `left` which is strictly smaller than the
+/// corresponding register of `right`, among the registers selected by `high`.
+const fn smaller<V: VariableWord>(left: u64, right: u64, high: u64) -> u64 {
+    // Since the most significant bit of each register of the minuend is set, and the one of the
+    // subtrahend is cleared, the subtraction does not borrow across registers, and the most
+    // significant bit of each register of the difference tells whether the lower bits of the
+    // left register are greater or equal than the lower bits of the right register.
+    let lower_greater_or_equal = (left | V::HIGH) - (right & V::LOW);
+    let greater_or_equal = (left & !right) | (!(left ^ right) & lower_greater_or_equal);
+    !greater_or_equal & high
+}
+
+#[inline]
+/// Returns the word with the maximum between each register of the two words.
+const fn maximum<V: VariableWord>(left: u64, right: u64) -> u64 {
+    let mask = spread::<V>(smaller::<V>(left, right, V::HIGH));
+    (left & !mask) | (right & mask)
+}
+
+#[inline]
+/// Returns the number of zero registers in the word, among the registers selected by `high`.
+const fn number_of_zeros<V: VariableWord>(word: u64, high: u64) -> u32 {
+    // Adding the lower mask to the lower bits of a register sets its most significant bit
+    // if and only if any of its lower bits is set, without overflowing in the next register.
+    let non_zeros = ((word & V::LOW) + V::LOW) | word;
+    (!non_zeros & high).count_ones()
+}
+
+#[inline]
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "The register is masked to at most eight bits"
+)]
+/// Returns the register of the word whose least significant bit is at the given shift.
+const fn register_at<V: VariableWord>(word: u64, shift: u32) -> u8 {
+    ((word >> shift) & V::MASK) as u8
+}
+
+#[inline]
+/// Returns the mask of the most significant bits of the registers stored in a word.
+///
+/// # Arguments
+/// * `remaining` - The number of registers still to be processed, including the ones in this word.
+const fn word_high_bits<V: VariableWord>(remaining: usize) -> u64 {
+    if remaining >= V::NUMBER_OF_ENTRIES_USIZE {
+        V::HIGH
+    } else {
+        high_bits::<V>(remaining)
+    }
+}
+
+/// Returns the harmonic sum and the number of zeros of the maximum of the registers.
+///
+/// # Arguments
+/// * `left` - The words of the first array of registers.
+/// * `right` - The words of the second array of registers.
+/// * `number_of_registers` - The number of registers stored in the words.
+///
+/// # Implementative details
+/// The harmonic sum is computed following the order of the registers, so that the result is
+/// exactly the same as the one obtained by iterating over the registers one at a time.
+pub(super) fn harmonic_sum_and_zeros<V: VariableWord>(
+    left: &[u64],
+    right: &[u64],
+    number_of_registers: usize,
+) -> (f64, u64) {
+    debug_assert!(V::NUMBER_OF_BITS <= 8, "The registers must fit in a byte.");
+    let mut harmonic_sum = f64::ZERO;
+    let mut zeros = 0_u64;
+    let mut remaining = number_of_registers;
+
+    for (left, right) in left.iter().zip(right.iter()) {
+        if remaining == 0 {
+            break;
+        }
+        let high = word_high_bits::<V>(remaining);
+        let maximum = maximum::<V>(*left, *right);
+        zeros += u64::from(number_of_zeros::<V>(maximum, high));
+
+        let entries = remaining.min(V::NUMBER_OF_ENTRIES_USIZE);
+        let mut shift = 64;
+        for _ in 0..entries {
+            shift -= u32::from(V::NUMBER_OF_BITS);
+            harmonic_sum += f64::integer_exp2_minus(register_at::<V>(maximum, shift));
+        }
+        remaining -= entries;
+    }
+
+    (harmonic_sum, zeros)
+}
+
+/// Sets each register of `left` to the maximum between itself and the register of `right`.
+///
+/// # Arguments
+/// * `left` - The words of the registers to be updated.
+/// * `right` - The words of the other registers.
+/// * `number_of_registers` - The number of registers stored in the words.
+/// * `on_increase` - Function called with the previous and the new value of each increased
+///   register, following the order of the registers.
+pub(super) fn set_greater<V: VariableWord, F: FnMut(u8, u8)>(
+    left: &mut [u64],
+    right: &[u64],
+    number_of_registers: usize,
+    mut on_increase: F,
+) {
+    debug_assert!(V::NUMBER_OF_BITS <= 8, "The registers must fit in a byte.");
+    let mut remaining = number_of_registers;
+
+    for (left, right) in left.iter_mut().zip(right.iter()) {
+        if remaining == 0 {
+            break;
+        }
+        let mut increased = smaller::<V>(*left, *right, word_high_bits::<V>(remaining));
+        remaining -= remaining.min(V::NUMBER_OF_ENTRIES_USIZE);
+
+        // In dense counters, most words are left unchanged by a merge.
+        if increased == 0 {
+            continue;
+        }
+
+        let previous = *left;
+        let mask = spread::<V>(increased);
+        *left = (previous & !mask) | (right & mask);
+
+        // We visit the increased registers starting from the most significant bits,
+        // which is the order of the registers.
+        while increased != 0 {
+            let leading_zeros = increased.leading_zeros();
+            let shift = 64 - u32::from(V::NUMBER_OF_BITS) - leading_zeros;
+            on_increase(
+                register_at::<V>(previous, shift),
+                register_at::<V>(*left, shift),
+            );
+            increased ^= 1 << (63 - leading_zeros);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    /// The number of words employed in the tests.
+    const NUMBER_OF_WORDS: usize = 16;
+
+    /// Returns the register at the given index of the provided words.
+    fn register<V: VariableWord>(words: &[u64; NUMBER_OF_WORDS], index: usize) -> u8 {
+        let word = words[index / V::NUMBER_OF_ENTRIES_USIZE];
+        let entry = u32::try_from(index % V::NUMBER_OF_ENTRIES_USIZE).unwrap();
+        register_at::<V>(word, 64 - u32::from(V::NUMBER_OF_BITS) * (entry + 1))
+    }
+
+    /// Checks the word-level operations against the register-by-register ones.
+    fn check_swar<V: VariableWord>() {
+        // We leave some unused registers in the last word, which are filled with
+        // random bits and must be ignored.
+        let number_of_registers = NUMBER_OF_WORDS * V::NUMBER_OF_ENTRIES_USIZE - 3;
+
+        for seed in 0..100_u64 {
+            let mut left = [0_u64; NUMBER_OF_WORDS];
+            let mut right = [0_u64; NUMBER_OF_WORDS];
+            for (i, (word, other)) in
+                iter_random_values::<u64>(NUMBER_OF_WORDS as u64, None, Some(seed))
+                    .zip(iter_random_values::<u64>(
+                        NUMBER_OF_WORDS as u64,
+                        None,
+                        Some(seed + 100),
+                    ))
+                    .enumerate()
+            {
+                // Zero and equal registers are frequent in practice, so we mask some words.
+                left[i] = if i % 3 == 0 {
+                    word & 0xF0F0_0000_FFFF_0000
+                } else {
+                    word
+                };
+                right[i] = if i % 4 == 0 {
+                    other & 0xFF00_FF00_0000_FFFF
+                } else {
+                    other
+                };
+            }
+
+            let mut expected_harmonic_sum = f64::ZERO;
+            let mut expected_zeros = 0_u64;
+            for index in 0..number_of_registers {
+                let maximum = register::<V>(&left, index).max(register::<V>(&right, index));
+                expected_harmonic_sum += f64::integer_exp2_minus(maximum);
+                expected_zeros += u64::from(maximum == 0);
+            }
+
+            let (harmonic_sum, zeros) =
+                harmonic_sum_and_zeros::<V>(&left, &right, number_of_registers);
+            assert_eq!(harmonic_sum.to_bits(), expected_harmonic_sum.to_bits());
+            assert_eq!(zeros, expected_zeros);
+
+            let mut expected_increases = (0..number_of_registers)
+                .map(|index| (register::<V>(&left, index), register::<V>(&right, index)))
+                .filter(|(old, new)| new > old);
+            let mut merged = left;
+            set_greater::<V, _>(&mut merged, &right, number_of_registers, |old, new| {
+                assert_eq!(expected_increases.next(), Some((old, new)));
+            });
+            assert_eq!(expected_increases.next(), None);
+
+            for index in 0..number_of_registers {
+                assert_eq!(
+                    register::<V>(&merged, index),
+                    register::<V>(&left, index).max(register::<V>(&right, index))
+                );
+            }
+            // The unused registers of the last word are left untouched.
+            let unused = !high_bits::<V>(V::NUMBER_OF_ENTRIES_USIZE - 3);
+            assert_eq!(
+                merged[NUMBER_OF_WORDS - 1] & spread::<V>(unused & V::HIGH),
+                left[NUMBER_OF_WORDS - 1] & spread::<V>(unused & V::HIGH)
+            );
+        }
+    }
+
+    #[test]
+    fn test_swar() {
+        check_swar::<Bits1>();
+        check_swar::<Bits2>();
+        check_swar::<Bits3>();
+        check_swar::<Bits4>();
+        check_swar::<Bits5>();
+        check_swar::<Bits6>();
+        check_swar::<Bits7>();
+        check_swar::<Bits8>();
+    }
+}
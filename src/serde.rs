@@ -1,9 +1,31 @@
 //! Module to handle serialization and deserialization of the registers
+use crate::prelude::*;
 use core::any::type_name;
 use core::fmt::Formatter;
 use core::marker::PhantomData;
 use serde::de::SeqAccess;
 use serde::de::Visitor;
+use serde::ser::SerializeSeq;
+
+/// Wrapper to serialize the values of the registers as a sequence of bytes.
+pub(crate) struct RegisterValues<'registers, P, B, R>(&'registers R, PhantomData<(P, B)>);
+
+impl<'registers, P, B, R> RegisterValues<'registers, P, B, R> {
+    /// Creates a new [`RegisterValues`] wrapping the provided registers.
+    pub(crate) fn new(registers: &'registers R) -> Self {
+        Self(registers, PhantomData)
+    }
+}
+
+impl<P: Precision, B: Bits, R: Registers<P, B>> serde::Serialize for RegisterValues<'_, P, B, R> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(1 << P::EXPONENT))?;
+        for register in self.0.iter_registers() {
+            seq.serialize_element(&register)?;
+        }
+        seq.end()
+    }
+}
 
 /// Struct to deserialize a vector of T
 pub(crate) struct RegisterVisitor<T> {
@@ -35,17 +57,34 @@ impl<'de, T: Default + Copy + serde::Deserialize<'de>> Visitor<'de> for Register
     }
 
     fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
-        let mut array = vec![T::default(); self.expected_length];
-        {
-            let mut array_iter = array.iter_mut();
-            while let Some(value) = seq.next_element()? {
-                if let Some(target) = array_iter.next() {
-                    *target = value;
-                } else {
-                    return Err(serde::de::Error::invalid_length(array.len(), &self));
-                }
+        let mut array = Vec::with_capacity(self.expected_length);
+        while let Some(value) = seq.next_element()? {
+            if array.len() == self.expected_length {
+                return Err(serde::de::Error::invalid_length(array.len() + 1, &self));
             }
+            array.push(value);
         }
         Ok(array)
     }
 }
+
+/// Struct to deserialize the seed of the hashers followed by the values of the registers.
+pub(crate) struct SeededRegisterVisitor;
+
+impl<'de> Visitor<'de> for SeededRegisterVisitor {
+    type Value = (u64, Vec<u8>);
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("a seed followed by the values of the registers")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let seed = seq
+            .next_element()?
+            .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+        let values = seq
+            .next_element()?
+            .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+        Ok((seed, values))
+    }
+}
@@ -0,0 +1,293 @@
+//! Submodule providing a sliding-window [`HyperLogLog`] counter, following the approach of
+//! Chabchoub and Hébrail.
+//!
+//! Instead of a single value, each register stores the list of its future possible maxima:
+//! the pairs of timestamp and register value that may be the maximum of the register in a
+//! window starting at some timestamp. A pair is dropped from the list as soon as a pair with a
+//! greater or equal value and a later or equal timestamp is inserted, as the latter is in every
+//! window that includes the former. The lists are therefore sorted by increasing timestamps and
+//! strictly decreasing values, and the value of a register in the window starting at a given
+//! timestamp is the value of the first pair of its list with a later or equal timestamp.
+//!
+//! The registers of the window are then loaded into the counter `H`, so that the estimates employ
+//! the same corrections of the counter: the estimate of the window starting at a timestamp is
+//! identical to the one of a counter where only the elements inserted at later or equal
+//! timestamps were inserted.
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+
+use crate::prelude::*;
+use crate::utils::PositiveInteger;
+
+/// Inserts the pair of timestamp and value in the list of future possible maxima of a register,
+/// returning whether the list was modified.
+fn insert_possible_maximum(maxima: &mut Vec<(u64, u8)>, timestamp: u64, value: u8) -> bool {
+    // The first pair with a later or equal timestamp has the largest value among them.
+    let position = maxima.partition_point(|&(other_timestamp, _)| other_timestamp < timestamp);
+    let next = maxima.get(position).copied();
+    if next.is_some_and(|(_, next_value)| next_value >= value) {
+        return false;
+    }
+
+    // The pairs with an earlier timestamp and a smaller or equal value are dominated by
+    // the new pair, and since the values are decreasing they are the last ones before it.
+    let start = maxima[..position].partition_point(|&(_, other_value)| other_value > value);
+    let end = position + usize::from(next.is_some_and(|(other, _)| other == timestamp));
+    maxima.splice(start..end, [(timestamp, value)]);
+    true
+}
+
+/// Returns the value of a register in the window starting at the given timestamp.
+fn maximum_since(maxima: &[(u64, u8)], timestamp: u64) -> u8 {
+    let position = maxima.partition_point(|&(other_timestamp, _)| other_timestamp < timestamp);
+    maxima.get(position).map_or(0, |&(_, value)| value)
+}
+
+/// A sliding-window counter, estimating the cardinality of the elements inserted since a timestamp.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+///
+/// let mut window = SlidingWindow::<Counter>::default();
+/// let mut last_hour = Counter::default();
+///
+/// for minute in 0..120_u64 {
+///     for user in 0..10_u64 {
+///         let user = minute * 10 + user;
+///         window.insert_at(&user, minute);
+///         if minute >= 60 {
+///             last_hour.insert(&user);
+///         }
+///     }
+/// }
+///
+/// assert_eq!(window.counter_since(60), last_hour);
+/// assert_eq!(
+///     window.estimate_cardinality_since(60),
+///     last_hour.estimate_cardinality()
+/// );
+///
+/// // The pairs that cannot be in the windows of interest anymore may be dropped.
+/// window.expire_before(60);
+/// assert_eq!(window.counter_since(0), last_hour);
+/// ```
+pub struct SlidingWindow<H: HyperLogLog> {
+    /// The lists of future possible maxima of the registers.
+    maxima: Vec<Vec<(u64, u8)>>,
+    /// The builder of the hashers of the counter, which is handed to the counters of the windows.
+    hasher_builder: H::HasherBuilder,
+    /// The type of the counter employed for the estimates.
+    counter: PhantomData<H>,
+}
+
+impl<H: HyperLogLog> Debug for SlidingWindow<H> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        formatter
+            .debug_struct("SlidingWindow")
+            .field("maxima", &self.maxima)
+            .field("hasher_builder", &self.hasher_builder)
+            .finish()
+    }
+}
+
+impl<H: HyperLogLog> Clone for SlidingWindow<H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            maxima: self.maxima.clone(),
+            hasher_builder: self.hasher_builder.clone(),
+            counter: PhantomData,
+        }
+    }
+}
+
+impl<H: HyperLogLog> PartialEq for SlidingWindow<H> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.maxima == other.maxima && self.hasher_builder == other.hasher_builder
+    }
+}
+
+impl<H: HyperLogLog> Eq for SlidingWindow<H> {}
+
+impl<H: HyperLogLog> Default for SlidingWindow<H> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher_builder(H::HasherBuilder::default())
+    }
+}
+
+impl<H: HyperLogLog> SlidingWindow<H> {
+    #[inline]
+    #[must_use]
+    /// Returns a new empty counter hashing its elements with the hashers created by the
+    /// provided builder.
+    ///
+    /// # Arguments
+    /// * `hasher_builder` - The builder of the hashers of the counter.
+    pub fn with_hasher_builder(hasher_builder: H::HasherBuilder) -> Self {
+        Self {
+            maxima: vec![Vec::new(); H::Precision::NUMBER_OF_REGISTERS.to_usize()],
+            hasher_builder,
+            counter: PhantomData,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the builder of the hashers of the counter.
+    pub fn hasher_builder(&self) -> &H::HasherBuilder {
+        &self.hasher_builder
+    }
+
+    #[inline]
+    /// Inserts the element at the given timestamp, returning whether the counter was modified.
+    ///
+    /// # Implementative details
+    /// The element is hashed with the hashers of the counter, which are also the ones of the
+    /// counters returned by [`SlidingWindow::counter_since`].
+    pub fn insert_at<T: Hash>(&mut self, element: &T, timestamp: u64) -> bool {
+        self.insert_hash_at(self.hasher_builder.hash_one(element), timestamp)
+    }
+
+    #[inline]
+    /// Inserts a pre-computed hash at the given timestamp, returning whether the counter was
+    /// modified.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the element, computed with the hashers of the counter.
+    /// * `timestamp` - The timestamp of the insertion.
+    pub fn insert_hash_at(&mut self, hash: u64, timestamp: u64) -> bool {
+        let (value, index) = H::split_hash(hash);
+        insert_possible_maximum(&mut self.maxima[index.to_usize()], timestamp, value)
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the counter of the elements inserted at timestamps later or equal to the given one.
+    pub fn counter_since(&self, timestamp: u64) -> H {
+        let mut maxima = self.maxima.iter();
+        let mut registers = H::Registers::default();
+        registers.apply_to_registers(|_| {
+            maxima
+                .next()
+                .map_or(0, |maxima| maximum_since(maxima, timestamp))
+        });
+        H::from_registers_with_hasher_builder(registers, self.hasher_builder.clone())
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the estimated cardinality of the elements inserted at timestamps later or equal
+    /// to the given one.
+    pub fn estimate_cardinality_since(&self, timestamp: u64) -> f64
+    where
+        H: Estimator<f64>,
+    {
+        self.counter_since(timestamp).estimate_cardinality()
+    }
+
+    #[inline]
+    /// Drops the pairs inserted at timestamps earlier than the given one, which only
+    /// affect the windows starting before it.
+    pub fn expire_before(&mut self, timestamp: u64) {
+        for maxima in &mut self.maxima {
+            let expired =
+                maxima.partition_point(|&(other_timestamp, _)| other_timestamp < timestamp);
+            maxima.drain(..expired);
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the total number of pairs stored in the lists of future possible maxima.
+    ///
+    /// # Implementative details
+    /// When the elements are inserted with non-decreasing timestamps, the list of a register
+    /// holds on average a logarithmic number of pairs in the number of elements hashed to the
+    /// register since the oldest timestamp that was not expired.
+    pub fn number_of_possible_maxima(&self) -> usize {
+        self.maxima.iter().map(Vec::len).sum()
+    }
+}
+
+impl<H: HyperLogLog> MutableSet for SlidingWindow<H> {
+    #[inline]
+    fn clear(&mut self) {
+        for maxima in &mut self.maxima {
+            maxima.clear();
+        }
+    }
+}
+
+impl<H: HyperLogLog> BitOrAssign<&Self> for SlidingWindow<H> {
+    #[inline]
+    /// Merges the pairs of the provided counter, so that the windows of the resulting counter
+    /// are the union of the corresponding windows of the two counters.
+    fn bitor_assign(&mut self, rhs: &Self) {
+        debug_assert!(
+            self.hasher_builder == rhs.hasher_builder,
+            "The counters must hash their elements with the same hashers."
+        );
+        for (maxima, rhs_maxima) in self.maxima.iter_mut().zip(rhs.maxima.iter()) {
+            for &(timestamp, value) in rhs_maxima {
+                insert_possible_maximum(maxima, timestamp, value);
+            }
+        }
+    }
+}
+
+impl<H: HyperLogLog> BitOrAssign for SlidingWindow<H> {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self |= &rhs;
+    }
+}
+
+impl<H: HyperLogLog> BitOr for SlidingWindow<H> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= &rhs;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_possible_maximum() {
+        let mut maxima = Vec::new();
+        assert!(insert_possible_maximum(&mut maxima, 10, 3));
+        assert!(insert_possible_maximum(&mut maxima, 20, 1));
+        assert!(insert_possible_maximum(&mut maxima, 30, 2));
+        assert_eq!(maxima, [(10, 3), (30, 2)]);
+
+        // Pairs dominated by a later pair with a greater or equal value are ignored.
+        assert!(!insert_possible_maximum(&mut maxima, 5, 3));
+        assert!(!insert_possible_maximum(&mut maxima, 30, 2));
+        assert!(!insert_possible_maximum(&mut maxima, 25, 1));
+
+        // Out of order pairs drop the earlier pairs they dominate.
+        assert!(insert_possible_maximum(&mut maxima, 30, 4));
+        assert_eq!(maxima, [(30, 4)]);
+        assert!(insert_possible_maximum(&mut maxima, 20, 5));
+        assert!(insert_possible_maximum(&mut maxima, 40, 1));
+        assert_eq!(maxima, [(20, 5), (30, 4), (40, 1)]);
+
+        assert_eq!(maximum_since(&maxima, 0), 5);
+        assert_eq!(maximum_since(&maxima, 21), 4);
+        assert_eq!(maximum_since(&maxima, 40), 1);
+        assert_eq!(maximum_since(&maxima, 41), 0);
+    }
+}
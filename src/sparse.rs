@@ -0,0 +1,697 @@
+//! Submodule providing a sparse representation for [`HyperLogLog`] counters, in the spirit of
+//! the sparse mode of `HyperLogLog++`.
+//!
+//! While the number of distinct elements is small, the counter does not allocate the registers:
+//! it stores instead the sorted composite hashes of the inserted elements, encoded as
+//! variable-length deltas in a growable buffer. Newly inserted hashes are first collected in a
+//! small sorted insertion buffer, which is merged into the sorted list in batches. Only once the
+//! encoded list exceeds the footprint of the dense registers the counter switches to them.
+//!
+//! Differently from the [`Hybrid`] counter, whose capacity is bounded by the number of composite
+//! hashes that fit in the register array, the capacity of the sparse representation depends on
+//! how densely the hashes are distributed, and the counts remain exact far longer. As for the
+//! [`Hybrid`] counter, the number of distinct composite hashes is corrected for the collisions
+//! expected with the words of type `CH`.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::ops::{BitOr, BitOrAssign};
+
+#[cfg(feature = "serde")]
+use crate::hybrid::next_composite_hash;
+use crate::prelude::*;
+use crate::utils::PositiveInteger;
+
+/// The minimum number of hashes that the insertion buffer may hold before being merged.
+const MINIMUM_BUFFER_CAPACITY: usize = 4;
+/// The maximum number of hashes that the insertion buffer may hold before being merged.
+const MAXIMUM_BUFFER_CAPACITY: usize = 256;
+/// The number of hashes of the encoded list between two consecutive checkpoints.
+const CHECKPOINT_INTERVAL: usize = 32;
+
+/// Appends the provided value to the buffer using the LEB128 variable-length encoding.
+fn encode_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        // The cast is safe, as we are keeping only the lowest seven bits.
+        buffer.push(u8::try_from(value & 0x7F).unwrap() | 0x80);
+        value >>= 7;
+    }
+    buffer.push(u8::try_from(value).unwrap());
+}
+
+/// Returns the sorted values encoded as LEB128 deltas, with the checkpoints from which their
+/// decoding may be resumed.
+///
+/// # Arguments
+/// * `values` - The values, sorted in increasing order.
+/// * `capacity` - The expected number of bytes of the encoded values.
+///
+/// # Implementative details
+/// Every [`CHECKPOINT_INTERVAL`] values, we record the value with the offset of the byte that
+/// follows its encoding, so that a value can be searched by bisecting the checkpoints and then
+/// decoding at most [`CHECKPOINT_INTERVAL`] deltas.
+fn encode_sorted_hashes(values: &[u64], capacity: usize) -> (Vec<u8>, Vec<(u64, usize)>) {
+    let mut encoded = Vec::with_capacity(capacity);
+    let mut checkpoints = Vec::with_capacity(values.len() / CHECKPOINT_INTERVAL);
+    let mut previous = 0;
+    for (position, &value) in values.iter().enumerate() {
+        encode_varint(&mut encoded, value - previous);
+        previous = value;
+        if (position + 1) % CHECKPOINT_INTERVAL == 0 {
+            checkpoints.push((value, encoded.len()));
+        }
+    }
+    (encoded, checkpoints)
+}
+
+/// Iterator over the values encoded as LEB128 deltas.
+struct DeltaDecoder<'encoded> {
+    /// The encoded deltas.
+    encoded: &'encoded [u8],
+    /// The previously decoded value.
+    previous: u64,
+}
+
+impl<'encoded> DeltaDecoder<'encoded> {
+    /// Creates a new decoder over the provided encoded deltas.
+    fn new(encoded: &'encoded [u8]) -> Self {
+        Self::resume(encoded, 0)
+    }
+
+    /// Creates a new decoder over the provided encoded deltas, which follow the provided value.
+    fn resume(encoded: &'encoded [u8], previous: u64) -> Self {
+        Self { encoded, previous }
+    }
+}
+
+impl Iterator for DeltaDecoder<'_> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut delta = 0_u64;
+        let mut shift = 0;
+        loop {
+            let (&byte, rest) = self.encoded.split_first()?;
+            self.encoded = rest;
+            delta |= u64::from(byte & 0x7F) << shift;
+            if byte < 0x80 {
+                break;
+            }
+            shift += 7;
+        }
+        self.previous += delta;
+        Some(self.previous)
+    }
+}
+
+/// Returns the sorted and deduplicated values of the two sorted iterators.
+///
+/// # Arguments
+/// * `left` - The first sorted iterator.
+/// * `right` - The second sorted iterator.
+/// * `capacity` - The expected number of values, to allocate the merged values at once.
+fn merge_sorted<I: Iterator<Item = u64>, J: Iterator<Item = u64>>(
+    left: I,
+    right: J,
+    capacity: usize,
+) -> Vec<u64> {
+    let mut merged = Vec::with_capacity(capacity);
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+
+    while let (Some(&left_value), Some(&right_value)) = (left.peek(), right.peek()) {
+        if left_value <= right_value {
+            left.next();
+        }
+        if right_value <= left_value {
+            right.next();
+        }
+        merged.push(left_value.min(right_value));
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A counter that stores the hashes of the elements in a sparse, delta-encoded list up until
+/// the list exceeds the footprint of the registers of the dense counter `H`.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// let mut sparse: Sparse<
+///     PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array, twox_hash::XxHash64>,
+/// > = Sparse::default();
+///
+/// for value in 0..100_u64 {
+///     sparse.insert(&value);
+/// }
+///
+/// // The counter is still sparse, and the estimate is exact.
+/// assert!(sparse.is_sparse());
+/// assert_eq!(sparse.estimate_cardinality(), 100.0);
+///
+/// for value in 100..5_000_u64 {
+///     sparse.insert(&value);
+/// }
+///
+/// // The counter has switched to the registers.
+/// assert!(!sparse.is_sparse());
+/// ```
+pub struct Sparse<H: HyperLogLog, CH = u32> {
+    /// The sorted composite hashes, encoded as variable-length deltas.
+    encoded: Vec<u8>,
+    /// The number of composite hashes in the encoded list.
+    number_of_hashes: usize,
+    /// Every [`CHECKPOINT_INTERVAL`]-th composite hash of the encoded list, with the offset of
+    /// the byte that follows its encoding.
+    checkpoints: Vec<(u64, usize)>,
+    /// The composite hashes inserted since the last merge, sorted, distinct and absent from
+    /// the encoded list.
+    buffer: Vec<u64>,
+    /// The dense counter, allocated once the sparse representation exceeds its footprint.
+    dense: Option<Box<H>>,
+    /// The builder of the hashers of the counter, which is handed to the dense counter.
+    hasher_builder: H::HasherBuilder,
+    /// The type of the composite hash to employ.
+    composite_hash: PhantomData<CH>,
+}
+
+impl<H: HyperLogLog, CH> Default for Sparse<H, CH> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher_builder(H::HasherBuilder::default())
+    }
+}
+
+impl<H: HyperLogLog, CH> Sparse<H, CH> {
+    #[inline]
+    #[must_use]
+    /// Returns a new empty counter hashing its elements with the hashers created by the
+    /// provided builder.
+    ///
+    /// # Arguments
+    /// * `hasher_builder` - The builder of the hashers of the counter.
+    pub fn with_hasher_builder(hasher_builder: H::HasherBuilder) -> Self {
+        Self {
+            encoded: Vec::new(),
+            number_of_hashes: 0,
+            checkpoints: Vec::new(),
+            buffer: Vec::new(),
+            dense: None,
+            hasher_builder,
+            composite_hash: PhantomData,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the builder of the hashers of the counter.
+    pub fn hasher_builder(&self) -> &H::HasherBuilder {
+        &self.hasher_builder
+    }
+}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> Sparse<H, CH> {
+    #[inline]
+    #[must_use]
+    /// Returns whether the counter is still using the sparse representation.
+    pub fn is_sparse(&self) -> bool {
+        self.dense.is_none()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the dense counter, if the counter has switched to the registers.
+    pub fn dense(&self) -> Option<&H> {
+        self.dense.as_deref()
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns the number of bytes employed by the registers of the dense counter.
+    pub fn dense_size() -> usize {
+        core::mem::size_of::<H::Registers>()
+    }
+
+    /// Returns the number of hashes the insertion buffer may hold before being merged.
+    fn buffer_capacity() -> usize {
+        (Self::dense_size() / core::mem::size_of::<u64>())
+            .clamp(MINIMUM_BUFFER_CAPACITY, MAXIMUM_BUFFER_CAPACITY)
+    }
+
+    /// Returns the composite hash of the provided element, computed with the hashers of the
+    /// counter, which are also the ones of the dense counter.
+    fn composite_hash<T: Hash>(&self, element: &T) -> u64 {
+        let hash = self.hasher_builder.hash_one(element);
+        let (register, index) = H::split_hash(hash);
+        CH::encode(register, index, hash).into()
+    }
+
+    /// Returns the number of distinct composite hashes, including the buffered ones.
+    fn number_of_distinct_hashes(&self) -> usize {
+        self.number_of_hashes + self.buffer.len()
+    }
+
+    /// Returns whether the composite hash is present in the encoded list.
+    ///
+    /// # Implementative details
+    /// We bisect the checkpoints to find the last one preceding the composite hash, and resume
+    /// the decoding from it, so that at most [`CHECKPOINT_INTERVAL`] deltas are decoded.
+    fn encoded_contains_hash(&self, composite_hash: u64) -> bool {
+        let position = self
+            .checkpoints
+            .partition_point(|&(hash, _)| hash < composite_hash);
+        let (previous, offset) = match position.checked_sub(1) {
+            Some(position) => self.checkpoints[position],
+            None => (0, 0),
+        };
+        self.checkpoints
+            .get(position)
+            .is_some_and(|&(hash, _)| hash == composite_hash)
+            || DeltaDecoder::resume(&self.encoded[offset..], previous)
+                .take(CHECKPOINT_INTERVAL)
+                .take_while(|&hash| hash <= composite_hash)
+                .any(|hash| hash == composite_hash)
+    }
+
+    /// Returns whether the composite hash is present in the encoded list or in the buffer.
+    fn contains_hash(&self, composite_hash: u64) -> bool {
+        self.buffer.binary_search(&composite_hash).is_ok()
+            || self.encoded_contains_hash(composite_hash)
+    }
+
+    /// Returns the sorted and deduplicated composite hashes, including the buffered ones.
+    fn sorted_hashes(&self) -> Vec<u64> {
+        self.merge_sorted_hashes(&self.buffer)
+    }
+
+    /// Returns the sorted and deduplicated composite hashes of the encoded list and of the
+    /// provided sorted hashes.
+    ///
+    /// # Arguments
+    /// * `sorted_hashes` - The sorted hashes to merge with the encoded list.
+    fn merge_sorted_hashes(&self, sorted_hashes: &[u64]) -> Vec<u64> {
+        merge_sorted(
+            DeltaDecoder::new(&self.encoded),
+            sorted_hashes.iter().copied(),
+            self.number_of_hashes + sorted_hashes.len(),
+        )
+    }
+
+    /// Merges the insertion buffer into the encoded list, switching to the registers
+    /// if the encoded list exceeds their footprint.
+    fn merge_buffer(&mut self) {
+        let hashes = self.merge_sorted_hashes(&self.buffer);
+        self.buffer.clear();
+        self.store_sorted_hashes(&hashes);
+    }
+
+    /// Replaces the encoded list with the provided hashes, switching to the registers
+    /// if the encoded list exceeds their footprint.
+    ///
+    /// # Arguments
+    /// * `hashes` - The composite hashes, sorted in strictly increasing order.
+    fn store_sorted_hashes(&mut self, hashes: &[u64]) {
+        let (encoded, checkpoints) =
+            encode_sorted_hashes(hashes, self.encoded.len() + hashes.len());
+        if encoded.len() > Self::dense_size() {
+            self.densify(hashes);
+        } else {
+            self.encoded = encoded;
+            self.checkpoints = checkpoints;
+            self.number_of_hashes = hashes.len();
+        }
+    }
+
+    /// Switches the counter to the registers, inserting the provided composite hashes.
+    fn densify(&mut self, hashes: &[u64]) {
+        let mut registers = H::Registers::default();
+        for &hash in hashes {
+            let (register, index) = CH::decode(CH::Word::try_from_u64(hash).unwrap());
+            registers.set_greater(index, register);
+        }
+        self.dense = Some(Box::new(H::from_registers_with_hasher_builder(
+            registers,
+            self.hasher_builder.clone(),
+        )));
+        self.encoded = Vec::new();
+        self.checkpoints = Vec::new();
+        self.buffer = Vec::new();
+        self.number_of_hashes = 0;
+    }
+
+    /// Switches the counter to the registers, if it is still sparse.
+    fn switch_to_dense(&mut self) {
+        if self.dense.is_none() {
+            let hashes = self.sorted_hashes();
+            self.densify(&hashes);
+        }
+    }
+}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> PartialEq for Sparse<H, CH> {
+    #[inline]
+    /// Returns whether the two counters store the same hashes or the same registers, regardless
+    /// of which hashes are still in the insertion buffer.
+    fn eq(&self, other: &Self) -> bool {
+        self.hasher_builder == other.hasher_builder
+            && match (self.dense.as_ref(), other.dense.as_ref()) {
+                (None, None) => self.sorted_hashes() == other.sorted_hashes(),
+                (Some(left), Some(right)) => left == right,
+                _ => false,
+            }
+    }
+}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> Eq for Sparse<H, CH> {}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> BitOrAssign for Sparse<H, CH> {
+    #[inline]
+    /// Merges the provided counter into the current one.
+    ///
+    /// # Implementative details
+    /// When both counters are sparse, their sorted hashes are merged and the counter switches
+    /// to the registers only if the merged list exceeds their footprint, so that its estimate
+    /// may still be exact. Otherwise, the sparse counters are switched to the registers before
+    /// merging them.
+    fn bitor_assign(&mut self, mut rhs: Self) {
+        debug_assert!(
+            self.hasher_builder == rhs.hasher_builder,
+            "The counters must hash their elements with the same hashers."
+        );
+
+        if self.is_sparse() && rhs.is_sparse() {
+            let left = self.sorted_hashes();
+            let right = rhs.sorted_hashes();
+            let capacity = left.len() + right.len();
+            self.buffer.clear();
+            self.store_sorted_hashes(&merge_sorted(left.into_iter(), right.into_iter(), capacity));
+            return;
+        }
+
+        self.switch_to_dense();
+        rhs.switch_to_dense();
+        if let (Some(dense), Some(rhs_dense)) = (self.dense.as_mut(), rhs.dense) {
+            **dense |= *rhs_dense;
+        }
+    }
+}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> BitOr for Sparse<H, CH> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "Sparse")]
+/// The serialized state of a [`Sparse`] counter.
+enum SparseState<H> {
+    /// The counter is sparse, and stores the sorted composite hashes.
+    Hashes {
+        /// The composite hashes, sorted in strictly increasing order.
+        hashes: Vec<u64>,
+        #[serde(default)]
+        /// The seed of the hashers, if they are seeded.
+        seed: Option<u64>,
+    },
+    /// The counter has switched to the registers.
+    Dense(H),
+}
+
+#[cfg(feature = "serde")]
+impl<H: HyperLogLog + serde::Serialize, CH: CompositeHash<H::Precision, H::Bits>> serde::Serialize
+    for Sparse<H, CH>
+{
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStructVariant;
+        if let Some(dense) = self.dense.as_deref() {
+            return serializer.serialize_newtype_variant("Sparse", 1, "Dense", dense);
+        }
+        let seed = self.hasher_builder.seed();
+        let mut state = serializer.serialize_struct_variant(
+            "Sparse",
+            0,
+            "Hashes",
+            1 + usize::from(seed.is_some()),
+        )?;
+        state.serialize_field("hashes", &self.sorted_hashes())?;
+        match seed {
+            Some(seed) => state.serialize_field("seed", &seed)?,
+            None => state.skip_field("seed")?,
+        }
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, H: HyperLogLog + serde::Deserialize<'de>, CH: CompositeHash<H::Precision, H::Bits>>
+    serde::Deserialize<'de> for Sparse<H, CH>
+{
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{Error, Unexpected};
+
+        match SparseState::<H>::deserialize(deserializer)? {
+            SparseState::Dense(dense) => {
+                let mut sparse = Self::with_hasher_builder(dense.hasher_builder().clone());
+                sparse.dense = Some(Box::new(dense));
+                Ok(sparse)
+            }
+            SparseState::Hashes { hashes, seed } => {
+                let hasher_builder = H::HasherBuilder::from_seed(seed).ok_or_else(|| {
+                    D::Error::custom(crate::error::Error::SeedMismatch {
+                        expected_seeded: seed.is_none(),
+                    })
+                })?;
+                let mut previous_hash = 0;
+                for &hash in &hashes {
+                    if next_composite_hash::<H::Precision, H::Bits, CH>(hash, previous_hash)
+                        .is_none()
+                    {
+                        return Err(D::Error::invalid_value(
+                            Unexpected::Unsigned(hash),
+                            &"a strictly increasing sequence of valid composite hashes",
+                        ));
+                    }
+                    previous_hash = hash;
+                }
+                // The hashes are stored as they are, even when they exceed the footprint of the
+                // registers because some of them were still buffered, so that the loaded counter
+                // equals the serialized one and switches to the registers at its next merge.
+                let mut sparse = Self::with_hasher_builder(hasher_builder);
+                (sparse.encoded, sparse.checkpoints) = encode_sorted_hashes(&hashes, hashes.len());
+                sparse.number_of_hashes = hashes.len();
+                Ok(sparse)
+            }
+        }
+    }
+}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> SetProperties for Sparse<H, CH> {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.dense.as_ref().map_or(
+            self.number_of_hashes == 0 && self.buffer.is_empty(),
+            |dense| dense.is_empty(),
+        )
+    }
+
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.dense.as_ref().is_some_and(|dense| dense.is_full())
+    }
+}
+
+impl<H: HyperLogLog, CH: CompositeHash<H::Precision, H::Bits>> MutableSet for Sparse<H, CH> {
+    #[inline]
+    fn clear(&mut self) {
+        *self = Self::with_hasher_builder(self.hasher_builder.clone());
+    }
+}
+
+impl<
+        T: Hash,
+        H: HyperLogLog + ExtendableApproximatedSet<T>,
+        CH: CompositeHash<H::Precision, H::Bits>,
+    > ExtendableApproximatedSet<T> for Sparse<H, CH>
+{
+    #[inline]
+    /// Inserts the element into the counter.
+    ///
+    /// # Implementation details
+    /// In sparse mode, the returned value is whether the composite hash of the element was
+    /// neither in the insertion buffer nor in the encoded list, which is searched through its
+    /// checkpoints.
+    fn insert(&mut self, element: &T) -> bool {
+        if let Some(dense) = self.dense.as_mut() {
+            return dense.insert(element);
+        }
+
+        let composite_hash = self.composite_hash(element);
+        match self.buffer.binary_search(&composite_hash) {
+            Ok(_) => return false,
+            Err(_) if self.encoded_contains_hash(composite_hash) => return false,
+            Err(position) => self.buffer.insert(position, composite_hash),
+        }
+        if self.buffer.len() >= Self::buffer_capacity() {
+            self.merge_buffer();
+        }
+        true
+    }
+}
+
+impl<T: Hash, H: HyperLogLog + ApproximatedSet<T>, CH: CompositeHash<H::Precision, H::Bits>>
+    ApproximatedSet<T> for Sparse<H, CH>
+{
+    #[inline]
+    fn may_contain(&self, element: &T) -> bool {
+        if let Some(dense) = self.dense.as_ref() {
+            return dense.may_contain(element);
+        }
+
+        self.contains_hash(self.composite_hash(element))
+    }
+}
+
+impl<H: HyperLogLog + Correction + Estimator<f64>, CH: CompositeHash<H::Precision, H::Bits>>
+    Estimator<f64> for Sparse<H, CH>
+{
+    #[inline]
+    fn estimate_cardinality(&self) -> f64 {
+        match self.dense.as_ref() {
+            Some(dense) => dense.estimate_cardinality(),
+            // The number of hashes is bounded by the number of bytes of the dense registers,
+            // which cosily fits in an u32, and is corrected for the expected collisions.
+            None => CH::collision_corrected_cardinality(
+                u32::try_from(self.number_of_distinct_hashes()).unwrap(),
+            ),
+        }
+    }
+
+    #[inline]
+    fn is_union_estimate_non_deterministic(&self, other: &Self) -> bool {
+        match (self.dense.as_ref(), other.dense.as_ref()) {
+            (Some(left), Some(right)) => left.is_union_estimate_non_deterministic(right),
+            _ => false,
+        }
+    }
+
+    #[inline]
+    fn estimate_union_cardinality_with_cardinalities(
+        &self,
+        other: &Self,
+        self_cardinality: f64,
+        other_cardinality: f64,
+    ) -> f64 {
+        match (self.dense.as_ref(), other.dense.as_ref()) {
+            (None, None) => {
+                let left = self.sorted_hashes();
+                let right = other.sorted_hashes();
+                CH::collision_corrected_cardinality(unique_count_from_sorted_iterators(
+                    left.into_iter(),
+                    right.into_iter(),
+                ))
+            }
+            (None, Some(dense)) => union_estimation_from_sorted_iterator_and_counter(
+                self.sorted_hashes()
+                    .into_iter()
+                    .map(|hash| CH::decode(CH::Word::try_from_u64(hash).unwrap())),
+                dense.as_ref(),
+                self_cardinality,
+                other_cardinality,
+            ),
+            (Some(_), None) => other.estimate_union_cardinality_with_cardinalities(
+                self,
+                other_cardinality,
+                self_cardinality,
+            ),
+            (Some(left), Some(right)) => left.estimate_union_cardinality_with_cardinalities(
+                right,
+                self_cardinality,
+                other_cardinality,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: HyperLogLog + Named + Default, CH: Default + Named> Named for Sparse<H, CH> {
+    #[inline]
+    fn name(&self) -> String {
+        format!(
+            "S[{}]-{}",
+            CH::default().name(),
+            self.dense
+                .as_ref()
+                .map_or_else(|| H::default().name(), |dense| dense.name())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_deltas() {
+        let values = [1_u64, 2, 127, 128, 300, 16_383, 16_384, u64::MAX];
+        let mut encoded = Vec::new();
+        let mut previous = 0;
+        for &value in &values {
+            encode_varint(&mut encoded, value - previous);
+            previous = value;
+        }
+        assert!(DeltaDecoder::new(&encoded).eq(values.iter().copied()));
+    }
+
+    #[test]
+    #[cfg(all(feature = "plusplus", feature = "precision_8"))]
+    fn test_number_of_distinct_hashes() {
+        let mut sparse = Sparse::<
+            PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>,
+            u32,
+        >::default();
+        // The values are drawn with repetitions, so that many of them are inserted again
+        // once they have been merged into the encoded list.
+        for value in iter_random_values::<u64>(2_000, Some(500), Some(5_678)) {
+            sparse.insert(&value);
+            if !sparse.is_sparse() {
+                break;
+            }
+            assert_eq!(
+                sparse.number_of_distinct_hashes(),
+                sparse.sorted_hashes().len()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "plusplus", feature = "precision_8"))]
+    fn test_encoded_contains_hash() {
+        let mut sparse = Sparse::<
+            PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>,
+            u32,
+        >::default();
+        let hashes: Vec<u64> = (1..=150).map(|hash| hash * 3).collect();
+        sparse.store_sorted_hashes(&hashes);
+        assert!(sparse.is_sparse());
+        assert_eq!(sparse.checkpoints.len(), hashes.len() / CHECKPOINT_INTERVAL);
+        for hash in 0..=460 {
+            assert_eq!(sparse.encoded_contains_hash(hash), hashes.contains(&hash));
+        }
+    }
+}
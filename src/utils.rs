@@ -13,7 +13,9 @@
 mod to_bytes;
 mod composite_hash;
 mod constants;
+mod hasher_builder;
 mod hasher_type;
+pub(crate) mod math;
 mod matrix;
 mod number;
 mod random;
@@ -23,7 +25,8 @@ mod variable_words;
 pub use to_bytes::ToBytes;
 pub use composite_hash::CompositeHash;
 pub use constants::*;
-pub use hasher_type::HasherType;
+pub use hasher_builder::{HasherBuilder, SeededBuilder, UnseededBuilder};
+pub use hasher_type::{HasherType, SeedableHasher};
 pub use matrix::Matrix;
 pub(crate) use number::{FloatOps, Number, ToF64, PositiveInteger};
 pub use random::*;
@@ -3,6 +3,7 @@
 //! a register value and is symmetrically splittable back into a hash and
 //! a register value.
 
+use super::math::{exp, exp_m1, sqrt};
 use crate::prelude::*;
 
 /// Trait for a composite hash.
@@ -66,6 +67,97 @@ pub trait CompositeHash<P: Precision, B: Bits>: VariableWord {
 
         (register, index)
     }
+
+    #[must_use]
+    /// Returns the number of distinct hashes that most likely yielded the provided number of
+    /// distinct composite hashes, correcting for the collisions of the composite hashes.
+    ///
+    /// # Arguments
+    /// * `number_of_composite_hashes` - The number of distinct composite hashes.
+    ///
+    /// # Implementative details
+    /// Two hashes yield the same composite hash when they share the index, the padding and
+    /// the register value. The index and the padding are `NUMBER_OF_BITS - B::NUMBER_OF_BITS`
+    /// uniformly distributed bits of the hash, which identify equally likely cells. Within a
+    /// cell, the register values up to `K` have probability `2^-r`, while the remaining `2^-K`
+    /// of the probability falls on a single value, as the leading zeros are capped either by
+    /// the largest value of the registers or by the bits of the padding. As in the birthday
+    /// problem, after `n` distinct hashes the expected number of distinct composite hashes is
+    /// the sum over the `N` cells and the register values of `1 - exp(-n p / N)`, which we
+    /// invert with the Newton method, starting from the number of composite hashes. Since this
+    /// function is concave, the iterates increase monotonically towards the solution.
+    ///
+    /// The solution is rounded to the closest integer, so that the counts of the small sets,
+    /// whose expected number of collisions is negligible, are left unchanged.
+    fn collision_corrected_cardinality(number_of_composite_hashes: u32) -> f64 {
+        let observed = f64::from(number_of_composite_hashes);
+        let mut cardinality = observed;
+        for _ in 0..64 {
+            let (expected, derivative, _) = composite_hashes_moments::<P, B, Self>(cardinality);
+            let step = (observed - expected) / derivative;
+            cardinality += step;
+            if step <= cardinality * 1e-9 {
+                break;
+            }
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "The cardinality is positive and smaller than the number of the possible composite hashes."
+        )]
+        let rounded = (cardinality + 0.5) as u32;
+        f64::from(rounded)
+    }
+
+    #[must_use]
+    /// Returns the standard error of the collision corrected cardinality, i.e. of the number of
+    /// distinct hashes estimated from the number of distinct composite hashes they yielded.
+    ///
+    /// # Arguments
+    /// * `cardinality` - The collision corrected cardinality.
+    ///
+    /// # Implementative details
+    /// The number of distinct composite hashes is the number of occupied pairs of cells and
+    /// register values. Were the number of hashes Poisson distributed, their occupancies would
+    /// be independent, and the variance would be the sum of the ones of the occupancies: since
+    /// the number of hashes is instead fixed, we subtract the variance explained by it, i.e. the
+    /// squared derivative of the expected number of composite hashes times the cardinality. The
+    /// standard error of the cardinality follows through the same derivative.
+    fn collision_corrected_standard_error(cardinality: f64) -> f64 {
+        let (_, derivative, variance) = composite_hashes_moments::<P, B, Self>(cardinality);
+        sqrt(variance.max(0.0)) / derivative
+    }
+}
+
+/// Returns the expected number of distinct composite hashes yielded by the provided number of
+/// distinct hashes, with its derivative and its variance, as described in the methods of the
+/// [`CompositeHash`] trait.
+fn composite_hashes_moments<P: Precision, B: Bits, CH: CompositeHash<P, B>>(
+    cardinality: f64,
+) -> (f64, f64, f64) {
+    let number_of_cells = f64::integer_exp2(CH::NUMBER_OF_BITS - B::NUMBER_OF_BITS);
+    // The leading zeros are not shared with the bits of the padding up to this value.
+    let free_bits = 64 - CH::NUMBER_OF_BITS + B::NUMBER_OF_BITS;
+    let geometric_values = u8::try_from((B::MASK - 1).min(u64::from(free_bits))).unwrap();
+
+    let mut expected = 0.0;
+    let mut derivative = 0.0;
+    let mut variance = 0.0;
+    for register in 1..=geometric_values + 1 {
+        let probability = f64::integer_exp2_minus(register.min(geometric_values));
+        let rate = cardinality * probability / number_of_cells;
+        let empty = exp(-rate);
+        let occupied = -exp_m1(-rate);
+        expected += number_of_cells * occupied;
+        derivative += probability * empty;
+        variance += number_of_cells * empty * occupied;
+    }
+    (
+        expected,
+        derivative,
+        variance - cardinality * derivative * derivative,
+    )
 }
 
 /// Macro to implement the appropriate [`CompositeHash`] trait for a given type.
@@ -195,6 +287,72 @@ mod tests {
         }
     }
 
+    /// Returns the mean number of distinct composite hashes of the given number of random
+    /// hashes, and the mean of their collision corrected cardinality.
+    #[cfg(all(feature = "plusplus", feature = "std"))]
+    fn mean_composite_hashes<W: CompositeHash<Precision4, Bits4>>(
+        number_of_hashes: u32,
+    ) -> (f64, f64) {
+        type Counter = PlusPlus<
+            Precision4,
+            Bits4,
+            <Precision4 as ArrayRegister<Bits4>>::Array,
+            twox_hash::XxHash64,
+        >;
+        let number_of_trials: u32 = 200;
+        let mut composite_hashes = std::collections::HashSet::new();
+        let (mut total_observed, mut total_corrected) = (0.0, 0.0);
+        for trial in 0..number_of_trials {
+            composite_hashes.clear();
+            for hash in
+                iter_random_values::<u64>(u64::from(number_of_hashes), None, Some(u64::from(trial)))
+            {
+                let (register, index) = Counter::split_hash(hash);
+                composite_hashes.insert(W::encode(register, index, hash));
+            }
+            let observed = u32::try_from(composite_hashes.len()).unwrap();
+            total_observed += f64::from(observed);
+            total_corrected += W::collision_corrected_cardinality(observed);
+        }
+        (
+            total_observed / f64::from(number_of_trials),
+            total_corrected / f64::from(number_of_trials),
+        )
+    }
+
+    #[test]
+    #[cfg(all(feature = "plusplus", feature = "std"))]
+    fn test_collision_corrected_cardinality() {
+        for number_of_hashes in [10, 50, 100, 200] {
+            let (observed, corrected) = mean_composite_hashes::<u8>(number_of_hashes);
+            let expected = f64::from(number_of_hashes);
+            assert!(
+                (corrected - expected).abs() / expected < 0.05,
+                "The corrected cardinality ({corrected}) of {number_of_hashes} hashes is biased."
+            );
+            assert!(
+                (corrected - expected).abs() < (observed - expected).abs(),
+                "The correction does not improve the number of composite hashes ({observed})."
+            );
+        }
+        for number_of_hashes in [1_000, 2_000] {
+            let (_, corrected) = mean_composite_hashes::<u16>(number_of_hashes);
+            let expected = f64::from(number_of_hashes);
+            assert!((corrected - expected).abs() / expected < 0.01);
+        }
+
+        // When collisions are negligible, the number of composite hashes is left unchanged.
+        for number_of_composite_hashes in [0, 1, 10, 1_000, 1 << 15] {
+            assert_eq!(
+                <u64 as CompositeHash<Precision4, Bits4>>::collision_corrected_cardinality(
+                    number_of_composite_hashes
+                )
+                .to_bits(),
+                f64::from(number_of_composite_hashes).to_bits()
+            );
+        }
+    }
+
     #[test_all_precisions_and_bits]
     fn test_composite_hash<P: Precision, B: Bits<Word = u8>>() {
         test_composite_hash_for_word::<P, B, u32>();
@@ -16,13 +16,6 @@ pub trait One {
     fn is_one(&self) -> bool;
 }
 
-#[cfg(feature = "plusplus")]
-/// The two value for this type.
-pub(crate) trait Two {
-    /// The two value for this type.
-    const TWO: Self;
-}
-
 /// Macro implementing several constants for integers.
 macro_rules! impl_constants {
     ($($t:ty)*) => ($(
@@ -36,10 +29,6 @@ macro_rules! impl_constants {
             #[inline]
             fn is_zero(&self) -> bool { *self == 0 }
         }
-        #[cfg(feature = "plusplus")]
-        impl Two for $t {
-            const TWO: Self = 2;
-        }
     )*)
 }
 
@@ -65,8 +54,3 @@ impl Zero for f64 {
         *self == 0.0
     }
 }
-
-#[cfg(feature = "plusplus")]
-impl Two for f64 {
-    const TWO: Self = 2.0;
-}
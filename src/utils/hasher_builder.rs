@@ -0,0 +1,209 @@
+//! Builders of the hashers employed by the counters, which may be seeded.
+use super::{HasherType, SeedableHasher};
+use core::fmt::{Debug, Formatter};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::marker::PhantomData;
+
+/// Trait for the builders of the hashers employed by the counters.
+///
+/// The counters hash their elements with the hashers created by their builder, so that two
+/// counters are comparable, and may be merged, only when their builders are equal. The seed of
+/// the builder, if any, is recorded when the counter is serialized.
+pub trait HasherBuilder: BuildHasher + Default + Clone + Eq + Debug + Send + Sync {
+    /// Returns the seed of the hashers, or `None` if the hashers are not seeded.
+    fn seed(&self) -> Option<u64>;
+
+    /// Returns the builder with the provided seed, or `None` if the builder does not support it.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the hashers, or `None` for hashers that are not seeded.
+    fn from_seed(seed: Option<u64>) -> Option<Self>;
+}
+
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A zero-sized builder of hashers created with their [`Default`] implementation.
+///
+/// This is the default builder of the counters, whose elements are therefore hashed
+/// identically across counters and across runs.
+pub struct UnseededBuilder<H> {
+    /// The type of the hasher.
+    hasher: PhantomData<fn() -> H>,
+}
+
+// The traits are implemented manually, as deriving them would require the hasher to implement them.
+impl<H> Debug for UnseededBuilder<H> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        formatter.debug_struct("UnseededBuilder").finish()
+    }
+}
+
+impl<H> Clone for UnseededBuilder<H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for UnseededBuilder<H> {}
+
+impl<H> Default for UnseededBuilder<H> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H> PartialEq for UnseededBuilder<H> {
+    #[inline]
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<H> Eq for UnseededBuilder<H> {}
+
+impl<H> Hash for UnseededBuilder<H> {
+    #[inline]
+    fn hash<S: Hasher>(&self, _state: &mut S) {}
+}
+
+impl<H: Default + Hasher> BuildHasher for UnseededBuilder<H> {
+    type Hasher = H;
+
+    #[inline]
+    fn build_hasher(&self) -> H {
+        H::default()
+    }
+}
+
+impl<H: HasherType> HasherBuilder for UnseededBuilder<H> {
+    #[inline]
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+
+    #[inline]
+    fn from_seed(seed: Option<u64>) -> Option<Self> {
+        seed.is_none().then(Self::default)
+    }
+}
+
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+/// A builder of hashers keyed by a seed.
+///
+/// # Implementative details
+/// The hashers are created with the keyed constructor of their [`SeedableHasher`] implementation,
+/// so that counters with different seeds hash the same elements into different values and allow
+/// for independent sketches of the same stream, and so that without the seed the hashes cannot be
+/// predicted to craft inputs colliding into the same registers.
+///
+/// # Examples
+///
+/// ```rust
+/// use hyperloglog_rs::prelude::*;
+///
+/// type Counter = PlusPlus<
+///     Precision8,
+///     Bits6,
+///     <Precision8 as ArrayRegister<Bits6>>::Array,
+///     twox_hash::XxHash64,
+///     SeededBuilder<twox_hash::XxHash64>,
+/// >;
+///
+/// let mut first = Counter::with_seed(1);
+/// let mut same = Counter::with_seed(1);
+/// let mut second = Counter::with_seed(2);
+/// for value in 0..1_000_u64 {
+///     first.insert(&value);
+///     same.insert(&value);
+///     second.insert(&value);
+/// }
+///
+/// assert_eq!(first, same);
+/// assert_ne!(first.registers(), second.registers());
+/// ```
+pub struct SeededBuilder<H> {
+    /// The seed keying the hashers.
+    seed: u64,
+    /// The type of the hasher.
+    hasher: PhantomData<fn() -> H>,
+}
+
+impl<H> SeededBuilder<H> {
+    #[inline]
+    #[must_use]
+    /// Returns a new builder of hashers keyed by the provided seed.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed keying the hashers.
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H> Debug for SeededBuilder<H> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        formatter
+            .debug_struct("SeededBuilder")
+            .field("seed", &self.seed)
+            .finish()
+    }
+}
+
+impl<H> Clone for SeededBuilder<H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for SeededBuilder<H> {}
+
+impl<H> Default for SeededBuilder<H> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<H> PartialEq for SeededBuilder<H> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.seed == other.seed
+    }
+}
+
+impl<H> Eq for SeededBuilder<H> {}
+
+impl<H> Hash for SeededBuilder<H> {
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        self.seed.hash(state);
+    }
+}
+
+impl<H: SeedableHasher> BuildHasher for SeededBuilder<H> {
+    type Hasher = H;
+
+    #[inline]
+    fn build_hasher(&self) -> H {
+        H::with_seed(self.seed)
+    }
+}
+
+impl<H: SeedableHasher> HasherBuilder for SeededBuilder<H> {
+    #[inline]
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    #[inline]
+    fn from_seed(seed: Option<u64>) -> Option<Self> {
+        seed.map(Self::new)
+    }
+}
@@ -5,3 +5,36 @@ use core::hash::Hasher;
 pub trait HasherType: Default + Hasher + Send + Sync + Clone {}
 
 impl<T> HasherType for T where T: Default + Hasher + Send + Sync + Clone {}
+
+/// Trait for Hashers that can be keyed by a seed.
+///
+/// The seed is handed to the keyed constructor of the hasher, so that the hashes of the
+/// elements cannot be predicted, nor collisions among them crafted, without knowing it.
+pub trait SeedableHasher: HasherType {
+    /// Returns a new hasher keyed by the provided seed.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed keying the hasher.
+    fn with_seed(seed: u64) -> Self;
+}
+
+impl SeedableHasher for twox_hash::XxHash64 {
+    #[inline]
+    fn with_seed(seed: u64) -> Self {
+        Self::with_seed(seed)
+    }
+}
+
+impl SeedableHasher for twox_hash::Xxh3Hash64 {
+    #[inline]
+    fn with_seed(seed: u64) -> Self {
+        Self::with_seed(seed)
+    }
+}
+
+impl SeedableHasher for twox_hash::Xxh3Hash128 {
+    #[inline]
+    fn with_seed(seed: u64) -> Self {
+        Self::with_seed(seed)
+    }
+}
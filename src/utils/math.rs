@@ -0,0 +1,185 @@
+//! Submodule providing the elementary functions employed by the estimators in `no_std`.
+//!
+//! The exponential, the logarithm and the square root are not available in `core`, and these
+//! implementations are employed in place of the ones of the standard library, so that the
+//! estimates do not depend on whether the `std` feature is enabled.
+use super::FloatOps;
+use core::f64::consts::{LN_2, SQRT_2};
+
+/// The high bits of `ln 2`, whose products with the exponents of the `f64` are exact.
+const LN_2_HIGH: f64 = 0.693_147_180_369_123_8;
+/// The difference between `ln 2` and [`LN_2_HIGH`].
+const LN_2_LOW: f64 = 1.908_214_929_270_587_7e-10;
+
+/// Returns the binary exponent and the mantissa in `[1, 2)` of a positive and normal value.
+fn split_exponent(value: f64) -> (i16, f64) {
+    debug_assert!(value.is_normal() && value > 0.0);
+    let exponent = i16::try_from((value.to_bits() >> 52) & 0x7FF).unwrap() - 1023;
+    (exponent, value * f64::integer_exp2_minus_signed(exponent))
+}
+
+#[must_use]
+#[inline]
+/// Returns the largest integer not greater than the binary logarithm of a positive value.
+pub(crate) fn floor_log2(value: f64) -> i16 {
+    split_exponent(value).0
+}
+
+#[must_use]
+/// Returns the exponential of the provided value.
+///
+/// # Implementative details
+/// The value is reduced to `k ln 2 + r`, with `|r|` at most `ln 2 / 2`, and the exponential of
+/// `r` is computed with its Taylor series, which is then scaled by `2^k`. The product `k ln 2`
+/// is subtracted in two parts, so that the remainder is exact also for large exponents.
+pub(crate) fn exp(value: f64) -> f64 {
+    if value.is_nan() {
+        return value;
+    }
+    if value > 709.782_712_893_384 {
+        return f64::INFINITY;
+    }
+    if value < -708.396_418_532_264 {
+        // The result would be subnormal, and it is flushed to zero.
+        return 0.0;
+    }
+    let rounded = value / LN_2 + if value < 0.0 { -0.5 } else { 0.5 };
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "The value is between -1022 and 1024 after the checks above."
+    )]
+    let exponent = rounded as i16;
+    let remainder = (value - f64::from(exponent) * LN_2_HIGH) - f64::from(exponent) * LN_2_LOW;
+
+    let mut term = 1.0;
+    let mut series = 1.0;
+    for denominator in 1..=16 {
+        term *= remainder / f64::from(denominator);
+        series += term;
+    }
+    if exponent > 1023 {
+        return series * f64::integer_exp2_minus_signed(-1023) * 2.0;
+    }
+    series * f64::integer_exp2_minus_signed(-exponent)
+}
+
+#[must_use]
+/// Returns the exponential of the provided value minus one, accurately also for small values.
+pub(crate) fn exp_m1(value: f64) -> f64 {
+    if value.abs() >= 0.5 {
+        return exp(value) - 1.0;
+    }
+    let mut term = 1.0;
+    let mut series = 0.0;
+    for denominator in 1..=20 {
+        term *= value / f64::from(denominator);
+        series += term;
+    }
+    series
+}
+
+#[must_use]
+/// Returns the natural logarithm of the provided value.
+///
+/// # Implementative details
+/// The value is split into its binary exponent and a mantissa between `1 / sqrt(2)` and
+/// `sqrt(2)`, whose logarithm is computed with the series of the inverse hyperbolic tangent.
+pub(crate) fn ln(value: f64) -> f64 {
+    if value.is_nan() || value < 0.0 {
+        return f64::NAN;
+    }
+    if value == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if value.is_infinite() {
+        return value;
+    }
+    if !value.is_normal() {
+        // The subnormal values are scaled by 2^54 to make them normal.
+        return ln(value * f64::integer_exp2(54)) - 54.0 * LN_2;
+    }
+    let (mut exponent, mut mantissa) = split_exponent(value);
+    if mantissa > SQRT_2 {
+        mantissa *= 0.5;
+        exponent += 1;
+    }
+    let ratio = (mantissa - 1.0) / (mantissa + 1.0);
+    let squared_ratio = ratio * ratio;
+    let mut power = ratio;
+    let mut series = 0.0;
+    // The ratio is at most 0.172, and its squares become negligible quickly.
+    for denominator in (1..30).step_by(2) {
+        series += power / f64::from(denominator);
+        power *= squared_ratio;
+    }
+    f64::from(exponent) * LN_2 + 2.0 * series
+}
+
+#[must_use]
+/// Returns the square root of a non-negative value.
+///
+/// # Implementative details
+/// We employ the Newton's method starting from a power of two not smaller than the root,
+/// from which it converges monotonically from above.
+pub(crate) fn sqrt(value: f64) -> f64 {
+    if value.is_nan() || value < 0.0 {
+        return f64::NAN;
+    }
+    if value == 0.0 || value.is_infinite() {
+        return value;
+    }
+    if !value.is_normal() {
+        return sqrt(value * f64::integer_exp2(54)) * f64::integer_exp2_minus(27);
+    }
+    let (exponent, _) = split_exponent(value);
+    let mut root = f64::integer_exp2_minus_signed(-(exponent.div_euclid(2) + 1));
+    loop {
+        let next = 0.5 * (root + value / root);
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the relative error of the value with respect to the expected one.
+    fn relative_error(value: f64, expected: f64) -> f64 {
+        if value.to_bits() == expected.to_bits() {
+            0.0
+        } else {
+            ((value - expected) / expected).abs()
+        }
+    }
+
+    #[test]
+    fn test_elementary_functions() {
+        let mut value = 1e-300_f64;
+        while value < 1e300 {
+            assert!(relative_error(sqrt(value), value.sqrt()) <= f64::EPSILON);
+            assert!(relative_error(ln(value), value.ln()) <= 4.0 * f64::EPSILON);
+            value *= 1.37;
+        }
+        let mut value = -700.0_f64;
+        while value < 700.0 {
+            assert!(relative_error(exp(value), value.exp()) <= 8.0 * f64::EPSILON);
+            assert!(relative_error(exp_m1(value), value.exp_m1()) <= 8.0 * f64::EPSILON);
+            value += 0.173;
+        }
+        for value in [1e-20, 1e-10, 1e-3, 0.1, 0.5, 1.0, 10.0, 1e10] {
+            assert!(relative_error(exp_m1(value), value.exp_m1()) <= 4.0 * f64::EPSILON);
+            assert!(relative_error(exp_m1(-value), (-value).exp_m1()) <= 4.0 * f64::EPSILON);
+        }
+        assert_eq!(floor_log2(1.0), 0);
+        assert_eq!(floor_log2(0.75), -1);
+        assert_eq!(floor_log2(1024.5), 10);
+        assert!(exp(710.0).is_infinite());
+        assert!(exp(-1000.0).abs() < f64::EPSILON);
+        assert!(ln(0.0).is_infinite());
+        assert!(ln(-1.0).is_nan());
+        assert!(sqrt(0.0).abs() < f64::EPSILON);
+    }
+}
@@ -20,6 +20,27 @@ pub trait VariableWords<W: VariableWord> {
     /// Inserts a value into the array searching for the correct position within a given length.
     fn sorted_insert_with_len(&mut self, value: W::Word, len: usize) -> bool;
 
+    /// Merges sorted values into the array within a given length, returning the new length.
+    ///
+    /// # Arguments
+    /// * `values` - The values to be merged, sorted in strictly increasing order.
+    /// * `len` - The number of words currently stored in the array.
+    ///
+    /// # Implementative details
+    /// The values are merged starting from the end of the array, so that each word is
+    /// moved at most once. If the merged values do not fit in the array, `None` is returned
+    /// and the array is left unchanged.
+    fn sorted_merge_with_len(&mut self, values: &[W::Word], len: usize) -> Option<usize>;
+
+    /// Returns the word at the given position.
+    fn variable_word(&self, position: usize) -> W::Word;
+
+    /// Replaces the word at the given position.
+    fn set_variable_word(&mut self, position: usize, value: W::Word);
+
+    /// Sorts the words within a given length and removes the duplicates, returning the new length.
+    fn sort_dedup_with_len(&mut self, len: usize) -> usize;
+
     /// Returns an iterator over the words.
     fn iter_variable_words<'words>(&'words self, len: usize) -> Self::Words<'words>
     where
@@ -111,6 +132,68 @@ where
         }
     }
 
+    fn sorted_merge_with_len(&mut self, values: &[W::Word], len: usize) -> Option<usize> {
+        debug_assert!(
+            self.as_ref().len() >= len,
+            "The array must have enough elements."
+        );
+        debug_assert!(
+            values.windows(2).all(|w| w[0] < w[1]),
+            "The values must be strictly sorted, i.e. sorted with no duplicates."
+        );
+
+        let words = self.as_mut();
+        let number_of_duplicates = values
+            .iter()
+            .filter(|&&value| words[0..len].binary_search(&value.into()).is_ok())
+            .count();
+        let new_len = len + values.len() - number_of_duplicates;
+        if new_len > words.len() {
+            return None;
+        }
+
+        let mut left = len;
+        let mut right = values.len();
+        let mut position = new_len;
+        while right > 0 {
+            let value: <Self as Words<W>>::SliceType = values[right - 1].into();
+            position -= 1;
+            if left > 0 && words[left - 1] > value {
+                words[position] = words[left - 1];
+                left -= 1;
+            } else {
+                if left > 0 && words[left - 1] == value {
+                    left -= 1;
+                }
+                words[position] = value;
+                right -= 1;
+            }
+        }
+
+        Some(new_len)
+    }
+
+    fn variable_word(&self, position: usize) -> W::Word {
+        W::Word::from(self.as_ref()[position])
+    }
+
+    fn set_variable_word(&mut self, position: usize, value: W::Word) {
+        self.as_mut()[position] = value.into();
+    }
+
+    fn sort_dedup_with_len(&mut self, len: usize) -> usize {
+        let words = &mut self.as_mut()[0..len];
+        words.sort_unstable();
+        let mut number_of_unique_words = usize::from(len > 0);
+        for position in 1..len {
+            if words[position] != words[number_of_unique_words - 1] {
+                words[number_of_unique_words] = words[position];
+                number_of_unique_words += 1;
+            }
+        }
+        number_of_unique_words
+    }
+
     fn iter_variable_words<'words>(&'words self, len: usize) -> Self::Words<'words>
     where
         W: 'words,
@@ -148,6 +231,33 @@ mod test {
                         assert_eq!(words, [1, 2, 3, 4, 5], "We do not insert the value if it is already in the array.");
                     }
 
+                    #[test]
+                    fn [<test_sorted_merge_with_len_ $typ>]() {
+                        let mut words: [$typ; 8] = [2, 4, 6, 0, 0, 0, 0, 0];
+                        assert_eq!(words.sorted_merge_with_len(&[1, 4, 7], 3), Some(5));
+                        assert_eq!(words, [1, 2, 4, 6, 7, 0, 0, 0]);
+                        assert_eq!(words.sorted_merge_with_len(&[], 5), Some(5));
+                        assert_eq!(words, [1, 2, 4, 6, 7, 0, 0, 0]);
+                        assert_eq!(words.sorted_merge_with_len(&[0, 3, 5, 8], 5), None, "We do not merge the values if they do not fit in the array.");
+                        assert_eq!(words, [1, 2, 4, 6, 7, 0, 0, 0]);
+                        assert_eq!(words.sorted_merge_with_len(&[3, 5, 8], 5), Some(8));
+                        assert_eq!(words, [1, 2, 3, 4, 5, 6, 7, 8]);
+                        assert_eq!(words.sorted_merge_with_len(&[1, 8], 8), Some(8), "We merge the values already in the array even if the array is full.");
+                        assert_eq!(words, [1, 2, 3, 4, 5, 6, 7, 8]);
+                    }
+
+                    #[test]
+                    fn [<test_sort_dedup_with_len_ $typ>]() {
+                        let mut words: [$typ; 6] = [5, 2, 5, 1, 2, 9];
+                        assert_eq!(words.sort_dedup_with_len(5), 3);
+                        assert_eq!(&words[..3], &[1, 2, 5]);
+                        assert_eq!(words[5], 9, "We do not touch the words beyond the length.");
+                        assert_eq!(words.sort_dedup_with_len(0), 0);
+
+                        words.set_variable_word(1, 4);
+                        assert_eq!(words.variable_word(1), 4);
+                    }
+
                     #[test]
                     fn [<test_find_sorted_with_len_ $typ>]() {
                         let words: [$typ; 5] = [1, 2, 3, 4, 5];
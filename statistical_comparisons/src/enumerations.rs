@@ -67,12 +67,19 @@ where
     SimpleHLL(SimpleHLL<H, EXPONENT>),
     PP4ArrayXxhasher(PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H>),
     PP4PackedXxhasher(PlusPlus<P, B, <P as ArrayRegister<B>>::Packed, H>),
+    PPI4ArrayXxhasher(PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H, UnseededBuilder<H>, PlusPlusBias<IntegerBiases>>),
+    PPK4ArrayXxhasher(PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H, UnseededBuilder<H>, PlusPlusBias<FloatBiases, KMeansInterpolation>>),
+    PPIK4ArrayXxhasher(PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H, UnseededBuilder<H>, PlusPlusBias<IntegerBiases, KMeansInterpolation>>),
+    PPStdLn4ArrayXxhasher(PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H, UnseededBuilder<H>, PlusPlusBias<FloatBiases, LinearInterpolation, LinearCounting, StdLn>>),
     LLB4ArrayXxhasher(LogLogBeta<P, B, <P as ArrayRegister<B>>::Array, H>),
+    LLHB4ArrayXxhasher(LogLogBeta<P, B, <P as ArrayRegister<B>>::Array, H, UnseededBuilder<H>, BetaBias<HornerBeta>>),
+    IMP4ArrayXxhasher(Improved<P, B, <P as ArrayRegister<B>>::Array, H>),
     MLEPP4Xxhasher(MLE<PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H>>),
     MLELLB4Xxhasher(MLE<LogLogBeta<P, B, <P as ArrayRegister<B>>::Array, H>>),
     HybridPP4ArrayXxhasher(Hybrid<PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H>, CH>),
     HybridPP4PackedXxhasher(Hybrid<PlusPlus<P, B, <P as ArrayRegister<B>>::Packed, H>, CH>),
     HybridLLB4ArrayXxhasher(Hybrid<LogLogBeta<P, B, <P as ArrayRegister<B>>::Array, H>, CH>),
+    HybridIMP4ArrayXxhasher(Hybrid<Improved<P, B, <P as ArrayRegister<B>>::Array, H>, CH>),
     HybridMLEPP4Xxhasher(Hybrid<MLE<PlusPlus<P, B, <P as ArrayRegister<B>>::Array, H>>, CH>),
     HybridMLELLB4Xxhasher(Hybrid<MLE<LogLogBeta<P, B, <P as ArrayRegister<B>>::Array, H>>, CH>),
 }
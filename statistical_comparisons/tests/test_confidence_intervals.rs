@@ -0,0 +1,107 @@
+//! Test suite for the empirical coverage of the confidence intervals of the estimates.
+use hyperloglog_rs::prelude::*;
+
+/// The number of pairs of sets on which the coverage is measured.
+const NUMBER_OF_TRIALS: u64 = 500;
+/// The confidence of the intervals.
+const CONFIDENCE: f64 = 0.95;
+/// The registers of the counters with precision 8.
+type Registers8 = <Precision8 as ArrayRegister<Bits6>>::Array;
+/// The registers of the counters with precision 10.
+type Registers10 = <Precision10 as ArrayRegister<Bits6>>::Array;
+
+/// The estimates with bounds of the cardinality, union, intersection, Jaccard index and
+/// difference of a pair of counters.
+type Intervals = [EstimateWithBounds; 5];
+
+/// Returns the intervals of the pair of counters.
+fn intervals<H: SetEstimatorWithBounds>(left: &H, right: &H) -> Intervals {
+    [
+        left.estimate_with_bounds(CONFIDENCE),
+        left.estimate_union_with_bounds(right, CONFIDENCE),
+        left.estimate_intersection_with_bounds(right, CONFIDENCE),
+        left.estimate_jaccard_index_with_bounds(right, CONFIDENCE),
+        left.estimate_difference_with_bounds(right, CONFIDENCE),
+    ]
+}
+
+/// Returns the fraction of the trials in which each interval contains the exact value.
+///
+/// # Arguments
+/// * `number_of_values` - The number of values inserted into either counter of each trial.
+fn coverage<H: SetEstimatorWithBounds + ExtendableApproximatedSet<u64> + Default>(
+    number_of_values: u64,
+) -> [f64; 5] {
+    let mut state = number_of_values.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut hits = [0_u64; 5];
+    for _ in 0..NUMBER_OF_TRIALS {
+        let mut left = H::default();
+        let mut right = H::default();
+        // The cardinalities of the elements only in the left, only in the right and in both.
+        let mut regions = [0.0_f64; 3];
+        for _ in 0..number_of_values {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let region = usize::try_from(state % 3).unwrap();
+            if region != 1 {
+                left.insert(&state);
+            }
+            if region != 0 {
+                right.insert(&state);
+            }
+            regions[region] += 1.0;
+        }
+
+        let [left_only, right_only, both] = regions;
+        let union = left_only + right_only + both;
+        let exact = [left_only + both, union, both, both / union, left_only];
+        for ((hit, interval), value) in hits.iter_mut().zip(intervals(&left, &right)).zip(exact) {
+            assert!(interval.lower_bound <= interval.estimate);
+            assert!(interval.estimate <= interval.upper_bound);
+            *hit += u64::from(interval.contains(value));
+        }
+    }
+    hits.map(|hit| hit as f64 / NUMBER_OF_TRIALS as f64)
+}
+
+/// Checks that the empirical coverage of the intervals is close to the confidence.
+fn check_coverage<H: SetEstimatorWithBounds + ExtendableApproximatedSet<u64> + Default>() {
+    for number_of_values in [5_000, 20_000, 100_000] {
+        let coverage = coverage::<H>(number_of_values);
+        for (operation, coverage) in coverage.into_iter().enumerate() {
+            assert!(
+                (0.9..=0.99).contains(&coverage),
+                "The interval {operation} with {number_of_values} values has coverage {coverage}."
+            );
+        }
+    }
+}
+
+#[test]
+fn test_confidence_intervals_plusplus() {
+    check_coverage::<PlusPlus<Precision10, Bits6, Registers10>>();
+}
+
+#[test]
+fn test_confidence_intervals_beta() {
+    check_coverage::<LogLogBeta<Precision10, Bits6, Registers10>>();
+}
+
+#[test]
+fn test_confidence_intervals_improved() {
+    check_coverage::<Improved<Precision10, Bits6, Registers10>>();
+}
+
+#[test]
+fn test_confidence_intervals_hybrid() {
+    check_coverage::<Hybrid<PlusPlus<Precision10, Bits6, Registers10>>>();
+}
+
+#[test]
+#[cfg(feature = "mle")]
+fn test_confidence_intervals_mle() {
+    check_coverage::<MLE<PlusPlus<Precision10, Bits6, Registers10>>>();
+    check_coverage::<MLE<PlusPlus<Precision8, Bits6, Registers8>>>();
+    check_coverage::<MLE<LogLogBeta<Precision10, Bits6, Registers10>>>();
+}
@@ -0,0 +1,105 @@
+//! Utilities shared by the test suites.
+#![allow(
+    dead_code,
+    reason = "Each test suite employs only some of the utilities."
+)]
+
+use core::hash::{BuildHasher, Hasher};
+use hyperloglog_rs::prelude::*;
+
+/// The hasher of the `ahash` crate, with its identifier in the binary format.
+pub type AHasher = IdentifiedHasher<SeedableAHasher, 1>;
+/// The hasher of the `wyhash` crate, with its identifier in the binary format.
+pub type WyHash = IdentifiedHasher<SeedableWyHash, 2>;
+
+#[derive(Default, Clone)]
+/// The hasher of the `ahash` crate, keyed through its random state.
+pub struct SeedableAHasher(ahash::AHasher);
+
+impl Hasher for SeedableAHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+impl SeedableHasher for SeedableAHasher {
+    fn with_seed(seed: u64) -> Self {
+        // The four keys of the random state are derived from the seed.
+        let first = splitmix64(seed);
+        let second = splitmix64(first);
+        let third = splitmix64(second);
+        let fourth = splitmix64(third);
+        Self(ahash::RandomState::with_seeds(first, second, third, fourth).build_hasher())
+    }
+}
+
+#[derive(Default, Clone)]
+/// The hasher of the `wyhash` crate, keyed through its seeded constructor.
+pub struct SeedableWyHash(wyhash::WyHash);
+
+impl Hasher for SeedableWyHash {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+impl SeedableHasher for SeedableWyHash {
+    fn with_seed(seed: u64) -> Self {
+        Self(wyhash::WyHash::with_seed(seed))
+    }
+}
+
+/// Returns the counter serialized in the binary format.
+pub fn serialize<C: BinaryFormat>(counter: &C) -> Vec<u8> {
+    let mut bytes = vec![0; counter.serialized_size()];
+    assert_eq!(counter.write_bytes(&mut bytes), Ok(bytes.len()));
+    bytes
+}
+
+/// Rewrites the FNV-1a checksum closing the serialized counter, so that tampered bytes pass the
+/// integrity check and reach the validation of their content.
+pub fn reseal(bytes: &mut [u8]) {
+    let checksum_offset = bytes.len() - 8;
+    let checksum = bytes[..checksum_offset]
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    bytes[checksum_offset..].copy_from_slice(&checksum.to_le_bytes());
+}
+
+/// Returns the mean relative error of the provided estimates of the counter at the provided
+/// cardinalities, averaged over several seeds.
+///
+/// # Arguments
+/// * `cardinalities` - The increasing cardinalities at which the counter is estimated.
+/// * `number_of_seeds` - The number of seeds of the random values inserted in the counter.
+/// * `estimate` - The estimate of the cardinality of the counter.
+pub fn mean_relative_error<H: ExtendableApproximatedSet<u64> + Default>(
+    cardinalities: &[u64],
+    number_of_seeds: u64,
+    estimate: fn(&H) -> f64,
+) -> f64 {
+    let mut total_error = 0.0;
+    for seed in 1..=number_of_seeds {
+        let mut counter = H::default();
+        let mut inserted = 0;
+        let mut values = iter_random_values::<u64>(u64::MAX, None, Some(seed));
+        for &cardinality in cardinalities {
+            while inserted < cardinality {
+                counter.insert(&values.next().unwrap());
+                inserted += 1;
+            }
+            total_error += (estimate(&counter) - cardinality as f64).abs() / cardinality as f64;
+        }
+    }
+    total_error / (cardinalities.len() as u64 * number_of_seeds) as f64
+}
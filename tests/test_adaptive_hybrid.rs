@@ -0,0 +1,208 @@
+//! Test suite for the adaptive hybrid counters.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use core::hash::BuildHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use std::collections::HashSet;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// The number of bits of the composite hashes employed by the adaptive counters, from the widest.
+const COMPOSITE_HASH_BITS: [u8; 6] = [64, 56, 48, 40, 32, 24];
+
+/// Returns the collision corrected cardinality of the provided number of composite hashes
+/// of the given number of bits.
+fn collision_corrected_cardinality<P: Precision, B: Bits>(
+    composite_hash_bits: u8,
+    number_of_composite_hashes: usize,
+) -> f64
+where
+    u24: CompositeHash<P, B>,
+{
+    let number_of_composite_hashes = u32::try_from(number_of_composite_hashes).unwrap();
+    match composite_hash_bits {
+        64 => <u64 as CompositeHash<P, B>>::collision_corrected_cardinality(
+            number_of_composite_hashes,
+        ),
+        56 => <u56 as CompositeHash<P, B>>::collision_corrected_cardinality(
+            number_of_composite_hashes,
+        ),
+        48 => <u48 as CompositeHash<P, B>>::collision_corrected_cardinality(
+            number_of_composite_hashes,
+        ),
+        40 => <u40 as CompositeHash<P, B>>::collision_corrected_cardinality(
+            number_of_composite_hashes,
+        ),
+        32 => <u32 as CompositeHash<P, B>>::collision_corrected_cardinality(
+            number_of_composite_hashes,
+        ),
+        _ => <u24 as CompositeHash<P, B>>::collision_corrected_cardinality(
+            number_of_composite_hashes,
+        ),
+    }
+}
+
+/// Returns the composite hash of the provided hash, encoded with the provided word.
+fn encode<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType, CH: CompositeHash<P, B>>(
+    hash: u64,
+) -> u64 {
+    let (register, index) = PlusPlus::<P, B, R, H>::split_hash(hash);
+    CH::encode(register, index, hash).into()
+}
+
+#[test_estimator]
+fn test_adaptive_hybrid_plusplus<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>
+        + VariableWords<u64>
+        + VariableWords<u56>
+        + VariableWords<u48>
+        + VariableWords<u40>
+        + VariableWords<u32>
+        + VariableWords<u24>,
+    H: HasherType,
+>()
+where
+    u24: CompositeHash<P, B>,
+{
+    type Counter<P, B, R, H> = AdaptiveHybrid<PlusPlus<P, B, R, H>>;
+
+    let mut counter = Counter::<P, B, R, H>::default();
+    let mut fixed = Hybrid::<PlusPlus<P, B, R, H>, u64>::default();
+    let mut dense = PlusPlus::<P, B, R, H>::default();
+    assert!(counter.is_empty());
+    assert!(counter.is_hybrid());
+    assert_eq!(counter.composite_hash_bits(), 64);
+    assert_eq!(counter.capacity(), fixed.capacity());
+
+    // The distinct composite hashes of the inserted values, for each width.
+    let mut exact: [HashSet<u64>; 6] = Default::default();
+    let mut snapshots: Vec<Counter<P, B, R, H>> = Vec::new();
+    let mut inserted = Vec::new();
+    let mut outlasted_fixed = false;
+
+    let number_of_values = 100 << P::EXPONENT;
+    for value in iter_random_values::<u64>(number_of_values, None, Some(6_543)) {
+        let hash = dense.hasher_builder().hash_one(value);
+        let composite_hashes = [
+            encode::<P, B, R, H, u64>(hash),
+            encode::<P, B, R, H, u56>(hash),
+            encode::<P, B, R, H, u48>(hash),
+            encode::<P, B, R, H, u40>(hash),
+            encode::<P, B, R, H, u32>(hash),
+            encode::<P, B, R, H, u24>(hash),
+        ];
+        for (set, composite_hash) in exact.iter_mut().zip(composite_hashes) {
+            set.insert(composite_hash);
+        }
+
+        let bits = counter.composite_hash_bits();
+        counter.insert(&value);
+        fixed.insert(&value);
+        dense.insert(&value);
+        inserted.push(value);
+
+        // The composite hashes only ever become narrower.
+        assert!(counter.composite_hash_bits() <= bits);
+
+        if !counter.is_hybrid() {
+            // The counter is dehybridized only once the narrowest composite hashes are full.
+            assert_eq!(counter.composite_hash_bits(), 24);
+            break;
+        }
+
+        // While in hybrid mode, the counter counts the distinct composite hashes of the current
+        // words, corrected for their expected collisions.
+        let width = COMPOSITE_HASH_BITS
+            .iter()
+            .position(|&bits| bits == counter.composite_hash_bits())
+            .unwrap();
+        assert_eq!(
+            counter.estimate_cardinality(),
+            collision_corrected_cardinality::<P, B>(
+                counter.composite_hash_bits(),
+                exact[width].len()
+            )
+        );
+        assert!(!counter.is_full() || counter.composite_hash_bits() == 24);
+
+        if counter.composite_hash_bits() != bits {
+            assert!(inserted.iter().all(|value| counter.may_contain(value)));
+            snapshots.push(counter.clone());
+        }
+
+        // The adaptive counter stays in hybrid mode longer than the one with the widest words.
+        if !fixed.is_hybrid() {
+            outlasted_fixed = true;
+        }
+    }
+
+    assert!(outlasted_fixed);
+    assert!(!counter.is_hybrid());
+    assert!(inserted.iter().all(|value| counter.may_contain(value)));
+    assert_eq!(
+        counter.estimate_cardinality().to_bits(),
+        dense.estimate_cardinality().to_bits()
+    );
+
+    // The snapshots contain increasingly large prefixes of the inserted values, so the
+    // union of two of them is the latest one, once the hashes are equally narrow.
+    for (position, earlier) in snapshots.iter().enumerate() {
+        for later in &snapshots[position..] {
+            assert_eq!(
+                earlier.estimate_union_cardinality(later),
+                later.estimate_cardinality()
+            );
+            assert_eq!(
+                later.estimate_union_cardinality(earlier),
+                later.estimate_cardinality()
+            );
+        }
+    }
+
+    counter.clear();
+    assert!(counter.is_empty());
+    assert!(counter.is_hybrid());
+    assert_eq!(counter.composite_hash_bits(), 64);
+}
+
+#[test]
+#[cfg(feature = "precision_18")]
+/// Checks the estimates of the counters holding more than `u16::MAX` composite hashes, which
+/// the narrowest composite hashes allow at the largest precision they support.
+fn test_hybrid_more_hashes_than_u16() {
+    type Counter = PlusPlus<Precision18, Bits6, <Precision18 as ArrayRegister<Bits6>>::Array>;
+
+    // The counters are filled up to their capacity while still in hybrid mode.
+    let mut fixed = Hybrid::<Counter, u24>::default();
+    assert!(fixed.capacity() > usize::from(u16::MAX));
+    let mut fixed_values = 0_u64;
+    while !fixed.is_full() {
+        fixed.insert(&fixed_values);
+        fixed_values += 1;
+    }
+    assert!(fixed.is_hybrid());
+
+    let mut adaptive = AdaptiveHybrid::<Counter>::default();
+    let mut adaptive_values = 0_u64;
+    while !adaptive.is_full() {
+        adaptive.insert(&adaptive_values);
+        adaptive_values += 1;
+    }
+    assert!(adaptive.is_hybrid());
+    assert_eq!(adaptive.composite_hash_bits(), 24);
+    assert_eq!(adaptive.capacity(), fixed.capacity());
+
+    for (estimate, number_of_values) in [
+        (fixed.estimate_cardinality(), fixed_values),
+        (adaptive.estimate_cardinality(), adaptive_values),
+    ] {
+        assert!(
+            (estimate - number_of_values as f64).abs() < 0.02 * number_of_values as f64,
+            "Got {estimate} for {number_of_values} values."
+        );
+    }
+}
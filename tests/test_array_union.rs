@@ -0,0 +1,76 @@
+//! Test suite checking that the unions of array registers match the ones of packed registers.
+//!
+//! When the `swar` feature is enabled, the unions of the unpacked array registers are computed
+//! one word at a time, while the ones of the packed registers are always computed one register
+//! at a time: the two must yield exactly the same results.
+#![cfg(feature = "plusplus")]
+
+use hyperloglog_derive::test_all_precisions_and_bits;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+
+/// Checks that the unions of array and packed registers are identical.
+fn check_array_union<P: ArrayRegister<B>, B: Bits>() {
+    for (left_size, right_size, seed) in [
+        (0, 0, 1),
+        (0, 10, 2),
+        (10, 100, 3),
+        (1_000, 5_000, 4),
+        (50_000, 20_000, 5),
+    ] {
+        let left_values: Vec<u64> =
+            iter_random_values::<u64>(left_size, None, Some(seed)).collect();
+        let right_values: Vec<u64> =
+            iter_random_values::<u64>(right_size, None, Some(seed + 10)).collect();
+
+        let mut array_left = PlusPlus::<P, B, <P as ArrayRegister<B>>::Array, XxHash>::default();
+        let mut array_right = array_left.clone();
+        let mut packed_left = PlusPlus::<P, B, <P as ArrayRegister<B>>::Packed, XxHash>::default();
+        let mut packed_right = packed_left.clone();
+        array_left.extend(left_values.iter().copied());
+        array_right.extend(right_values.iter().copied());
+        packed_left.extend(left_values.iter().copied());
+        packed_right.extend(right_values.iter().copied());
+
+        assert_eq!(
+            array_left
+                .estimate_union_cardinality(&array_right)
+                .to_bits(),
+            packed_left
+                .estimate_union_cardinality(&packed_right)
+                .to_bits()
+        );
+
+        let array_union = array_left.clone() | array_right.clone();
+        let packed_union = packed_left.clone() | packed_right.clone();
+        assert!(array_union
+            .registers()
+            .iter_registers()
+            .eq(packed_union.registers().iter_registers()));
+        assert_eq!(
+            array_union.estimate_cardinality().to_bits(),
+            packed_union.estimate_cardinality().to_bits()
+        );
+
+        // The union must be the same counter obtained by inserting all the values.
+        let mut array_direct = array_left.clone();
+        array_direct.extend(right_values.iter().copied());
+        assert!(array_union == array_direct);
+        assert!(array_union == array_right | array_left);
+    }
+}
+
+#[test_all_precisions_and_bits]
+fn test_array_union<P: ArrayRegister<B>, B: Bits>() {
+    check_array_union::<P, B>();
+}
+
+#[test]
+#[cfg(feature = "precision_10")]
+fn test_array_union_other_bits() {
+    check_array_union::<Precision10, Bits1>();
+    check_array_union::<Precision10, Bits2>();
+    check_array_union::<Precision10, Bits3>();
+    check_array_union::<Precision10, Bits7>();
+    check_array_union::<Precision10, Bits8>();
+}
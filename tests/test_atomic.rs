@@ -0,0 +1,72 @@
+//! Test suite for the concurrent insertion into the atomic counters.
+#![cfg(feature = "plusplus")]
+
+use core::hash::{Hash, Hasher};
+use hyperloglog_derive::test_all_precisions_and_bits;
+use hyperloglog_rs::prelude::*;
+use std::thread;
+use twox_hash::XxHash;
+
+/// The number of threads inserting into the same counter.
+const NUMBER_OF_THREADS: usize = 8;
+
+/// Checks that the concurrent insertion yields the same counter as the sequential one.
+fn check_atomic<P: ArrayRegister<B>, B: Bits>() {
+    for (number_of_values, seed) in [(0, 1), (10, 2), (1_000, 3), (50_000, 4)] {
+        let values: Vec<u64> =
+            iter_random_values::<u64>(number_of_values, None, Some(seed)).collect();
+        let hashes: Vec<u64> = values
+            .iter()
+            .map(|value| {
+                let mut hasher = XxHash::default();
+                value.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+
+        let mut sequential = PlusPlus::<P, B, <P as ArrayRegister<B>>::Array, XxHash>::default();
+        sequential.extend(values.iter().copied());
+
+        let by_value = AtomicPlusPlus::<P, B, <P as ArrayRegister<B>>::Atomic, XxHash>::default();
+        let by_hash = AtomicPlusPlus::<P, B, <P as ArrayRegister<B>>::Atomic, XxHash>::default();
+
+        // All the threads insert all the values, each starting from a different offset, so
+        // that the same registers are contended by several threads at the same time.
+        thread::scope(|scope| {
+            for thread_number in 0..NUMBER_OF_THREADS {
+                let offset = thread_number * values.len() / NUMBER_OF_THREADS;
+                let (by_value, by_hash, values, hashes) = (&by_value, &by_hash, &values, &hashes);
+                scope.spawn(move || {
+                    for index in (offset..values.len()).chain(0..offset) {
+                        by_value.insert(&values[index]);
+                        by_hash.insert_hash(hashes[index]);
+                    }
+                });
+            }
+        });
+
+        let by_value = by_value.snapshot();
+        assert!(by_value == sequential);
+        assert!(by_hash.snapshot() == sequential);
+        assert_eq!(
+            by_value.estimate_cardinality().to_bits(),
+            sequential.estimate_cardinality().to_bits()
+        );
+
+        // Resuming from an existing counter keeps its registers.
+        let resumed =
+            AtomicPlusPlus::<P, B, <P as ArrayRegister<B>>::Atomic, XxHash>::from(&sequential);
+        for value in &values {
+            assert!(!resumed.insert(value));
+        }
+        assert!(resumed.snapshot() == sequential);
+
+        resumed.clear();
+        assert!(resumed.snapshot() == PlusPlus::default());
+    }
+}
+
+#[test_all_precisions_and_bits]
+fn test_atomic<P: ArrayRegister<B>, B: Bits>() {
+    check_atomic::<P, B>();
+}
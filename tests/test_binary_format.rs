@@ -0,0 +1,297 @@
+//! Test suite for the binary format of the counters.
+#![cfg(feature = "plusplus")]
+
+mod common;
+
+use common::{reseal, serialize, AHasher, WyHash};
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+
+/// Checks that corrupted, truncated or otherwise invalid bytes are rejected.
+fn check_corruptions<C: BinaryFormat>(bytes: &[u8]) {
+    let mut small_buffer = vec![0; bytes.len() - 1];
+    assert_eq!(
+        C::from_bytes(bytes).unwrap().write_bytes(&mut small_buffer),
+        Err(Error::BufferTooSmall {
+            required: bytes.len(),
+            available: bytes.len() - 1
+        })
+    );
+
+    assert_eq!(
+        C::from_bytes(&bytes[..bytes.len() - 1]).err(),
+        Some(Error::UnexpectedLength {
+            expected: bytes.len(),
+            found: bytes.len() - 1
+        })
+    );
+
+    let mut corrupted = bytes.to_vec();
+    corrupted[0] = b'X';
+    assert_eq!(C::from_bytes(&corrupted).err(), Some(Error::InvalidMagic));
+
+    let mut corrupted = bytes.to_vec();
+    corrupted[4] = 255;
+    assert_eq!(
+        C::from_bytes(&corrupted).err(),
+        Some(Error::UnsupportedVersion(255))
+    );
+
+    for position in [6, 12, 20, bytes.len() - 9] {
+        let mut corrupted = bytes.to_vec();
+        corrupted[position] ^= 0b0001_0000;
+        assert_eq!(
+            C::from_bytes(&corrupted).err(),
+            Some(Error::ChecksumMismatch)
+        );
+    }
+}
+
+#[test_estimator]
+fn test_plusplus_binary_format<
+    P: Precision + ArrayRegister<B> + ArrayRegister<Bits8>,
+    B: Bits,
+    R: Registers<P, B>,
+    H: HasherType + HasherId,
+>() {
+    let mut counter = PlusPlus::<P, B, R, H>::default();
+    let empty = serialize(&counter);
+    assert!(PlusPlus::<P, B, R, H>::from_bytes(&empty).unwrap() == counter);
+
+    counter.extend(iter_random_values::<u64>(5_000, None, Some(8_765)));
+    let bytes = serialize(&counter);
+    assert_eq!(&bytes[..4], &MAGIC);
+    assert_eq!(bytes[4], VERSION);
+
+    let loaded = PlusPlus::<P, B, R, H>::from_bytes(&bytes).unwrap();
+    assert!(loaded == counter);
+    assert!(
+        (loaded.estimate_cardinality() - counter.estimate_cardinality()).abs()
+            <= counter.estimate_cardinality() * f64::EPSILON * 16.0
+    );
+    assert_eq!(serialize(&loaded), bytes);
+
+    check_corruptions::<PlusPlus<P, B, R, H>>(&bytes);
+
+    // Loading the counter with a different layout fails.
+    if R::PACKED {
+        assert_eq!(
+            PlusPlus::<P, B, <P as ArrayRegister<B>>::Array, H>::from_bytes(&bytes).err(),
+            Some(Error::LayoutMismatch {
+                expected_packed: false
+            })
+        );
+    } else {
+        assert_eq!(
+            PlusPlus::<P, B, <P as ArrayRegister<B>>::Packed, H>::from_bytes(&bytes).err(),
+            Some(Error::LayoutMismatch {
+                expected_packed: true
+            })
+        );
+    }
+
+    // Loading the counter with a different number of bits fails.
+    assert_eq!(
+        PlusPlus::<P, Bits8, <P as ArrayRegister<Bits8>>::Array, H>::from_bytes(&bytes).err(),
+        Some(Error::BitsMismatch {
+            expected: 8,
+            found: B::NUMBER_OF_BITS
+        })
+    );
+
+    // Loading the counter with a different hasher fails.
+    assert_eq!(
+        PlusPlus::<P, B, R, twox_hash::XxHash32>::from_bytes(&bytes).err(),
+        Some(Error::HasherMismatch {
+            expected: twox_hash::XxHash32::HASHER_ID,
+            found: H::HASHER_ID
+        })
+    );
+
+    // Loading the counter with a different estimator fails.
+    #[cfg(feature = "beta")]
+    assert_eq!(
+        LogLogBeta::<P, B, R, H>::from_bytes(&bytes).err(),
+        Some(Error::EstimatorMismatch {
+            expected: LOGLOGBETA_ESTIMATOR,
+            found: PLUSPLUS_ESTIMATOR
+        })
+    );
+}
+
+#[cfg(feature = "beta")]
+#[test_estimator]
+fn test_beta_binary_format<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType + HasherId>() {
+    let mut counter = LogLogBeta::<P, B, R, H>::default();
+    counter.extend(iter_random_values::<u64>(5_000, None, Some(1_234)));
+    let bytes = serialize(&counter);
+    let loaded = LogLogBeta::<P, B, R, H>::from_bytes(&bytes).unwrap();
+    assert!(loaded == counter);
+    assert_eq!(serialize(&loaded), bytes);
+    check_corruptions::<LogLogBeta<P, B, R, H>>(&bytes);
+}
+
+#[cfg(feature = "improved")]
+#[test_estimator]
+fn test_improved_binary_format<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    H: HasherType + HasherId,
+>() {
+    let mut counter = Improved::<P, B, R, H>::default();
+    counter.extend(iter_random_values::<u64>(5_000, None, Some(8_642)));
+    let bytes = serialize(&counter);
+    let loaded = Improved::<P, B, R, H>::from_bytes(&bytes).unwrap();
+    assert!(loaded == counter);
+    assert_eq!(serialize(&loaded), bytes);
+    check_corruptions::<Improved<P, B, R, H>>(&bytes);
+
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::from_bytes(&bytes).err(),
+        Some(Error::EstimatorMismatch {
+            expected: PLUSPLUS_ESTIMATOR,
+            found: IMPROVED_ESTIMATOR
+        })
+    );
+}
+
+#[cfg(feature = "mle")]
+#[test_estimator]
+fn test_mle_plusplus_binary_format<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    H: HasherType + HasherId,
+>() {
+    let mut counter = MLE::<PlusPlus<P, B, R, H>>::default();
+    counter.extend(iter_random_values::<u64>(5_000, None, Some(4_321)));
+    let bytes = serialize(&counter);
+    let loaded = MLE::<PlusPlus<P, B, R, H>>::from_bytes(&bytes).unwrap();
+    assert!(loaded == counter);
+    assert_eq!(serialize(&loaded), bytes);
+
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::from_bytes(&bytes).err(),
+        Some(Error::EstimatorMismatch {
+            expected: PLUSPLUS_ESTIMATOR,
+            found: PLUSPLUS_ESTIMATOR | MLE_ESTIMATOR_FLAG
+        })
+    );
+}
+
+#[test_estimator]
+fn test_hybrid_plusplus_binary_format<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<u32> + VariableWords<u64>,
+    H: HasherType + HasherId,
+>()
+where
+    u32: CompositeHash<P, B>,
+    u64: CompositeHash<P, B>,
+{
+    let mut counter = Hybrid::<PlusPlus<P, B, R, H>, u32>::default();
+    let mut next_check = 0_u64;
+
+    // We check the roundtrip both in hybrid mode and after the counter has
+    // switched to the registers.
+    for (inserted, value) in (1_u64..).zip(iter_random_values::<u64>(5_000, None, Some(2_468))) {
+        counter.insert(&value);
+
+        if inserted < next_check {
+            continue;
+        }
+        next_check = inserted + if counter.is_hybrid() { 7 } else { 1_000 };
+
+        let bytes = serialize(&counter);
+        assert_eq!(bytes[8] & 0b10 != 0, counter.is_hybrid());
+        assert_eq!(bytes[9], 32);
+        let loaded = Hybrid::<PlusPlus<P, B, R, H>, u32>::from_bytes(&bytes).unwrap();
+        assert!(loaded == counter);
+        assert_eq!(loaded.is_hybrid(), counter.is_hybrid());
+        assert_eq!(
+            loaded.estimate_cardinality().to_bits(),
+            counter.estimate_cardinality().to_bits()
+        );
+        assert_eq!(serialize(&loaded), bytes);
+
+        assert_eq!(
+            Hybrid::<PlusPlus<P, B, R, H>, u64>::from_bytes(&bytes).err(),
+            Some(Error::CompositeHashMismatch {
+                expected: 64,
+                found: 32
+            })
+        );
+        assert_eq!(
+            PlusPlus::<P, B, R, H>::from_bytes(&bytes).err(),
+            Some(Error::EstimatorMismatch {
+                expected: PLUSPLUS_ESTIMATOR,
+                found: PLUSPLUS_ESTIMATOR | HYBRID_ESTIMATOR_FLAG
+            })
+        );
+
+        if counter.is_hybrid() {
+            check_hybrid_tampering::<P, B, R, H>(&bytes, counter.capacity());
+        }
+    }
+}
+
+/// Checks that tampered bytes of a counter in hybrid mode are rejected even when their
+/// checksum is valid.
+fn check_hybrid_tampering<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<u32>,
+    H: HasherType + HasherId,
+>(
+    bytes: &[u8],
+    capacity: usize,
+) where
+    u32: CompositeHash<P, B>,
+{
+    type Counter<P, B, R, H> = Hybrid<PlusPlus<P, B, R, H>, u32>;
+
+    // Only the hybrid counters may load the hashes stored in hybrid mode.
+    let mut tampered = bytes.to_vec();
+    tampered[5] = PLUSPLUS_ESTIMATOR;
+    tampered[9] = 0;
+    reseal(&mut tampered);
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::from_bytes(&tampered).err(),
+        Some(Error::UnsupportedHybridMode)
+    );
+
+    let mut tampered = bytes.to_vec();
+    tampered[20..24].copy_from_slice(&u32::try_from(capacity + 1).unwrap().to_le_bytes());
+    reseal(&mut tampered);
+    assert_eq!(
+        Counter::<P, B, R, H>::from_bytes(&tampered).err(),
+        Some(Error::InvalidNumberOfHashes {
+            capacity,
+            found: capacity + 1
+        })
+    );
+
+    // The stored hashes must be non-zero and sorted in strictly increasing order.
+    let number_of_hashes = u32::from_le_bytes(bytes[20..24].try_into().unwrap());
+    let mut tampered = bytes.to_vec();
+    tampered[36..bytes.len() - 8].fill(0);
+    reseal(&mut tampered);
+    assert_eq!(
+        Counter::<P, B, R, H>::from_bytes(&tampered).err(),
+        Some(Error::InvalidCompositeHash(0))
+    );
+
+    if number_of_hashes >= 4 {
+        // Each word stores two hashes, so repeating the first word repeats its hashes.
+        let mut tampered = bytes.to_vec();
+        tampered.copy_within(36..44, 44);
+        reseal(&mut tampered);
+        assert!(matches!(
+            Counter::<P, B, R, H>::from_bytes(&tampered),
+            Err(Error::InvalidCompositeHash(_))
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+//! Test suite for the rings of counters keyed by epoch.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// The number of epochs retained by the rings in the tests.
+const NUMBER_OF_BUCKETS: usize = 8;
+
+/// The number of epochs covered by the tests.
+const NUMBER_OF_EPOCHS: u64 = 20;
+
+#[test_estimator]
+fn test_bucketed_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    let values: Vec<u64> = iter_random_values::<u64>(4_000, None, Some(5_612)).collect();
+    let epochs: Vec<u64> = (0..values.len() as u64)
+        .map(|i| i * NUMBER_OF_EPOCHS / values.len() as u64)
+        .collect();
+
+    let mut ring = BucketedCounter::<PlusPlus<P, B, R, H>, NUMBER_OF_BUCKETS>::default();
+    let mut shards = [
+        BucketedCounter::<PlusPlus<P, B, R, H>, NUMBER_OF_BUCKETS>::default(),
+        BucketedCounter::<PlusPlus<P, B, R, H>, NUMBER_OF_BUCKETS>::default(),
+    ];
+
+    for (i, (value, epoch)) in values.iter().zip(epochs.iter()).enumerate() {
+        assert!(ring.insert_at(value, *epoch) || ring.bucket(*epoch).unwrap().may_contain(value));
+        shards[i % 2].insert_at(value, *epoch);
+    }
+    assert_eq!(ring.epoch(), NUMBER_OF_EPOCHS - 1);
+
+    // Elements of epochs which are not retained anymore are not inserted.
+    assert!(!ring.insert_at(&values[0], 0));
+
+    let [first, second] = shards;
+    assert!(first | second == ring);
+
+    let oldest_epoch = NUMBER_OF_EPOCHS - NUMBER_OF_BUCKETS as u64;
+    for (start, end) in [
+        (0, NUMBER_OF_EPOCHS),
+        (oldest_epoch, NUMBER_OF_EPOCHS),
+        (oldest_epoch + 2, oldest_epoch + 5),
+        (NUMBER_OF_EPOCHS - 1, NUMBER_OF_EPOCHS),
+        (3, 4),
+    ] {
+        let mut expected = PlusPlus::<P, B, R, H>::default();
+        for (value, epoch) in values.iter().zip(epochs.iter()) {
+            if *epoch >= start.max(oldest_epoch) && *epoch < end {
+                expected.insert(value);
+            }
+        }
+
+        assert!(ring.counter_range(start..end) == expected);
+        // The harmonic sum of the expected counter is accumulated in a different order.
+        let estimate = expected.estimate_cardinality();
+        assert!((ring.estimate_range(start..end) - estimate).abs() <= estimate.abs() * 1e-9);
+    }
+
+    // Advancing the ring clears the buckets of the epochs that are not retained anymore.
+    let mut advanced = ring.clone();
+    advanced.advance_to(NUMBER_OF_EPOCHS + 2);
+    assert!(advanced.bucket(oldest_epoch + 1).is_none());
+    assert!(advanced.bucket(NUMBER_OF_EPOCHS).unwrap().is_empty());
+    assert!(advanced.counter_range(..) == ring.counter_range(oldest_epoch + 3..));
+    advanced.advance_to(NUMBER_OF_EPOCHS * 2);
+    assert_eq!(
+        advanced.estimate_range(..).to_bits(),
+        PlusPlus::<P, B, R, H>::default()
+            .estimate_cardinality()
+            .to_bits()
+    );
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&ring).unwrap();
+        let loaded: BucketedCounter<PlusPlus<P, B, R, H>, NUMBER_OF_BUCKETS> =
+            serde_json::from_str(&json).unwrap();
+        assert!(loaded == ring);
+        assert_eq!(loaded.epoch(), ring.epoch());
+
+        // A ring with a different number of buckets cannot be loaded.
+        assert!(serde_json::from_str::<BucketedCounter<PlusPlus<P, B, R, H>, 4>>(&json).is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "precision_8")]
+/// Checks that the rings keep working with the latest possible epochs.
+fn test_bucketed_latest_epochs() {
+    type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+
+    let mut ring = BucketedCounter::<Counter, NUMBER_OF_BUCKETS>::default();
+    let mut expected = Counter::default();
+    for (value, epoch) in (0..100_u64).zip((u64::MAX - 9..=u64::MAX).cycle()) {
+        ring.insert_at(&value, epoch);
+        if epoch > u64::MAX - NUMBER_OF_BUCKETS as u64 {
+            expected.insert(&value);
+        }
+    }
+
+    assert_eq!(ring.epoch(), u64::MAX);
+    assert!(ring.bucket(u64::MAX - NUMBER_OF_BUCKETS as u64).is_none());
+    assert!(ring.counter_range(..) == expected);
+    assert!(ring.counter_range(u64::MAX..) == *ring.bucket(u64::MAX).unwrap());
+}
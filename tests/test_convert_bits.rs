@@ -0,0 +1,94 @@
+//! Test suite for converting counters to a different number of bits per register.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Checks the conversion of the provided counter to a counter with a different number of bits.
+fn check_convert_bits<
+    From: HyperLogLog + ExtendableApproximatedSet<u64>,
+    To: HyperLogLog<
+            Precision = From::Precision,
+            Hasher = From::Hasher,
+            HasherBuilder = From::HasherBuilder,
+        > + ExtendableApproximatedSet<u64>,
+>() {
+    let mut from = From::default();
+    let mut direct = To::default();
+    // We insert many more values than registers, so that the registers of the
+    // counters with fewer bits saturate.
+    for value in iter_random_values::<u64>(50_000, None, Some(8_642)) {
+        from.insert(&value);
+        direct.insert(&value);
+    }
+
+    let converted: To = from.convert_bits();
+    let maximum = To::maximal_register_value();
+
+    // The register values are saturated to the largest value of the new registers.
+    assert!(converted.registers().iter_registers().eq(from
+        .registers()
+        .iter_registers()
+        .map(|value| value.min(maximum))));
+
+    if To::Bits::NUMBER_OF_BITS <= From::Bits::NUMBER_OF_BITS {
+        // Narrowing is equivalent to inserting the elements directly.
+        assert!(converted == direct);
+    } else {
+        // Widening is lossless, and converting back yields the original counter.
+        assert!(converted
+            .registers()
+            .iter_registers()
+            .eq(from.registers().iter_registers()));
+        assert!(converted.convert_bits::<From>() == from);
+    }
+}
+
+#[test_estimator]
+fn test_plusplus_convert_bits<
+    P: Precision
+        + ArrayRegister<Bits1>
+        + ArrayRegister<Bits4>
+        + ArrayRegister<Bits6>
+        + ArrayRegister<Bits8>,
+    B: Bits,
+    R: Registers<P, B>,
+    H: HasherType,
+>() {
+    check_convert_bits::<
+        PlusPlus<P, B, R, H>,
+        PlusPlus<P, Bits1, <P as ArrayRegister<Bits1>>::Packed, H>,
+    >();
+    check_convert_bits::<
+        PlusPlus<P, B, R, H>,
+        PlusPlus<P, Bits4, <P as ArrayRegister<Bits4>>::Packed, H>,
+    >();
+    check_convert_bits::<
+        PlusPlus<P, B, R, H>,
+        PlusPlus<P, Bits6, <P as ArrayRegister<Bits6>>::Array, H>,
+    >();
+    check_convert_bits::<
+        PlusPlus<P, B, R, H>,
+        PlusPlus<P, Bits8, <P as ArrayRegister<Bits8>>::Array, H>,
+    >();
+    check_convert_bits::<PlusPlus<P, B, R, H>, PlusPlus<P, B, R, H>>();
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut counter = PlusPlus::<P, B, R, H>::default();
+        let mut narrow = PlusPlus::<P, Bits4, <P as ArrayRegister<Bits4>>::Packed, H>::default();
+        counter.extend(iter_random_values::<u64>(50_000, None, Some(8_642)));
+        narrow.extend(iter_random_values::<u64>(50_000, None, Some(8_642)));
+
+        let dynamic = DynamicPlusPlus::<H>::from(counter);
+        assert_eq!(
+            dynamic.convert_bits(4).unwrap(),
+            DynamicPlusPlus::<H>::from(narrow)
+        );
+        assert_eq!(dynamic.convert_bits(B::NUMBER_OF_BITS).unwrap(), dynamic);
+        assert_eq!(dynamic.convert_bits(9), Err(Error::UnsupportedBits(9)));
+    }
+}
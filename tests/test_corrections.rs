@@ -0,0 +1,191 @@
+//! Test suite for the bias correction strategies selected with the type parameters of the counters.
+#![cfg(all(feature = "plusplus", feature = "beta"))]
+
+mod common;
+
+use common::mean_relative_error;
+use hyperloglog_rs::prelude::*;
+
+type P = Precision8;
+type B = Bits6;
+type R = <Precision8 as ArrayRegister<Bits6>>::Array;
+type H = twox_hash::XxHash64;
+type PlusPlusWith<C> = PlusPlus<P, B, R, H, UnseededBuilder<H>, C>;
+type LogLogBetaWith<C> = LogLogBeta<P, B, R, H, UnseededBuilder<H>, C>;
+
+/// Inserts the same values in the two counters, returning their estimates.
+fn estimates<
+    L: Estimator<f64> + ExtendableApproximatedSet<u64> + HyperLogLog + Default,
+    M: Estimator<f64>
+        + ExtendableApproximatedSet<u64>
+        + HyperLogLog<Registers = L::Registers>
+        + Default,
+>(
+    number_of_values: u64,
+) -> (f64, f64) {
+    let mut left = L::default();
+    let mut right = M::default();
+    for value in iter_random_values::<u64>(number_of_values, None, Some(7_654)) {
+        left.insert(&value);
+        right.insert(&value);
+    }
+    assert_eq!(left.registers(), right.registers());
+    (left.estimate_cardinality(), right.estimate_cardinality())
+}
+
+#[test]
+/// Checks that the default type arguments correspond to the default strategies.
+fn test_default_strategies() {
+    for number_of_values in [0, 10, 100, 1_000, 10_000] {
+        let (default, explicit) = estimates::<
+            PlusPlus<P, B, R>,
+            PlusPlusWith<PlusPlusBias<FloatBiases, LinearInterpolation, LinearCounting, LookupLn>>,
+        >(number_of_values);
+        assert_eq!(default.to_bits(), explicit.to_bits());
+
+        let (default, explicit) = estimates::<
+            LogLogBeta<P, B, R>,
+            LogLogBetaWith<BetaBias<PrecomputedBeta, LinearCounting, LookupLn>>,
+        >(number_of_values);
+        assert_eq!(default.to_bits(), explicit.to_bits());
+    }
+}
+
+#[test]
+/// Checks that all of the strategies coexist in the same build and estimate accurately.
+fn test_strategies_accuracy() {
+    let cardinalities: Vec<u64> = (0..30).map(|i| (10.0 * 1.3_f64.powi(i)) as u64).collect();
+    let errors = [
+        mean_relative_error::<PlusPlusWith<PlusPlusBias>>(
+            &cardinalities,
+            20,
+            Estimator::estimate_cardinality,
+        ),
+        mean_relative_error::<PlusPlusWith<PlusPlusBias<IntegerBiases>>>(
+            &cardinalities,
+            20,
+            Estimator::estimate_cardinality,
+        ),
+        mean_relative_error::<PlusPlusWith<PlusPlusBias<FloatBiases, KMeansInterpolation>>>(
+            &cardinalities,
+            20,
+            Estimator::estimate_cardinality,
+        ),
+        mean_relative_error::<PlusPlusWith<PlusPlusBias<IntegerBiases, KMeansInterpolation>>>(
+            &cardinalities,
+            20,
+            Estimator::estimate_cardinality,
+        ),
+        mean_relative_error::<LogLogBetaWith<BetaBias>>(
+            &cardinalities,
+            20,
+            Estimator::estimate_cardinality,
+        ),
+        mean_relative_error::<LogLogBetaWith<BetaBias<HornerBeta>>>(
+            &cardinalities,
+            20,
+            Estimator::estimate_cardinality,
+        ),
+    ];
+    for error in errors {
+        assert!(
+            error < 2.0 * P::error_rate(),
+            "The mean relative error is {error}."
+        );
+    }
+}
+
+#[test]
+/// Checks that the beta function evaluated at runtime matches the precomputed one.
+fn test_horner_beta() {
+    for number_of_values in [10, 100, 1_000, 10_000] {
+        let (precomputed, horner) = estimates::<
+            LogLogBetaWith<BetaBias>,
+            LogLogBetaWith<BetaBias<HornerBeta>>,
+        >(number_of_values);
+        assert!(
+            (precomputed - horner).abs() <= precomputed * 1e-6,
+            "Precomputed: {precomputed}, Horner: {horner}."
+        );
+    }
+}
+
+#[test]
+/// Checks that only the counters switching to the linear counting do so when many
+/// registers are zeros.
+fn test_small_range_correction() {
+    let (linear_counting, bias_only) = estimates::<
+        PlusPlusWith<PlusPlusBias>,
+        PlusPlusWith<PlusPlusBias<FloatBiases, LinearInterpolation, NoSmallRangeCorrection>>,
+    >(10);
+    assert_ne!(linear_counting.to_bits(), bias_only.to_bits());
+
+    // With few zero registers both counters rely on the bias correction alone.
+    let (linear_counting, bias_only) = estimates::<
+        PlusPlusWith<PlusPlusBias>,
+        PlusPlusWith<PlusPlusBias<FloatBiases, LinearInterpolation, NoSmallRangeCorrection>>,
+    >(2_000);
+    assert_eq!(linear_counting.to_bits(), bias_only.to_bits());
+}
+
+#[test]
+#[cfg(feature = "std")]
+/// Checks that the logarithms of the standard library match the lookup table.
+fn test_std_ln() {
+    for number_of_values in [10, 100, 1_000] {
+        let (lookup, std) = estimates::<
+            PlusPlusWith<PlusPlusBias>,
+            PlusPlusWith<PlusPlusBias<FloatBiases, LinearInterpolation, LinearCounting, StdLn>>,
+        >(number_of_values);
+        assert!(
+            (lookup - std).abs() <= lookup * 1e-9,
+            "Lookup: {lookup}, std: {std}."
+        );
+
+        let (lookup, std) = estimates::<
+            LogLogBetaWith<BetaBias<HornerBeta>>,
+            LogLogBetaWith<BetaBias<HornerBeta, LinearCounting, StdLn>>,
+        >(number_of_values);
+        assert!(
+            (lookup - std).abs() <= lookup * 1e-9,
+            "Lookup: {lookup}, std: {std}."
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+/// Checks that the names of the counters reflect their strategies.
+fn test_strategy_names() {
+    let names = [
+        PlusPlusWith::<PlusPlusBias>::default().name(),
+        PlusPlusWith::<PlusPlusBias<IntegerBiases>>::default().name(),
+        PlusPlusWith::<PlusPlusBias<FloatBiases, KMeansInterpolation>>::default().name(),
+        PlusPlusWith::<PlusPlusBias<IntegerBiases, KMeansInterpolation>>::default().name(),
+        PlusPlusWith::<PlusPlusBias<FloatBiases, LinearInterpolation, NoSmallRangeCorrection>>::default()
+            .name(),
+        PlusPlusWith::<PlusPlusBias<FloatBiases, LinearInterpolation, LinearCounting, StdLn>>::default()
+            .name(),
+        LogLogBetaWith::<BetaBias>::default().name(),
+        LogLogBetaWith::<BetaBias<HornerBeta>>::default().name(),
+        LogLogBetaWith::<BetaBias<HornerBeta, LinearCounting, StdLn>>::default().name(),
+    ];
+    let estimators: Vec<&str> = names
+        .iter()
+        .map(|name| name.split('<').next().unwrap())
+        .collect();
+    assert_eq!(
+        estimators,
+        [
+            "PP",
+            "PPI",
+            "PPK",
+            "PPIK",
+            "PP-no-lc",
+            "PP-std-ln",
+            "LLPB",
+            "LLB",
+            "LLB-std-ln"
+        ]
+    );
+}
@@ -0,0 +1,115 @@
+//! Test suite checking that the runtime-configured counters are bit-identical
+//! to their type-level counterparts.
+#![cfg(feature = "alloc")]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Inserts the same random values in the static and dynamic counters, checking
+/// at each step that the estimates are identical.
+fn test_dynamic_counter<
+    S: HyperLogLog + Estimator<f64> + ExtendableApproximatedSet<u64> + Clone,
+    D: Estimator<f64>
+        + ExtendableApproximatedSet<u64>
+        + BitOr<Output = D>
+        + Clone
+        + From<S>
+        + Eq
+        + core::fmt::Debug,
+>(
+    mut left_static: S,
+    mut left_dynamic: D,
+) {
+    let mut right_static = left_static.clone();
+    let mut right_dynamic = left_dynamic.clone();
+
+    for (i, value) in iter_random_values::<u64>(10_000, None, Some(4567)).enumerate() {
+        if i % 2 == 0 {
+            assert_eq!(left_static.insert(&value), left_dynamic.insert(&value));
+        } else {
+            assert_eq!(right_static.insert(&value), right_dynamic.insert(&value));
+        }
+
+        if i % 97 == 0 {
+            assert_eq!(
+                left_static.estimate_cardinality().to_bits(),
+                left_dynamic.estimate_cardinality().to_bits()
+            );
+            assert_eq!(
+                left_static
+                    .estimate_union_cardinality(&right_static)
+                    .to_bits(),
+                left_dynamic
+                    .estimate_union_cardinality(&right_dynamic)
+                    .to_bits()
+            );
+        }
+    }
+
+    assert_eq!(D::from(left_static.clone()), left_dynamic);
+
+    let union_static = left_static | right_static;
+    let union_dynamic = left_dynamic | right_dynamic;
+    assert_eq!(
+        union_static.estimate_cardinality().to_bits(),
+        union_dynamic.estimate_cardinality().to_bits()
+    );
+    assert_eq!(D::from(union_static), union_dynamic);
+}
+
+#[cfg(feature = "plusplus")]
+#[test_estimator]
+fn test_dynamic_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>()
+where
+    PlusPlus<P, B, R, H>: Default,
+{
+    test_dynamic_counter(
+        PlusPlus::<P, B, R, H>::default(),
+        DynamicPlusPlus::<H>::new(P::EXPONENT, B::NUMBER_OF_BITS).unwrap(),
+    );
+}
+
+#[cfg(feature = "beta")]
+#[test_estimator]
+fn test_dynamic_beta<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>()
+where
+    LogLogBeta<P, B, R, H>: Default,
+{
+    test_dynamic_counter(
+        LogLogBeta::<P, B, R, H>::default(),
+        DynamicLogLogBeta::<H>::new(P::EXPONENT, B::NUMBER_OF_BITS).unwrap(),
+    );
+}
+
+#[cfg(feature = "plusplus")]
+#[test_estimator]
+fn test_dynamic_plusplus_integer_kmeans<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>()
+where
+    PlusPlus<P, B, R, H>: Default,
+{
+    type Correction = PlusPlusBias<IntegerBiases, KMeansInterpolation, NoSmallRangeCorrection>;
+    test_dynamic_counter(
+        PlusPlus::<P, B, R, H, UnseededBuilder<H>, Correction>::default(),
+        DynamicPlusPlus::<H, UnseededBuilder<H>, Correction>::new(P::EXPONENT, B::NUMBER_OF_BITS)
+            .unwrap(),
+    );
+}
+
+#[cfg(feature = "beta")]
+#[test_estimator]
+fn test_dynamic_horner_beta<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>()
+where
+    LogLogBeta<P, B, R, H>: Default,
+{
+    test_dynamic_counter(
+        LogLogBeta::<P, B, R, H, UnseededBuilder<H>, BetaBias<HornerBeta>>::default(),
+        DynamicLogLogBeta::<H, UnseededBuilder<H>, BetaBias<HornerBeta>>::new(
+            P::EXPONENT,
+            B::NUMBER_OF_BITS,
+        )
+        .unwrap(),
+    );
+}
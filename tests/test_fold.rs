@@ -0,0 +1,84 @@
+//! Test suite for folding counters to lower precisions.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Checks that folding the high precision counter yields the low precision one.
+fn check_fold<
+    High: HyperLogLog + ExtendableApproximatedSet<u64>,
+    Low: HyperLogLog<Bits = High::Bits, Hasher = High::Hasher, HasherBuilder = High::HasherBuilder>
+        + ExtendableApproximatedSet<u64>,
+>() {
+    let mut high = High::default();
+    let mut low = Low::default();
+    let mut other_low = Low::default();
+    let mut union_low = Low::default();
+
+    for value in iter_random_values::<u64>(5_000, None, Some(3_579)) {
+        high.insert(&value);
+        low.insert(&value);
+        union_low.insert(&value);
+    }
+    for value in iter_random_values::<u64>(2_000, None, Some(9_753)) {
+        other_low.insert(&value);
+        union_low.insert(&value);
+    }
+
+    assert!(high.fold_to::<Low>().unwrap() == low);
+    assert!(high.folded_union::<Low, Low>(&other_low).unwrap() == union_low);
+    assert!(other_low.folded_union::<High, Low>(&high).unwrap() == union_low);
+
+    if Low::Precision::EXPONENT < High::Precision::EXPONENT {
+        assert_eq!(
+            low.fold_to::<High>().err(),
+            Some(Error::FoldToHigherPrecision {
+                from: Low::Precision::EXPONENT,
+                to: High::Precision::EXPONENT
+            })
+        );
+    }
+}
+
+#[test_estimator]
+fn test_plusplus_fold<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>()
+where
+    Precision4: ArrayRegister<B>,
+{
+    check_fold::<PlusPlus<P, B, R, H>, PlusPlus<P, B, R, H>>();
+    check_fold::<
+        PlusPlus<P, B, R, H>,
+        PlusPlus<Precision4, B, <Precision4 as ArrayRegister<B>>::Array, H>,
+    >();
+    check_fold::<
+        PlusPlus<P, B, R, H>,
+        PlusPlus<Precision4, B, <Precision4 as ArrayRegister<B>>::Packed, H>,
+    >();
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut high = PlusPlus::<P, B, R, H>::default();
+        let mut low =
+            PlusPlus::<Precision4, B, <Precision4 as ArrayRegister<B>>::Array, H>::default();
+        high.extend(iter_random_values::<u64>(5_000, None, Some(3_579)));
+        low.extend(iter_random_values::<u64>(5_000, None, Some(3_579)));
+
+        let dynamic_high = DynamicPlusPlus::<H>::from(high);
+        let dynamic_low = DynamicPlusPlus::<H>::from(low);
+        assert_eq!(dynamic_high.fold_to(4).unwrap(), dynamic_low);
+        assert_eq!(dynamic_high.folded_union(&dynamic_low), dynamic_low);
+        assert_eq!(dynamic_low.folded_union(&dynamic_high), dynamic_low);
+        if P::EXPONENT > 4 {
+            assert_eq!(
+                dynamic_low.fold_to(P::EXPONENT),
+                Err(Error::FoldToHigherPrecision {
+                    from: 4,
+                    to: P::EXPONENT
+                })
+            );
+        }
+    }
+}
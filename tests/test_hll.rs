@@ -127,6 +127,11 @@ fn test_beta<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
     test_approximated_counter_at_precision_and_bits::<P, LogLogBeta<P, B, R, H>>();
 }
 
+#[test_estimator]
+fn test_improved<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    test_approximated_counter_at_precision_and_bits::<P, Improved<P, B, R, H>>();
+}
+
 #[test_estimator]
 #[cfg(feature = "mle")]
 fn test_mle_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
@@ -199,6 +204,25 @@ fn test_hybrid_beta<
     test_approximated_counter_at_precision_and_bits::<P, Hybrid<LogLogBeta<P, B, R, H>, u64>>();
 }
 
+#[test_estimator]
+fn test_hybrid_improved<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>
+        + VariableWords<u32>
+        + VariableWords<u40>
+        + VariableWords<u48>
+        + VariableWords<u56>
+        + VariableWords<u64>,
+    H: HasherType,
+>() {
+    test_approximated_counter_at_precision_and_bits::<P, Hybrid<Improved<P, B, R, H>, u32>>();
+    test_approximated_counter_at_precision_and_bits::<P, Hybrid<Improved<P, B, R, H>, u40>>();
+    test_approximated_counter_at_precision_and_bits::<P, Hybrid<Improved<P, B, R, H>, u48>>();
+    test_approximated_counter_at_precision_and_bits::<P, Hybrid<Improved<P, B, R, H>, u56>>();
+    test_approximated_counter_at_precision_and_bits::<P, Hybrid<Improved<P, B, R, H>, u64>>();
+}
+
 #[test]
 #[cfg(feature = "plusplus")]
 fn test_hybrid_plusplus_low_bits_hash() {
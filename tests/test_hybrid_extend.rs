@@ -0,0 +1,97 @@
+//! Test suite for the batched insertion of elements into hybrid counters.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use core::hash::BuildHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+#[test_estimator]
+fn test_hybrid_plusplus_extend<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<u32>,
+    H: HasherType,
+>()
+where
+    u32: CompositeHash<P, B>,
+{
+    type Counter<P, B, R, H> = Hybrid<PlusPlus<P, B, R, H>, u32>;
+
+    let capacity = Counter::<P, B, R, H>::default().capacity();
+    let values: Vec<u64> =
+        iter_random_values::<u64>(3 * capacity as u64 + 1_000, None, Some(5_432)).collect();
+    let counter = PlusPlus::<P, B, R, H>::default();
+
+    // We extend the counters with batches of different sizes, including repeated
+    // elements, and compare them with the counters built inserting one element at a time.
+    for number_of_elements in [0, 1, 17, capacity / 2, capacity, 3 * capacity] {
+        let elements = &values[..number_of_elements];
+        let repeated = elements.iter().chain(elements.iter().step_by(3));
+
+        let mut sequential = Counter::<P, B, R, H>::default();
+        for element in repeated.clone() {
+            sequential.insert(element);
+        }
+        let mut extended = Counter::<P, B, R, H>::default();
+        extended.extend(repeated.clone());
+        let mut from_hashes = Counter::<P, B, R, H>::default();
+        let hashes: Vec<u64> = repeated
+            .clone()
+            .map(|element| counter.hasher_builder().hash_one(element))
+            .collect();
+        from_hashes.extend_hashes(&hashes);
+
+        assert!(elements.iter().all(|element| extended.may_contain(element)));
+        assert!(extended == from_hashes);
+
+        // The counters inserting one element at a time switch to the registers as soon as
+        // an element arrives when they are full, even if it is already stored, while the
+        // batched insertion does so only when the distinct hashes exceed the capacity.
+        if sequential.is_hybrid() {
+            assert!(extended.is_hybrid());
+        }
+        if sequential.is_hybrid() == extended.is_hybrid() {
+            assert!(extended == sequential);
+            assert_eq!(
+                extended.estimate_cardinality().to_bits(),
+                sequential.estimate_cardinality().to_bits()
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "precision_8")]
+/// Checks that extending a hybrid counter with fewer hashes than its capacity keeps it in
+/// hybrid mode without forwarding the hashes to the batched insertion of the registers.
+fn test_hybrid_extend_hashes_remains_hybrid() {
+    type Counter =
+        Hybrid<PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>, u32>;
+    let mut counter = Counter::default();
+    counter.extend_hashes(&[1 << 40, 2 << 40, 3 << 40]);
+    assert!(counter.is_hybrid());
+
+    let hashes = [
+        0x9E37_79B9_7F4A_7C15,
+        0xC2B2_AE3D_27D4_EB4F,
+        0x1656_67B1_9E37_79F9,
+    ];
+    let mut counter = Counter::default();
+    counter.extend_hashes(&hashes);
+    assert!(counter.is_hybrid());
+    assert_eq!(counter.estimate_cardinality(), 3.0);
+}
+
+#[test]
+#[cfg(feature = "precision_8")]
+#[should_panic(expected = "The batched insertion is not available in hybrid mode.")]
+/// Checks that the batched insertion into the registers of a counter in hybrid mode, which
+/// would corrupt its hashes, panics instead.
+fn test_extend_hashes_panics_in_hybrid_mode() {
+    type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+    let mut counter = <Counter as Hybridazable<u32>>::new_hybrid();
+    counter.extend_hashes(&[1 << 40, 2 << 40, 3 << 40]);
+}
@@ -0,0 +1,144 @@
+//! Test suite checking that hybrid counters are serialized without losing their hybrid state.
+#![cfg(all(feature = "serde", feature = "plusplus"))]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Checks that the provided counter survives a round-trip through JSON.
+fn check_roundtrip<H, CH>(counter: &Hybrid<H, CH>) -> Hybrid<H, CH>
+where
+    H: Hybridazable<CH>
+        + Clone
+        + Correction
+        + Estimator<f64>
+        + Default
+        + serde::Serialize
+        + serde::de::DeserializeOwned,
+    CH: CompositeHash<H::Precision, H::Bits>,
+{
+    let json = serde_json::to_string(counter).unwrap();
+    let loaded: Hybrid<H, CH> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.is_hybrid(), counter.is_hybrid());
+    assert_eq!(
+        loaded.estimate_cardinality().to_bits(),
+        counter.estimate_cardinality().to_bits()
+    );
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    loaded
+}
+
+/// Inserts random values in a hybrid counter, checking the round-trip at several points.
+fn test_hybrid_serde_for_word<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<CH>,
+    H: HasherType,
+    CH: CompositeHash<P, B>,
+>() {
+    let mut counter = Hybrid::<PlusPlus<P, B, R, H>, CH>::default();
+    let mut next_check = 1;
+
+    for (inserted, value) in (1_u64..).zip(iter_random_values::<u64>(2_000, None, Some(9_753))) {
+        counter.insert(&value);
+        if inserted < next_check {
+            continue;
+        }
+        next_check *= 2;
+
+        // The loaded counter must keep behaving as the original one.
+        let mut loaded = check_roundtrip(&counter);
+        let mut expected = counter.clone();
+        for other in iter_random_values::<u64>(50, None, Some(inserted)) {
+            assert_eq!(loaded.insert(&other), expected.insert(&other));
+            assert!(loaded.may_contain(&other));
+        }
+        assert_eq!(
+            serde_json::to_string(&loaded).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+    }
+
+    check_roundtrip(&counter);
+    check_roundtrip(&Hybrid::<PlusPlus<P, B, R, H>, CH>::default());
+}
+
+/// Checks that malformed hybrid states are rejected.
+fn test_hybrid_serde_errors<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<CH>,
+    H: HasherType,
+    CH: CompositeHash<P, B>,
+>() {
+    let mut counter = Hybrid::<PlusPlus<P, B, R, H>, CH>::default();
+    // The smallest counters cannot store three composite hashes.
+    if counter.capacity() < 3 {
+        return;
+    }
+    counter.extend(iter_random_values::<u64>(3, None, Some(2_468)));
+    assert!(counter.is_hybrid());
+
+    let hashes: Vec<u64> = match serde_json::to_value(&counter).unwrap() {
+        serde_json::Value::Object(state) => state["Hashes"]["hashes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hash| hash.as_u64().unwrap())
+            .collect(),
+        _ => unreachable!("A hybrid counter is serialized as an object."),
+    };
+    assert_eq!(hashes.len(), 3);
+
+    let load = |number_of_hashes: usize, hashes: &[u64]| {
+        serde_json::from_value::<Hybrid<PlusPlus<P, B, R, H>, CH>>(serde_json::json!({
+            "Hashes": {"number_of_hashes": number_of_hashes, "hashes": hashes}
+        }))
+    };
+
+    assert!(load(3, &hashes).is_ok());
+    // The number of hashes must match the provided hashes.
+    assert!(load(2, &hashes).is_err());
+    // The hashes must be sorted and unique.
+    assert!(load(3, &[hashes[1], hashes[0], hashes[2]]).is_err());
+    assert!(load(3, &[hashes[0], hashes[0], hashes[2]]).is_err());
+    // The hashes must fit in the composite hash.
+    if CH::NUMBER_OF_BITS < 64 {
+        assert!(load(
+            3,
+            &[hashes[0], hashes[1], hashes[2] | (1 << CH::NUMBER_OF_BITS)]
+        )
+        .is_err());
+    }
+    // The hashes must encode a non-zero register.
+    assert!(load(1, &[hashes[0] & !(B::MASK << CH::OFFSET)]).is_err());
+}
+
+#[test_estimator]
+fn test_hybrid_serde<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>
+        + VariableWords<u24>
+        + VariableWords<u32>
+        + VariableWords<u40>
+        + VariableWords<u48>
+        + VariableWords<u56>
+        + VariableWords<u64>,
+    H: HasherType,
+>()
+where
+    u24: CompositeHash<P, B>,
+{
+    test_hybrid_serde_for_word::<P, B, R, H, u24>();
+    test_hybrid_serde_for_word::<P, B, R, H, u32>();
+    test_hybrid_serde_for_word::<P, B, R, H, u40>();
+    test_hybrid_serde_for_word::<P, B, R, H, u48>();
+    test_hybrid_serde_for_word::<P, B, R, H, u56>();
+    test_hybrid_serde_for_word::<P, B, R, H, u64>();
+
+    test_hybrid_serde_errors::<P, B, R, H, u24>();
+    test_hybrid_serde_errors::<P, B, R, H, u64>();
+}
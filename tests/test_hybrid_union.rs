@@ -0,0 +1,142 @@
+//! Test suite for the union of hybrid counters.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use core::hash::BuildHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use std::collections::HashSet;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Returns the set of the composite hashes of the provided values.
+///
+/// # Implementative details
+/// Composite hashes of few bits may collide for distinct values, so the cardinality of a
+/// counter in hybrid mode is estimated from the number of distinct composite hashes.
+fn composite_hashes<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>(
+    values: &[u64],
+) -> HashSet<u32>
+where
+    u32: CompositeHash<P, B>,
+{
+    let counter = PlusPlus::<P, B, R, H>::default();
+    values
+        .iter()
+        .map(|value| {
+            let hash = counter.hasher_builder().hash_one(value);
+            let (register, index) = PlusPlus::<P, B, R, H>::split_hash(hash);
+            <u32 as CompositeHash<P, B>>::encode(register, index, hash)
+        })
+        .collect()
+}
+
+#[test_estimator]
+fn test_hybrid_plusplus_union<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<u32>,
+    H: HasherType,
+>()
+where
+    u32: CompositeHash<P, B>,
+{
+    type Counter<P, B, R, H> = Hybrid<PlusPlus<P, B, R, H>, u32>;
+
+    let capacity = Counter::<P, B, R, H>::default().capacity();
+    let values: Vec<u64> =
+        iter_random_values::<u64>(4 * capacity as u64 + 100, None, Some(9_876)).collect();
+
+    // We merge counters with overlapping elements, growing them until their union no
+    // longer fits in hybrid mode.
+    for number_of_elements in [0, 1, capacity / 4, capacity / 2, capacity, 2 * capacity] {
+        let left_values = &values[..number_of_elements];
+        let right_values =
+            &values[number_of_elements / 2..number_of_elements / 2 + number_of_elements];
+
+        let mut left = Counter::<P, B, R, H>::default();
+        let mut right = Counter::<P, B, R, H>::default();
+        let mut expected = Counter::<P, B, R, H>::default();
+        left.extend(left_values);
+        right.extend(right_values);
+        expected.extend(left_values.iter().chain(right_values));
+
+        let union = left.clone() | right.clone();
+        let mut union_assign = right.clone();
+        union_assign |= left.clone();
+
+        let exact = composite_hashes::<P, B, R, H>(
+            &left_values
+                .iter()
+                .chain(right_values)
+                .copied()
+                .collect::<Vec<u64>>(),
+        );
+        if left.is_hybrid() && right.is_hybrid() && expected.is_hybrid() {
+            // While the union fits in the capacity, it remains exact up to the correction
+            // for the collisions of the composite hashes.
+            assert!(union.is_hybrid());
+            assert!(union_assign.is_hybrid());
+            assert_eq!(
+                union.estimate_cardinality(),
+                <u32 as CompositeHash<P, B>>::collision_corrected_cardinality(exact.len() as u32)
+            );
+            assert_eq!(
+                union.estimate_cardinality(),
+                left.estimate_union_cardinality(&right)
+            );
+        }
+
+        assert_eq!(union.is_hybrid(), expected.is_hybrid());
+        assert!(union == expected);
+        assert!(union_assign == expected);
+        assert!(left_values
+            .iter()
+            .chain(right_values)
+            .all(|value| union.may_contain(value)));
+        assert_eq!(
+            union.estimate_cardinality().to_bits(),
+            expected.estimate_cardinality().to_bits()
+        );
+
+        // Merging a counter in hybrid mode with a dense one folds the hashes into the registers.
+        let mut dense = Counter::<P, B, R, H>::default();
+        dense.extend(&values[..2 * capacity + 1]);
+        assert!(!dense.is_hybrid());
+        let mut expected = dense.clone();
+        expected.extend(left_values);
+
+        assert!(dense.clone() | left.clone() == expected);
+        assert!(left.clone() | dense.clone() == expected);
+        assert!(!(left | dense).is_hybrid());
+    }
+}
+
+#[cfg(feature = "mle")]
+#[test_estimator]
+fn test_hybrid_mle_union<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<u32>,
+    H: HasherType,
+>()
+where
+    u32: CompositeHash<P, B>,
+{
+    type Counter<P, B, R, H> = Hybrid<MLE<PlusPlus<P, B, R, H>>, u32>;
+
+    let capacity = Counter::<P, B, R, H>::default().capacity();
+    let mut left = Counter::<P, B, R, H>::default();
+    let mut right = Counter::<P, B, R, H>::default();
+    left.extend(0..capacity as u64 / 2);
+    right.extend(capacity as u64 / 4..capacity as u64 / 2 + capacity as u64 / 4);
+
+    let union = left.clone() | right.clone();
+    assert!(union.is_hybrid());
+    let values: Vec<u64> = (0..capacity as u64 / 2 + capacity as u64 / 4).collect();
+    let exact = composite_hashes::<P, B, R, H>(&values);
+    assert_eq!(
+        union.estimate_cardinality(),
+        <u32 as CompositeHash<P, B>>::collision_corrected_cardinality(exact.len() as u32)
+    );
+}
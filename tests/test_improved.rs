@@ -0,0 +1,52 @@
+//! Test suite for the improved raw estimator by Ertl.
+#![cfg(feature = "improved")]
+
+mod common;
+
+use common::mean_relative_error;
+use hyperloglog_rs::prelude::*;
+
+#[test]
+fn test_improved_empty() {
+    let counter =
+        Improved::<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>::default();
+    assert_eq!(counter.estimate_cardinality(), 0.0);
+    assert_eq!(counter.estimate_union_cardinality(&counter), 0.0);
+}
+
+#[test]
+#[cfg(feature = "plusplus")]
+/// Checks that the improved estimator is at least as accurate as the bias-corrected one, from
+/// the small range through the transition to the large range.
+fn test_improved_against_plusplus() {
+    let cardinalities: Vec<u64> = (0..40).map(|i| (10.0 * 1.25_f64.powi(i)) as u64).collect();
+    let improved = mean_relative_error::<
+        Improved<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>,
+    >(&cardinalities, 50, Estimator::estimate_cardinality);
+    let plusplus = mean_relative_error::<
+        PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>,
+    >(&cardinalities, 50, Estimator::estimate_cardinality);
+    assert!(
+        improved <= plusplus * 1.05,
+        "The improved estimator has error {improved}, while PlusPlus has error {plusplus}."
+    );
+}
+
+#[test]
+#[cfg(feature = "plusplus")]
+/// Checks that the estimator accounts for the saturated registers of counters with few bits,
+/// whose estimates are more accurate than the bias-corrected ones close to saturation, which
+/// for four bits and 64 registers starts at about one million elements.
+fn test_improved_saturation() {
+    let cardinalities: Vec<u64> = (0..8).map(|i| 1_u64 << (i + 14)).collect();
+    let improved = mean_relative_error::<
+        Improved<Precision6, Bits4, <Precision6 as ArrayRegister<Bits4>>::Array>,
+    >(&cardinalities, 10, Estimator::estimate_cardinality);
+    let plusplus = mean_relative_error::<
+        PlusPlus<Precision6, Bits4, <Precision6 as ArrayRegister<Bits4>>::Array>,
+    >(&cardinalities, 10, Estimator::estimate_cardinality);
+    assert!(
+        improved <= plusplus,
+        "The improved estimator has error {improved}, while PlusPlus has error {plusplus}."
+    );
+}
@@ -0,0 +1,75 @@
+//! Test suite for the insertion of pre-computed hashes.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use core::hash::Hash;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Returns the hashes of the provided values, computed with the provided hasher.
+fn hashes<H: HasherType>(values: &[u64]) -> Vec<u64> {
+    values
+        .iter()
+        .map(|value| {
+            let mut hasher = H::default();
+            value.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Checks that inserting the hashes is equivalent to inserting the values.
+fn check_insert_hash<
+    C: Estimator<f64> + ExtendableApproximatedSet<u64> + ExtendableHashes + Default + PartialEq,
+>(
+    values: &[u64],
+    hashes: &[u64],
+) {
+    let mut by_value = C::default();
+    let mut by_hash = C::default();
+    let mut by_batch = C::default();
+
+    for (value, hash) in values.iter().zip(hashes) {
+        assert_eq!(by_value.insert(value), by_hash.insert_hash(*hash));
+    }
+    for chunk in hashes.chunks(1_000) {
+        by_batch.extend_hashes(chunk);
+    }
+
+    assert!(by_value == by_hash);
+    assert!(by_value == by_batch);
+    assert_eq!(
+        by_value.estimate_cardinality().to_bits(),
+        by_hash.estimate_cardinality().to_bits()
+    );
+    // The batched insertion accumulates the harmonic sum in a different order.
+    let estimate = by_value.estimate_cardinality();
+    assert!((estimate - by_batch.estimate_cardinality()).abs() <= estimate * 1e-9);
+}
+
+#[test_estimator]
+fn test_plusplus_insert_hash<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B> + VariableWords<u32>,
+    H: HasherType,
+>()
+where
+    u32: CompositeHash<P, B>,
+{
+    let values: Vec<u64> = iter_random_values::<u64>(5_000, None, Some(1_470)).collect();
+    let hashes = hashes::<H>(&values);
+
+    check_insert_hash::<PlusPlus<P, B, R, H>>(&values, &hashes);
+    check_insert_hash::<Hybrid<PlusPlus<P, B, R, H>, u32>>(&values, &hashes);
+    #[cfg(feature = "mle")]
+    check_insert_hash::<MLE<PlusPlus<P, B, R, H>>>(&values, &hashes);
+
+    // The hybrid counter remains hybrid while the hashes fit.
+    let mut hybrid = Hybrid::<PlusPlus<P, B, R, H>, u32>::default();
+    let capacity = hybrid.capacity();
+    hybrid.extend_hashes(&hashes[..capacity.min(hashes.len()) / 2]);
+    assert!(hybrid.is_hybrid());
+}
@@ -0,0 +1,148 @@
+//! Test suite for the intersection estimates of three or more counters.
+#![cfg(any(feature = "plusplus", feature = "beta", feature = "improved"))]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Returns a pseudo-random generator of the region of the Venn diagram of `number_of_counters`
+/// counters each value is inserted into, as the bitmask of the counters.
+fn iter_regions(number_of_counters: usize, seed: u64) -> impl Iterator<Item = usize> {
+    let number_of_regions = (1_u64 << number_of_counters) - 1;
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    core::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        usize::try_from(state % number_of_regions).unwrap() + 1
+    })
+}
+
+/// Returns the counters of the Venn diagram and the exact cardinality of their intersection.
+fn build_counters<H: HyperLogLog + ExtendableApproximatedSet<u64> + Clone>(
+    number_of_counters: usize,
+    number_of_values: u64,
+    seed: u64,
+) -> (Vec<H>, f64) {
+    let mut counters = vec![H::default(); number_of_counters];
+    let mut intersection_cardinality = 0.0;
+    for (value, region) in (0..number_of_values).zip(iter_regions(number_of_counters, seed)) {
+        for (index, counter) in counters.iter_mut().enumerate() {
+            if region & (1 << index) != 0 {
+                counter.insert(&value);
+            }
+        }
+        if region == (1 << number_of_counters) - 1 {
+            intersection_cardinality += 1.0;
+        }
+    }
+    (counters, intersection_cardinality)
+}
+
+/// Checks the intersection estimates of the counters in the limit cases.
+fn check_intersection_of<
+    H: Correction + Estimator<f64> + ExtendableApproximatedSet<u64> + Clone,
+>(
+    maximum: usize,
+    tolerance: f64,
+) {
+    let (counters, _) = build_counters::<H>(3, 5_000, 7);
+    let [first, second, third] = [&counters[0], &counters[1], &counters[2]];
+
+    // The intersection of a counter with itself is the counter.
+    let estimate = first.estimate_cardinality();
+    let intersection = H::estimate_intersection_of(&[first, first, first]).unwrap();
+    assert!(
+        (intersection - estimate).abs() <= estimate * tolerance,
+        "Expected {estimate}, got {intersection}."
+    );
+    assert_eq!(
+        H::estimate_intersection_of(&[first]).unwrap().to_bits(),
+        H::estimate_union_of([first]).to_bits()
+    );
+
+    // The intersection is symmetric and not larger than the smallest counter.
+    let intersection = H::estimate_intersection_of(&[first, second, third]).unwrap();
+    let permuted = H::estimate_intersection_of(&[third, first, second]).unwrap();
+    assert!((intersection - permuted).abs() <= intersection.max(1.0) * tolerance);
+    assert!(intersection >= 0.0);
+    assert!(intersection <= estimate * (1.0 + tolerance));
+
+    // The intersection with an empty counter is empty, as the one of no counters.
+    let empty = H::default();
+    assert!(H::estimate_intersection_of(&[first, second, &empty]).unwrap() <= estimate * tolerance);
+    assert_eq!(
+        H::estimate_intersection_of(&[&empty, &empty, &empty]),
+        Ok(0.0)
+    );
+    assert_eq!(H::estimate_intersection_of(&[]), Ok(0.0));
+
+    let too_many = vec![first; maximum + 1];
+    assert_eq!(
+        H::estimate_intersection_of(&too_many),
+        Err(Error::TooManyCounters {
+            maximum,
+            found: maximum + 1
+        })
+    );
+}
+
+#[test_estimator]
+fn test_intersection_of_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_intersection_of::<PlusPlus<P, B, R, H>>(16, 1e-9);
+}
+
+#[test_estimator]
+fn test_intersection_of_beta<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_intersection_of::<LogLogBeta<P, B, R, H>>(16, 1e-9);
+}
+
+#[test_estimator]
+fn test_intersection_of_improved<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_intersection_of::<Improved<P, B, R, H>>(16, 1e-9);
+}
+
+#[test_estimator]
+#[cfg(feature = "mle")]
+fn test_intersection_of_plusplus_mle<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_intersection_of::<MLE<PlusPlus<P, B, R, H>>>(5, 0.1);
+}
+
+#[test]
+#[cfg(all(feature = "plusplus", feature = "mle"))]
+/// Checks that the joint MLE is more accurate than the inclusion–exclusion principle when the
+/// intersection is a small part of the union.
+fn test_intersection_of_accuracy() {
+    type Counter = PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>;
+    const NUMBER_OF_SEEDS: u64 = 20;
+
+    for number_of_counters in 2..=5 {
+        let mut inclusion_exclusion_error = 0.0;
+        let mut mle_error = 0.0;
+        for seed in 1..=NUMBER_OF_SEEDS {
+            let (counters, exact) =
+                build_counters::<MLE<Counter>>(number_of_counters, 20_000, seed);
+            let counters: Vec<&MLE<Counter>> = counters.iter().collect();
+            let mle = MLE::estimate_intersection_of(&counters).unwrap();
+            mle_error += ((mle - exact) / exact).powi(2);
+
+            let counters: Vec<Counter> = counters
+                .iter()
+                .map(|counter| *counter.registers())
+                .map(Counter::from_registers)
+                .collect();
+            let counters: Vec<&Counter> = counters.iter().collect();
+            let inclusion_exclusion = Counter::estimate_intersection_of(&counters).unwrap();
+            inclusion_exclusion_error += ((inclusion_exclusion - exact) / exact).powi(2);
+        }
+
+        let inclusion_exclusion_error = (inclusion_exclusion_error / NUMBER_OF_SEEDS as f64).sqrt();
+        let mle_error = (mle_error / NUMBER_OF_SEEDS as f64).sqrt();
+        assert!(
+            mle_error < inclusion_exclusion_error && mle_error < 0.3,
+            "With {number_of_counters} counters, the MLE has error {mle_error} while the inclusion–exclusion has error {inclusion_exclusion_error}."
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Test suite for the maximum likelihood estimation of the cardinality of a single counter.
+#![cfg(feature = "plusplus")]
+
+mod common;
+
+use common::mean_relative_error;
+use hyperloglog_rs::prelude::*;
+
+/// Returns the derivative of the log-likelihood of the histogram, multiplied by the
+/// cardinality, which is zero at the maximum likelihood estimate.
+fn scaled_likelihood_derivative<H: HyperLogLog>(counter: &H, cardinality: f64) -> f64 {
+    let histogram = counter.register_histogram();
+    let histogram = histogram.as_ref();
+    let saturated = usize::from(H::maximal_register_value());
+    let number_of_registers = histogram.iter().sum::<u32>() as f64;
+    let x = cardinality / number_of_registers;
+    let h = |u: f64| 1.0 - u / u.exp_m1();
+
+    let mut derivative = -x * histogram[0] as f64;
+    for (register_value, &count) in histogram.iter().enumerate().take(saturated).skip(1) {
+        let u = x / 2.0_f64.powi(register_value as i32);
+        derivative += count as f64 * (1.0 - h(u) - u);
+    }
+    derivative + histogram[saturated] as f64 * (1.0 - h(x / 2.0_f64.powi(saturated as i32 - 1)))
+}
+
+#[test]
+fn test_mle_cardinality_empty() {
+    let counter =
+        PlusPlus::<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>::default();
+    assert_eq!(counter.estimate_cardinality_mle::<2, LookupLn>(), 0.0);
+    assert_eq!(counter.estimate_cardinality_mle::<9, LookupLn>(), 0.0);
+}
+
+#[test]
+fn test_mle_cardinality_saturated() {
+    let mut counter =
+        PlusPlus::<Precision4, Bits1, <Precision4 as ArrayRegister<Bits1>>::Array>::default();
+    for value in 0..10_000_u64 {
+        counter.insert(&value);
+    }
+    assert_eq!(counter.register_histogram()[1], 16);
+    assert!(counter
+        .estimate_cardinality_mle::<2, LookupLn>()
+        .is_infinite());
+}
+
+#[test]
+/// Checks that the estimates maximize the likelihood of the histogram of the registers, for
+/// counters with enough bits and counters whose registers saturate.
+fn test_mle_cardinality_maximizes_likelihood() {
+    fn check<H: HyperLogLog + ExtendableApproximatedSet<u64>>() {
+        let number_of_registers = f64::from(1_u32 << <H::Precision as Precision>::EXPONENT);
+        let mut counter = H::default();
+        for (i, value) in iter_random_values::<u64>(200_000, None, Some(7)).enumerate() {
+            counter.insert(&value);
+            if i.is_power_of_two() {
+                let estimate = counter.estimate_cardinality_mle::<9, LookupLn>();
+                if estimate.is_infinite() {
+                    // All of the registers are saturated.
+                    break;
+                }
+                let derivative = scaled_likelihood_derivative(&counter, estimate);
+                assert!(
+                    derivative.abs() < 1e-6,
+                    "The derivative at the estimate {estimate} after {i} insertions is {derivative}."
+                );
+                // A looser error limit yields an estimate close to the same maximum.
+                let coarse = counter.estimate_cardinality_mle::<1, LookupLn>();
+                assert!((coarse - estimate).abs() <= estimate * 0.1 / number_of_registers.sqrt());
+            }
+        }
+    }
+    check::<PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>>();
+    check::<PlusPlus<Precision8, Bits4, <Precision8 as ArrayRegister<Bits4>>::Array>>();
+    check::<PlusPlus<Precision5, Bits3, <Precision5 as ArrayRegister<Bits3>>::Array>>();
+    check::<PlusPlus<Precision4, Bits1, <Precision4 as ArrayRegister<Bits1>>::Array>>();
+}
+
+#[test]
+/// Checks that the estimator is at least as accurate as the bias-corrected one, from the
+/// small range through the transition to the large range.
+fn test_mle_cardinality_against_plusplus() {
+    type Counter = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+    let cardinalities: Vec<u64> = (0..40).map(|i| (10.0 * 1.25_f64.powi(i)) as u64).collect();
+    let mle = mean_relative_error::<Counter>(&cardinalities, 50, |counter| {
+        counter.estimate_cardinality_mle::<2, LookupLn>()
+    });
+    let plusplus =
+        mean_relative_error::<Counter>(&cardinalities, 50, Estimator::estimate_cardinality);
+    assert!(
+        mle <= plusplus * 1.05,
+        "The MLE estimator has error {mle}, while PlusPlus has error {plusplus}."
+    );
+}
+
+#[test]
+/// Checks that the estimator accounts for the saturated registers of counters with few bits,
+/// whose estimates are more accurate than the bias-corrected ones close to saturation.
+fn test_mle_cardinality_saturation() {
+    type Counter = PlusPlus<Precision6, Bits4, <Precision6 as ArrayRegister<Bits4>>::Array>;
+    let cardinalities: Vec<u64> = (0..8).map(|i| 1_u64 << (i + 14)).collect();
+    let mle = mean_relative_error::<Counter>(&cardinalities, 10, |counter| {
+        counter.estimate_cardinality_mle::<2, LookupLn>()
+    });
+    let plusplus =
+        mean_relative_error::<Counter>(&cardinalities, 10, Estimator::estimate_cardinality);
+    assert!(
+        mle <= plusplus,
+        "The MLE estimator has error {mle}, while PlusPlus has error {plusplus}."
+    );
+}
+
+#[test]
+#[cfg(feature = "mle")]
+/// Checks that the cardinality estimate of the [`MLE`] counter is the maximum likelihood one,
+/// with the relative error limit selected by its `ERROR`.
+fn test_mle_counter_cardinality() {
+    type Counter = PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>;
+    let mut counter: MLE<Counter> = MLE::default();
+    let mut precise_counter: MLE<Counter, 6> = MLE::default();
+    for value in iter_random_values::<u64>(10_000, None, Some(3)) {
+        counter.insert(&value);
+        precise_counter.insert(&value);
+    }
+    assert_eq!(
+        counter.estimate_cardinality().to_bits(),
+        counter.estimate_cardinality_mle::<2, LookupLn>().to_bits()
+    );
+    assert_eq!(
+        precise_counter.estimate_cardinality().to_bits(),
+        counter.estimate_cardinality_mle::<6, LookupLn>().to_bits()
+    );
+}
+
+#[test]
+#[cfg(feature = "mle")]
+/// Checks that the [`MLE`] counter falls back to the estimate of the wrapped counter when all
+/// of its registers are saturated.
+fn test_mle_counter_cardinality_saturated() {
+    type Counter = PlusPlus<Precision4, Bits1, <Precision4 as ArrayRegister<Bits1>>::Array>;
+    let mut counter: MLE<Counter> = MLE::default();
+    let mut inner = Counter::default();
+    for value in 0..10_000_u64 {
+        counter.insert(&value);
+        inner.insert(&value);
+    }
+    assert!(counter
+        .estimate_cardinality_mle::<2, LookupLn>()
+        .is_infinite());
+    assert_eq!(
+        counter.estimate_cardinality().to_bits(),
+        inner.estimate_cardinality().to_bits()
+    );
+}
+
+#[test]
+#[cfg(all(feature = "mle", feature = "std"))]
+/// Checks that the logarithm of the [`MLE`] counter is selected with its type parameter, and
+/// that the estimates with the logarithm of the standard library are as accurate.
+fn test_mle_counter_logarithm() {
+    type Counter = PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array>;
+    let mut lookup: MLE<Counter> = MLE::default();
+    let mut std: MLE<Counter, 2, StdLn> = MLE::default();
+    for value in iter_random_values::<u64>(10_000, None, Some(5)) {
+        lookup.insert(&value);
+        std.insert(&value);
+    }
+    assert_eq!(
+        lookup.estimate_cardinality().to_bits(),
+        lookup.estimate_cardinality_mle::<2, LookupLn>().to_bits()
+    );
+    assert_eq!(
+        std.estimate_cardinality().to_bits(),
+        std.estimate_cardinality_mle::<2, StdLn>().to_bits()
+    );
+    let relative_difference =
+        (lookup.estimate_cardinality() - std.estimate_cardinality()).abs() / 10_000.0;
+    assert!(relative_difference < 1e-3, "{relative_difference}");
+}
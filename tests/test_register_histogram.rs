@@ -0,0 +1,82 @@
+//! Test suite for the histograms of the values of the registers.
+#![cfg(any(feature = "plusplus", feature = "beta", feature = "improved"))]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Returns the histogram of the values of the registers of the counter, computed from scratch.
+fn expected_histogram<H: HyperLogLog>(counter: &H) -> <H::Bits as Bits>::Histogram {
+    let mut histogram = <H::Bits as Bits>::Histogram::ZERO;
+    for register in counter.registers().iter_registers() {
+        histogram.as_mut()[usize::from(register)] += 1;
+    }
+    histogram
+}
+
+/// Checks that the histogram of the counter is kept consistent with its registers.
+fn check_register_histogram<H: HyperLogLog + ExtendableApproximatedSet<u64> + Clone>() {
+    let number_of_registers =
+        u32::try_from(1_usize << <H::Precision as Precision>::EXPONENT).unwrap();
+    let empty = H::default();
+    let mut expected = <H::Bits as Bits>::Histogram::ZERO;
+    expected.as_mut()[0] = number_of_registers;
+    assert_eq!(empty.register_histogram(), expected);
+
+    for (number_of_values, seed) in [(10, 1), (1_000, 2), (50_000, 3)] {
+        let mut left = H::default();
+        let mut right = H::default();
+        for (i, value) in iter_random_values::<u64>(number_of_values, None, Some(seed)).enumerate()
+        {
+            if i % 3 == 0 {
+                right.insert(&value);
+            } else {
+                left.insert(&value);
+            }
+        }
+        let left_histogram = left.register_histogram();
+        let right_histogram = right.register_histogram();
+        assert_eq!(left_histogram, expected_histogram(&left));
+        assert_eq!(right_histogram, expected_histogram(&right));
+        assert_eq!(
+            left_histogram.as_ref().iter().sum::<u32>(),
+            number_of_registers
+        );
+
+        let joint = left.registers().joint_register_histogram(right.registers());
+        assert_eq!(joint.left(), left_histogram);
+        assert_eq!(joint.right(), right_histogram);
+
+        let mut merged = left.clone();
+        merged |= right.clone();
+        assert_eq!(merged.register_histogram(), expected_histogram(&merged));
+        assert_eq!(merged.register_histogram(), joint.union());
+
+        let reconstructed = H::from_registers(merged.registers().clone());
+        assert_eq!(
+            reconstructed.register_histogram(),
+            merged.register_histogram()
+        );
+
+        let mut cleared = merged.clone();
+        cleared.clear();
+        assert_eq!(cleared.register_histogram(), expected);
+    }
+}
+
+#[test_estimator]
+fn test_register_histogram_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_register_histogram::<PlusPlus<P, B, R, H>>();
+}
+
+#[test_estimator]
+fn test_register_histogram_beta<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_register_histogram::<LogLogBeta<P, B, R, H>>();
+}
+
+#[test_estimator]
+fn test_register_histogram_improved<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_register_histogram::<Improved<P, B, R, H>>();
+}
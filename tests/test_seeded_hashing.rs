@@ -0,0 +1,301 @@
+//! Test suite for the counters hashing their elements with seeded hashers.
+#![cfg(feature = "plusplus")]
+
+mod common;
+
+use common::{serialize, AHasher, WyHash};
+use core::hash::{BuildHasher, Hash, Hasher};
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+
+/// The counter with seeded hashers employed by the tests of the wrappers.
+type Counter = PlusPlus<
+    Precision8,
+    Bits6,
+    <Precision8 as ArrayRegister<Bits6>>::Array,
+    twox_hash::XxHash64,
+    SeededBuilder<twox_hash::XxHash64>,
+>;
+/// The counter with the same parameters and the default, unseeded, hashers.
+type Unseeded = PlusPlus<Precision8, Bits6, <Precision8 as ArrayRegister<Bits6>>::Array>;
+
+#[test_estimator]
+fn test_seeded_hashing_plusplus<
+    P: Precision,
+    B: Bits,
+    R: Registers<P, B>,
+    H: SeedableHasher + HasherId,
+>() {
+    type Seeded<P, B, R, H> = PlusPlus<P, B, R, H, SeededBuilder<H>>;
+
+    let values: Vec<u64> = iter_random_values::<u64>(2_000, None, Some(31)).collect();
+    let mut first = Seeded::<P, B, R, H>::with_seed(1);
+    let mut same = Seeded::<P, B, R, H>::with_seed(1);
+    let mut second = Seeded::<P, B, R, H>::with_seed(2);
+    let mut unseeded = PlusPlus::<P, B, R, H>::default();
+    for value in &values {
+        first.insert(value);
+        same.insert(value);
+        second.insert(value);
+        unseeded.insert(value);
+    }
+
+    // The same seed yields the same counter, while counters with different seeds differ.
+    assert!(first == same);
+    assert!(first != second);
+    assert!(first.registers() != second.registers());
+    assert!(first.registers() != unseeded.registers());
+    assert!(values.iter().all(|value| first.may_contain(value)));
+
+    // The counters built from the hashes of the builder are the same.
+    let mut from_hashes = Seeded::<P, B, R, H>::with_seed(1);
+    for value in &values {
+        from_hashes.insert_hash(first.hasher_builder().hash_one(value));
+    }
+    assert!(from_hashes == first);
+
+    // The merges and the counters built from the registers keep the seed.
+    let mut union = Seeded::<P, B, R, H>::with_seed(1);
+    union.union_many([&first, &same]);
+    assert!(union == first);
+    assert_eq!(union.hasher_builder().seed(), Some(1));
+    assert!(first.clone() | same.clone() == first);
+
+    // The seed is recorded in the binary format.
+    let bytes = serialize(&second);
+    let loaded = Seeded::<P, B, R, H>::from_bytes(&bytes).unwrap();
+    assert!(loaded == second);
+    assert_eq!(loaded.hasher_builder().seed(), Some(2));
+    assert_eq!(serialize(&loaded), bytes);
+
+    // Counters with seeded and unseeded hashers cannot be loaded as one another.
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::from_bytes(&bytes).err(),
+        Some(Error::SeedMismatch {
+            expected_seeded: false
+        })
+    );
+    assert_eq!(
+        Seeded::<P, B, R, H>::from_bytes(&serialize(&unseeded)).err(),
+        Some(Error::SeedMismatch {
+            expected_seeded: true
+        })
+    );
+}
+
+#[test]
+/// Checks that the default builder is zero-sized and hashes the elements as the hasher does.
+fn test_unseeded_builder() {
+    assert_eq!(
+        core::mem::size_of::<UnseededBuilder<twox_hash::XxHash64>>(),
+        0
+    );
+    assert_eq!(
+        core::mem::size_of::<Unseeded>(),
+        core::mem::size_of::<
+            PlusPlus<
+                Precision8,
+                Bits6,
+                <Precision8 as ArrayRegister<Bits6>>::Array,
+                twox_hash::XxHash64,
+                UnseededBuilder<twox_hash::XxHash64>,
+            >,
+        >()
+    );
+
+    let mut counter = Unseeded::default();
+    let mut from_hashes = Unseeded::default();
+    for value in 0..1_000_u64 {
+        counter.insert(&value);
+        let mut hasher = twox_hash::XxHash64::default();
+        value.hash(&mut hasher);
+        from_hashes.insert_hash(hasher.finish());
+    }
+    assert_eq!(counter, from_hashes);
+    assert_eq!(counter.hasher_builder().seed(), None);
+    assert_eq!(
+        UnseededBuilder::<twox_hash::XxHash64>::from_seed(Some(1)),
+        None
+    );
+}
+
+#[test]
+/// Checks that the seeded builder keys the hashers with their seeded constructor.
+fn test_seeded_builder() {
+    let builder = SeededBuilder::<twox_hash::XxHash64>::new(42);
+    for value in 0..1_000_u64 {
+        let mut hasher = twox_hash::XxHash64::with_seed(42);
+        value.hash(&mut hasher);
+        assert_eq!(builder.hash_one(value), hasher.finish());
+    }
+    assert_eq!(
+        SeededBuilder::<twox_hash::Xxh3Hash64>::new(42)
+            .build_hasher()
+            .finish(),
+        twox_hash::Xxh3Hash64::with_seed(42).finish()
+    );
+}
+
+#[test]
+/// Checks that the seed is kept by the atomic and hybrid counters.
+fn test_seeded_wrappers() {
+    let mut expected = Counter::with_seed(7);
+    expected.extend(0..5_000_u64);
+
+    #[cfg(target_has_atomic = "64")]
+    {
+        let atomic = AtomicPlusPlus::<
+            Precision8,
+            Bits6,
+            <Precision8 as ArrayRegister<Bits6>>::Atomic,
+            twox_hash::XxHash64,
+            SeededBuilder<twox_hash::XxHash64>,
+        >::with_hasher_builder(SeededBuilder::new(7));
+        for value in 0..5_000_u64 {
+            atomic.insert(&value);
+        }
+        assert_eq!(atomic.snapshot(), expected);
+    }
+
+    let mut hybrid: Hybrid<Counter> = Hybrid::with_hasher_builder(SeededBuilder::new(7));
+    for value in 0..10_u64 {
+        hybrid.insert(&value);
+    }
+    assert!(hybrid.is_hybrid());
+    assert!((0..10_u64).all(|value| hybrid.may_contain(&value)));
+    assert_eq!(hybrid.estimate_cardinality(), 10.0);
+
+    let bytes = serialize(&hybrid);
+    let loaded = Hybrid::<Counter>::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded, hybrid);
+
+    hybrid.extend(10..5_000_u64);
+    assert!(!hybrid.is_hybrid());
+    let mut dense = Counter::with_seed(7);
+    dense.extend(0..5_000_u64);
+    assert_eq!(hybrid.estimate_cardinality(), dense.estimate_cardinality());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+/// Checks that the sparse counters hash their elements with the seeded hashers, and hand
+/// them to the registers once they switch to them.
+fn test_seeded_sparse() {
+    let mut sparse: Sparse<Counter> = Sparse::with_hasher_builder(SeededBuilder::new(7));
+    let mut unseeded: Sparse<Unseeded> = Sparse::default();
+    for value in 0..10_u64 {
+        sparse.insert(&value);
+        unseeded.insert(&value);
+    }
+    assert!(sparse.is_sparse());
+    assert!((0..10_u64).all(|value| sparse.may_contain(&value)));
+    assert_eq!(sparse.estimate_cardinality(), 10.0);
+
+    // The hashes inserted in sparse mode are the seeded ones, as the registers they are
+    // moved to are the ones of a counter with the same seed.
+    for value in 10..5_000_u64 {
+        sparse.insert(&value);
+        unseeded.insert(&value);
+    }
+    assert!(!sparse.is_sparse());
+    let mut expected = Counter::with_seed(7);
+    expected.extend(0..5_000_u64);
+    assert_eq!(sparse.dense(), Some(&expected));
+    assert!(unseeded.dense().unwrap().registers() != expected.registers());
+
+    sparse.clear();
+    assert!(sparse.is_sparse());
+    assert_eq!(sparse.hasher_builder().seed(), Some(7));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+/// Checks that the sliding-window counters hash their elements with the seeded hashers.
+fn test_seeded_sliding_window() {
+    let mut window = SlidingWindow::<Counter>::with_hasher_builder(SeededBuilder::new(7));
+    let mut unseeded = SlidingWindow::<Unseeded>::default();
+    for value in 0..5_000_u64 {
+        window.insert_at(&value, value);
+        unseeded.insert_at(&value, value);
+    }
+
+    let mut expected = Counter::with_seed(7);
+    expected.extend(2_500..5_000_u64);
+    let since = window.counter_since(2_500);
+    assert_eq!(since, expected);
+    assert_eq!(since.hasher_builder().seed(), Some(7));
+    assert!(unseeded.counter_since(2_500).registers() != expected.registers());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+/// Checks that the runtime-configured counters hash their elements with the seeded hashers.
+fn test_seeded_dynamic() {
+    let mut dynamic =
+        DynamicPlusPlus::<twox_hash::XxHash64, SeededBuilder<_>>::with_hasher_builder(
+            8,
+            6,
+            SeededBuilder::new(7),
+        )
+        .unwrap();
+    let mut unseeded = DynamicPlusPlus::<twox_hash::XxHash64>::new(8, 6).unwrap();
+    let mut expected = Counter::with_seed(7);
+    for value in 0..5_000_u64 {
+        dynamic.insert(&value);
+        unseeded.insert(&value);
+        expected.insert(&value);
+    }
+
+    assert_eq!(
+        dynamic.estimate_cardinality(),
+        expected.estimate_cardinality()
+    );
+    assert_eq!(dynamic, DynamicPlusPlus::from(expected));
+    assert!(unseeded.registers() != dynamic.registers());
+
+    // The counters derived from the dynamic counter keep the seed.
+    let folded = dynamic.fold_to(6).unwrap();
+    assert_eq!(folded.hasher_builder().seed(), Some(7));
+    assert_eq!(
+        dynamic.convert_bits(5).unwrap().hasher_builder().seed(),
+        Some(7)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+/// Checks that the seed is recorded by the serialization with serde.
+fn test_seeded_serde() {
+    type SmallCounter = PlusPlus<
+        Precision6,
+        Bits5,
+        <Precision6 as ArrayRegister<Bits5>>::Array,
+        twox_hash::XxHash64,
+        SeededBuilder<twox_hash::XxHash64>,
+    >;
+
+    let mut counter = SmallCounter::with_seed(11);
+    counter.extend(0..100_u64);
+    let json = serde_json::to_string(&counter).unwrap();
+    assert!(json.starts_with("[11,["));
+    let loaded: SmallCounter = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, counter);
+    assert_eq!(loaded.hasher_builder().seed(), Some(11));
+
+    let mut hybrid: Hybrid<SmallCounter> = Hybrid::with_hasher_builder(SeededBuilder::new(11));
+    hybrid.extend(0..10_u64);
+    let json = serde_json::to_string(&hybrid).unwrap();
+    let loaded: Hybrid<SmallCounter> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, hybrid);
+    assert!((0..10_u64).all(|value| loaded.may_contain(&value)));
+
+    // The counters with unseeded hashers are serialized as plain sequences of registers,
+    // which cannot be loaded as counters with seeded hashers.
+    let mut unseeded =
+        PlusPlus::<Precision6, Bits5, <Precision6 as ArrayRegister<Bits5>>::Array>::default();
+    unseeded.extend(0..100_u64);
+    let json = serde_json::to_string(&unseeded).unwrap();
+    assert!(!json.starts_with("[["));
+    assert!(serde_json::from_str::<SmallCounter>(&json).is_err());
+}
@@ -0,0 +1,63 @@
+//! Test suite for the sliding-window counters.
+#![cfg(all(feature = "alloc", feature = "plusplus"))]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// The starting timestamps of the windows checked in the tests.
+const WINDOWS: [u64; 6] = [0, 1, 250, 500, 999, 1_000];
+
+#[test_estimator]
+fn test_sliding_window_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    let values: Vec<u64> = iter_random_values::<u64>(5_000, None, Some(9_182)).collect();
+    // The timestamps are not sorted, as shards may receive the elements out of order.
+    let timestamps: Vec<u64> = iter_random_values::<u64>(5_000, Some(1_000), Some(2_837)).collect();
+
+    let mut window = SlidingWindow::<PlusPlus<P, B, R, H>>::default();
+    let mut shards = [
+        SlidingWindow::<PlusPlus<P, B, R, H>>::default(),
+        SlidingWindow::<PlusPlus<P, B, R, H>>::default(),
+        SlidingWindow::<PlusPlus<P, B, R, H>>::default(),
+    ];
+
+    for (i, (value, timestamp)) in values.iter().zip(timestamps.iter()).enumerate() {
+        window.insert_at(value, *timestamp);
+        shards[i % 3].insert_at(value, *timestamp);
+    }
+
+    let [first, second, third] = shards;
+    let merged = first | second | third;
+    assert_eq!(merged, window);
+
+    for start in WINDOWS {
+        let mut expected = PlusPlus::<P, B, R, H>::default();
+        for (value, timestamp) in values.iter().zip(timestamps.iter()) {
+            if *timestamp >= start {
+                expected.insert(value);
+            }
+        }
+
+        assert!(window.counter_since(start) == expected);
+        assert_eq!(
+            window.estimate_cardinality_since(start).to_bits(),
+            expected.estimate_cardinality().to_bits()
+        );
+    }
+
+    // Expiring the old pairs does not affect the later windows.
+    let number_of_possible_maxima = window.number_of_possible_maxima();
+    let mut expired = window.clone();
+    expired.expire_before(500);
+    assert!(expired.number_of_possible_maxima() <= number_of_possible_maxima);
+    for start in WINDOWS.into_iter().filter(|start| *start >= 500) {
+        assert!(expired.counter_since(start) == window.counter_since(start));
+    }
+    assert!(expired.counter_since(0) == window.counter_since(500));
+
+    window.clear();
+    assert!(window.counter_since(0) == PlusPlus::default());
+    assert_eq!(window.number_of_possible_maxima(), 0);
+}
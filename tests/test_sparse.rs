@@ -0,0 +1,193 @@
+//! Test suite for the sparse representation of the counters.
+#![cfg(all(feature = "alloc", feature = "plusplus"))]
+
+use ahash::AHasher;
+use core::hash::BuildHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use std::collections::HashSet;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Returns the set of the composite hashes of the provided values.
+///
+/// # Implementative details
+/// Composite hashes of few bits may collide for distinct values, so the cardinality of a
+/// sparse counter is estimated from the number of distinct composite hashes.
+fn composite_hashes<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>(
+    values: &[u64],
+) -> HashSet<u32>
+where
+    u32: CompositeHash<P, B>,
+{
+    let counter = PlusPlus::<P, B, R, H>::default();
+    values
+        .iter()
+        .map(|value| {
+            let hash = counter.hasher_builder().hash_one(value);
+            let (register, index) = PlusPlus::<P, B, R, H>::split_hash(hash);
+            <u32 as CompositeHash<P, B>>::encode(register, index, hash)
+        })
+        .collect()
+}
+
+#[test_estimator]
+fn test_sparse_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    let mut sparse = Sparse::<PlusPlus<P, B, R, H>, u64>::default();
+    let mut other_sparse = Sparse::<PlusPlus<P, B, R, H>, u64>::default();
+    let mut dense = PlusPlus::<P, B, R, H>::default();
+    let mut other_dense = PlusPlus::<P, B, R, H>::default();
+    let mut exact = HashSet::new();
+    let mut other_exact = HashSet::new();
+    let mut next_check = 1;
+
+    assert!(sparse.is_empty());
+    assert!(sparse.is_sparse());
+    assert_eq!(sparse.estimate_cardinality().to_bits(), 0.0_f64.to_bits());
+
+    for (inserted, value) in
+        (1_u64..).zip(iter_random_values::<u64>(10_000, Some(5_000), Some(7_531)))
+    {
+        let was_sparse = sparse.is_sparse();
+        let modified = sparse.insert(&value);
+        dense.insert(&value);
+        let new = exact.insert(value);
+        if was_sparse {
+            // While sparse, the insertion reports whether the value was not yet present.
+            assert_eq!(modified, new);
+        }
+        if value < 2_500 {
+            other_sparse.insert(&value.wrapping_mul(31));
+            other_dense.insert(&value.wrapping_mul(31));
+            other_exact.insert(value.wrapping_mul(31));
+        }
+        assert!(sparse.may_contain(&value));
+        assert!(!sparse.is_empty());
+
+        if inserted < next_check {
+            continue;
+        }
+        next_check *= 2;
+
+        if sparse.is_sparse() {
+            // While sparse, the estimates are exact.
+            assert_eq!(sparse.estimate_cardinality(), exact.len() as f64);
+            assert!(sparse.dense().is_none());
+        } else {
+            // Once dense, the registers are the same of the dense counter.
+            assert!(sparse.dense() == Some(&dense));
+            assert_eq!(
+                sparse.estimate_cardinality().to_bits(),
+                dense.estimate_cardinality().to_bits()
+            );
+        }
+
+        if sparse.is_sparse() && other_sparse.is_sparse() {
+            assert_eq!(
+                sparse.estimate_union_cardinality(&other_sparse),
+                exact.union(&other_exact).count() as f64
+            );
+        }
+        let union = sparse.estimate_union_cardinality(&other_sparse);
+        assert!(
+            union
+                >= sparse
+                    .estimate_cardinality()
+                    .max(other_sparse.estimate_cardinality())
+        );
+        assert_eq!(
+            union.to_bits(),
+            other_sparse.estimate_union_cardinality(&sparse).to_bits()
+        );
+
+        // The merged counters remain sparse while the union of their hashes fits, and
+        // otherwise have the registers of the merged dense counters.
+        let merged = sparse.clone() | other_sparse.clone();
+        assert!(merged == other_sparse.clone() | sparse.clone());
+        if merged.is_sparse() {
+            assert_eq!(
+                merged.estimate_cardinality(),
+                exact.union(&other_exact).count() as f64
+            );
+        } else {
+            assert!(merged.dense() == Some(&(dense.clone() | other_dense.clone())));
+        }
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&sparse).unwrap();
+            let loaded: Sparse<PlusPlus<P, B, R, H>, u64> = serde_json::from_str(&json).unwrap();
+            assert!(loaded == sparse);
+            assert_eq!(loaded.is_sparse(), sparse.is_sparse());
+            assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+        }
+    }
+
+    sparse.clear();
+    assert!(sparse.is_empty());
+    assert!(sparse.is_sparse());
+}
+
+#[test]
+#[cfg(feature = "precision_10")]
+fn test_sparse_is_exact_longer_than_hybrid() {
+    type Counter =
+        PlusPlus<Precision10, Bits6, <Precision10 as ArrayRegister<Bits6>>::Array, XxHash>;
+    let mut sparse = Sparse::<Counter, u24>::default();
+    let mut hybrid = Hybrid::<Counter, u24>::default();
+    let mut exact = HashSet::new();
+
+    for value in iter_random_values::<u64>(2_000, None, Some(1_357)) {
+        sparse.insert(&value);
+        hybrid.insert(&value);
+        exact.insert(value);
+        if !hybrid.is_hybrid() {
+            break;
+        }
+    }
+
+    // When the hybrid counter switches to the registers, the sparse one is still exact.
+    assert!(sparse.is_sparse());
+    assert_eq!(sparse.estimate_cardinality(), exact.len() as f64);
+}
+
+#[test_estimator]
+fn test_sparse_collisions<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>()
+where
+    u32: CompositeHash<P, B>,
+{
+    let mut left = Sparse::<PlusPlus<P, B, R, H>, u32>::default();
+    let mut right = Sparse::<PlusPlus<P, B, R, H>, u32>::default();
+    let values: Vec<u64> = iter_random_values::<u64>(10_000, None, Some(2_468)).collect();
+
+    for (inserted, value) in values.iter().enumerate() {
+        left.insert(value);
+        right.insert(&value.wrapping_mul(17));
+        if !left.is_sparse() || !right.is_sparse() {
+            break;
+        }
+        if inserted % 97 != 0 {
+            continue;
+        }
+
+        // While sparse, the counters count the distinct composite hashes, corrected for
+        // their expected collisions.
+        let left_values = &values[..=inserted];
+        let right_values: Vec<u64> = left_values
+            .iter()
+            .map(|value| value.wrapping_mul(17))
+            .collect();
+        let left_hashes = composite_hashes::<P, B, R, H>(left_values);
+        let right_hashes = composite_hashes::<P, B, R, H>(&right_values);
+        assert_eq!(
+            left.estimate_cardinality(),
+            <u32 as CompositeHash<P, B>>::collision_corrected_cardinality(left_hashes.len() as u32)
+        );
+        assert_eq!(
+            left.estimate_union_cardinality(&right),
+            <u32 as CompositeHash<P, B>>::collision_corrected_cardinality(
+                left_hashes.union(&right_hashes).count() as u32
+            )
+        );
+    }
+}
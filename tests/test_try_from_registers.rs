@@ -0,0 +1,78 @@
+//! Test suite for the fallible construction of counters from their registers.
+#![cfg(feature = "plusplus")]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+#[test_estimator]
+fn test_plusplus_try_from_registers<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    let mut counter = PlusPlus::<P, B, R, H>::default();
+    counter.extend(iter_random_values::<u64>(1_000, None, Some(6_543)));
+    let maximum = PlusPlus::<P, B, R, H>::maximal_register_value();
+    let values: Vec<u8> = counter.registers().iter_registers().collect();
+    assert!(values.iter().all(|value| *value <= maximum));
+
+    let loaded = PlusPlus::<P, B, R, H>::try_from(values.as_slice()).unwrap();
+    assert!(loaded == counter);
+    assert_eq!(
+        loaded.estimate_cardinality().to_bits(),
+        counter.estimate_cardinality().to_bits()
+    );
+    assert!(
+        PlusPlus::<P, B, R, H>::try_from_registers(counter.registers().clone()).unwrap() == counter
+    );
+
+    // The number of values must match the number of registers.
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::try_from(&values[1..]).err(),
+        Some(Error::UnexpectedNumberOfRegisters {
+            expected: values.len(),
+            found: values.len() - 1
+        })
+    );
+    let mut longer = values.clone();
+    longer.push(0);
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::try_from(longer.as_slice()).err(),
+        Some(Error::UnexpectedNumberOfRegisters {
+            expected: values.len(),
+            found: values.len() + 1
+        })
+    );
+
+    // The values must fit in the registers.
+    let mut invalid = values.clone();
+    let index = invalid.len() / 3;
+    invalid[index] = maximum + 1;
+    assert_eq!(
+        PlusPlus::<P, B, R, H>::try_from(invalid.as_slice()).err(),
+        Some(Error::InvalidRegisterValue {
+            index,
+            value: maximum + 1,
+            maximum
+        })
+    );
+
+    #[cfg(feature = "mle")]
+    assert!(
+        MLE::<PlusPlus<P, B, R, H>>::try_from(values.as_slice()).unwrap()
+            == MLE::from(counter.clone())
+    );
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&counter).unwrap();
+        let loaded: PlusPlus<P, B, R, H> = serde_json::from_str(&json).unwrap();
+        assert!(loaded == counter);
+
+        let short = serde_json::to_string(&values[1..]).unwrap();
+        assert!(serde_json::from_str::<PlusPlus<P, B, R, H>>(&short).is_err());
+        let long = serde_json::to_string(&longer).unwrap();
+        assert!(serde_json::from_str::<PlusPlus<P, B, R, H>>(&long).is_err());
+        let invalid = serde_json::to_string(&invalid).unwrap();
+        assert!(serde_json::from_str::<PlusPlus<P, B, R, H>>(&invalid).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Test suite for the union of many counters at once.
+#![cfg(any(
+    feature = "plusplus",
+    feature = "beta",
+    feature = "mle",
+    feature = "improved"
+))]
+
+use ahash::AHasher;
+use hyperloglog_derive::test_estimator;
+use hyperloglog_rs::prelude::*;
+use twox_hash::XxHash;
+use wyhash::WyHash;
+
+/// Checks that the union of many counters matches the one obtained by merging them one at a time.
+fn check_union_many<H: Correction + Estimator<f64> + ExtendableApproximatedSet<u64> + Clone>() {
+    for (number_of_counters, number_of_values, seed) in
+        [(1, 100, 1), (7, 2_000, 2), (150, 20_000, 3)]
+    {
+        let values: Vec<u64> =
+            iter_random_values::<u64>(number_of_values, None, Some(seed)).collect();
+        let mut counters = vec![H::default(); number_of_counters];
+        let mut expected = H::default();
+        for (i, value) in values.iter().enumerate() {
+            counters[i % number_of_counters].insert(value);
+            expected.insert(value);
+        }
+
+        let mut merged = H::default();
+        for counter in &counters {
+            merged |= counter.clone();
+        }
+        assert!(merged == expected);
+
+        let mut union = H::default();
+        union.union_many(&counters);
+        assert!(union == expected);
+
+        // The harmonic sum of the expected counter is accumulated in a different order.
+        let estimate = expected.estimate_cardinality();
+        let union_estimate = H::estimate_union_of(&counters);
+        assert_eq!(
+            union_estimate.to_bits(),
+            union.estimate_cardinality().to_bits()
+        );
+        assert!((union_estimate - estimate).abs() <= estimate.abs() * 1e-9);
+
+        // Merging into a non-empty counter keeps its registers.
+        let mut partial = counters[0].clone();
+        partial.union_many(counters.iter().skip(1));
+        assert!(partial == expected);
+    }
+
+    // The union of no counters is the empty counter.
+    let mut union = H::default();
+    union.union_many([]);
+    assert!(union == H::default());
+    assert_eq!(
+        H::estimate_union_of([]).to_bits(),
+        H::default().estimate_cardinality().to_bits()
+    );
+}
+
+#[test_estimator]
+fn test_union_many_plusplus<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_union_many::<PlusPlus<P, B, R, H>>();
+}
+
+#[test_estimator]
+fn test_union_many_beta<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_union_many::<LogLogBeta<P, B, R, H>>();
+}
+
+#[test_estimator]
+fn test_union_many_improved<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_union_many::<Improved<P, B, R, H>>();
+}
+
+#[test_estimator]
+#[cfg(feature = "mle")]
+fn test_union_many_plusplus_mle<P: Precision, B: Bits, R: Registers<P, B>, H: HasherType>() {
+    check_union_many::<MLE<PlusPlus<P, B, R, H>>>();
+}